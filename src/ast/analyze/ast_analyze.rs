@@ -1,9 +1,28 @@
-use crate::ast::ast_struct::{Assign, If, Name, Print, PyCtx, PyRootNode, Type, While};
+use crate::ast::ast_struct::{Alias, Arguments, Assign, AugAssign, ClassDef, Delete, ExceptHandler, For, FunctionDef, If, Import, ImportFrom, List, Name, Operator, Print, PyCtx, PyRootNode, Raise, Return, Starred, Try, Tuple, Type, While, With, WithItem, Yield, YieldFrom};
+use crate::ast::analyze::local_names::local_names;
+use crate::ast::analyze::yield_lowering::YieldLowering;
 use crate::ast::error::{BasicError, ErrorType};
 use crate::ast::error::parser_error::ParserError;
 use crate::ast::namespace::{Namespace, PyNamespace};
 use crate::ast::scanner::{Literal, Scanner, Token, TokenType};
-use crate::ast::scanner::TokenType::{COLON, ELIF, ELSE, EOF, EQUAL, IDENTIFIER, IF, LineBreak, PRINT, SPACE, TAB, WHILE};
+use crate::ast::scanner::TokenType::{AmpersandEqual, AS, AtEqual, CaretEqual, CLASS, Comma, COLON, DEDENT, DEF, DEL, Dot, ELIF, Ellipsis, ELSE, EOF, EQUAL, ExactDivisionEqual, EXCEPT, FINALLY, FOR, FROM, IDENTIFIER, IF, IMPORT, In, INDENT, LeftBrace, LeftBracket, LeftParen, LeftShiftEqual, MinusEqual, ModEqual, NEWLINE, PipeEqual, PlusEqual, Pow, PowEqual, PRINT, RAISE, RETURN, RightBrace, RightBracket, RightParen, RightShiftEqual, Slash, SlashEqual, Star, StarEqual, TRY, WHILE, WITH, YIELD};
+
+/// 增强赋值的符号和对应的二元运算
+const AUGMENTED_ASSIGN: [(TokenType, Operator); 13] = [
+    (PlusEqual, Operator::Add),
+    (MinusEqual, Operator::Sub),
+    (StarEqual, Operator::Mult),
+    (AtEqual, Operator::MatMult),
+    (SlashEqual, Operator::Div),
+    (ExactDivisionEqual, Operator::FloorDiv),
+    (ModEqual, Operator::Mod),
+    (PowEqual, Operator::Pow),
+    (LeftShiftEqual, Operator::LShift),
+    (RightShiftEqual, Operator::RShift),
+    (AmpersandEqual, Operator::BitAnd),
+    (CaretEqual, Operator::BitXor),
+    (PipeEqual, Operator::BitOr),
+];
 
 #[derive(Debug, Clone)]
 pub struct TokenIter {
@@ -46,7 +65,7 @@ impl TokenIter {
             }
         }
     }
-    fn check(&self, token_type: TokenType) -> bool {
+    pub(crate) fn check(&self, token_type: TokenType) -> bool {
         if self.is_at_end() {
             false
         } else {
@@ -82,7 +101,7 @@ impl TokenIter {
                     return false;
                 }
             }
-            if confirms.iter().filter(|&&x| x == true).count() == confirms.len() {
+            if confirms.iter().filter(|&&x| x).count() == confirms.len() {
                 return true;
             } else {
                 self.back(confirms.len()).unwrap();
@@ -103,14 +122,23 @@ impl TokenIter {
                 .lineno(self.peek().lineno as u64)
                 .col_offset(self.peek().col_offset as u64)
                 .lexeme(self.peek().lexeme),
-        ))
+        ).into())
     }
-    pub fn return_surplus(&self) -> Vec<Token> {
-        let tokens: Vec<Token> = self.vec_token[self.current..]
-            .into_iter()
-            .map(|x| x.clone())
-            .collect();
-        tokens
+    /// 当前的左括号与它匹配的右括号之后是否紧接着token_type
+    pub(crate) fn closes_before(&self, token_type: TokenType) -> bool {
+        let mut depth = 0;
+        for (index, token) in self.vec_token.iter().enumerate().skip(self.current) {
+            match token.token_type {
+                LeftParen | LeftBracket | LeftBrace => depth += 1,
+                RightParen | RightBracket | RightBrace => depth -= 1,
+                EOF => return false,
+                _ => {}
+            }
+            if depth == 0 {
+                return self.vec_token.get(index + 1).is_some_and(|x| x.token_type == token_type);
+            }
+        }
+        false
     }
 }
 #[allow(dead_code)]
@@ -119,15 +147,17 @@ pub struct Parser {
     ast_list: PyRootNode,
     pub token_iter: TokenIter,
     namespace: Namespace,
-    pub indent: u64,
-    pub parent_indent: Vec<u64>,
+    /// 正在解析函数体，return只能出现在函数中
+    pub(crate) in_function: bool,
+    /// 正在解析的函数体中出现过yield
+    pub(crate) generator: bool,
 }
 pub(crate) fn build_parser(scanner: Scanner, py_env: PyNamespace) -> Parser {
     let lineno = scanner.lineno;
     let end_lineno = scanner.end_lineno;
     let col_offset = scanner.col_offset;
     let end_col_offset = scanner.end_col_offset;
-    return Parser {
+    Parser {
         ast_list: PyRootNode {
             body: vec![],
             py_root_env: py_env,
@@ -138,9 +168,9 @@ pub(crate) fn build_parser(scanner: Scanner, py_env: PyNamespace) -> Parser {
         },
         token_iter: TokenIter::new(scanner.token),
         namespace: Namespace::Global,
-        indent: 0,
-        parent_indent: vec![0],
-    };
+        in_function: false,
+        generator: false,
+    }
 }
 
 impl Default for Parser {
@@ -152,285 +182,651 @@ impl Default for Parser {
                 vec_token: vec![],
             },
             namespace: Namespace::Builtin,
-            indent: 0,
-            parent_indent: vec![0],
+            in_function: false,
+            generator: false,
         }
     }
 }
 impl Parser {
-    // Builder
-    pub fn position(
-        &mut self,
-        lineno: u64,
-        end_lineno: u64,
-        col_offset: u64,
-        end_col_offset: u64,
-    ) -> Parser {
-        self.ast_list.lineno = lineno as usize;
-        self.ast_list.end_lineno = end_lineno as usize;
-        self.ast_list.col_offset = col_offset as usize;
-        self.ast_list.end_col_offset = end_col_offset as usize;
-        return self.clone();
-    }
     pub fn tokens(&mut self, tokens: Vec<Token>) -> Self {
         self.token_iter = TokenIter::new(tokens);
         self.clone()
     }
-    pub fn namespace(&mut self, namespace: Namespace) -> Self {
-        self.namespace = namespace;
-        self.clone()
-    }
-    pub fn indent(&mut self, indent: usize, parent_indent: Vec<u64>) -> Self {
-        self.indent = indent as u64;
-        self.parent_indent = parent_indent;
-        self.clone()
-    }
 }
 impl Parser {
-    pub fn parser(&mut self) -> Result<Type, ErrorType> {
-        // println!("{:?}", self.expression());
-        return self.statement();
-    }
     pub fn return_err(&self) -> ErrorType {
         ParserError::new(
             BasicError::default()
                 .lineno(self.token_iter.peek().lineno as u64)
                 .lexeme(self.token_iter.peek().lexeme)
                 .col_offset(self.token_iter.peek().col_offset as u64),
+        ).into()
+    }
+    pub(crate) fn syntax_error(&self, token: &Token, message: &str) -> ErrorType {
+        ParserError::syntax_error(
+            BasicError::default()
+                .lineno(token.lineno as u64)
+                .col_offset(token.col_offset as u64)
+                .lexeme(token.lexeme.clone()),
+            message,
         )
     }
-    pub fn test_indent(&mut self) -> (usize,usize){
-        let mut indent = 0;
-        let mut times = 0;
-        loop {
-            times+=1;
-            if self.token_iter.catch([LineBreak]){
-                continue
-            }else if self.token_iter.catch([SPACE]) {
-                indent+=1
-            }else if self.token_iter.catch([TAB]) {
-                indent+=4
-            }else {
-                times-=1;
-                break
-            }
-        }
-        (indent,times)
+    /// 缩进错误，位置是出错的token
+    fn indentation_error(&self, token: &Token, message: &str) -> ErrorType {
+        ParserError::indentation_error(
+            BasicError::default()
+                .lineno(token.lineno as u64)
+                .col_offset(token.col_offset as u64)
+                .lexeme(token.lexeme.clone()),
+            message,
+        )
     }
-    pub fn parser_without_panic(&mut self) -> Result<Type, ErrorType> {
-        let mut indent: u64 = 0;
-        let mut times = 0;
-        loop {
-            times+=1;
-            if self.token_iter.catch([TAB]) {
-                indent += 4;
-            } else if self.token_iter.catch([SPACE]) {
-                indent += 1
-            } else {
-                break;
-            }
-        }
-        if indent == self.indent {
-        } else if self.parent_indent.contains(&indent) {
-            self.token_iter.back(times-1).unwrap();
-            return Ok(Type::None);
-        } else {
-            panic!("Error to indent")
+    /// 语句不能以INDENT开始，比上一行缩进更多的行不属于任何语句块
+    fn check_unexpected_indent(&self) -> Result<(), ErrorType> {
+        if self.token_iter.check(INDENT) {
+            return Err(self.indentation_error(&self.token_iter.peek(), "unexpected indent"));
         }
-        let x = self.statement();
-        return x;
+        Ok(())
     }
-    pub fn create_vec(&mut self) -> Vec<Box<Type>> {
-        let mut nodes: Vec<Box<Type>> = vec![];
+    pub fn create_vec(&mut self) -> Result<Vec<Type>, ErrorType> {
+        let mut nodes: Vec<Type> = vec![];
         while !self.token_iter.is_at_end() {
-            match self.parser_without_panic() {
-                Ok(x) => match x {
-                    Type::None => break,
-                    _ => nodes.push(Box::from(x)),
-                },
-                Err(e) => {
-                    panic!("{}", e)
-                }
-            }
-            while self.token_iter.catch([TokenType::LineBreak]) {
+            if self.token_iter.catch([NEWLINE]) {
                 continue;
             }
+            self.check_unexpected_indent()?;
+            nodes.push(self.statement()?);
         }
-        nodes
+        Ok(nodes)
     }
     fn statement(&mut self) -> Result<Type, ErrorType> {
-        if self.token_iter.catch([PRINT]) {
-            return self.print_statement();
-        }
         if self.token_iter.catch([IF]) {
             return self.if_statement();
         }
         if self.token_iter.catch([WHILE]) {
             return self.while_statement();
         }
-        self.expression()
+        if self.token_iter.catch([FOR]) {
+            return self.for_statement();
+        }
+        if self.token_iter.catch([DEF]) {
+            return self.function_def();
+        }
+        if self.token_iter.catch([CLASS]) {
+            return self.class_def();
+        }
+        if self.token_iter.catch([TRY]) {
+            return self.try_statement();
+        }
+        if self.token_iter.catch([WITH]) {
+            return self.with_statement();
+        }
+        self.simple_statement()
     }
-    fn identifier_statement(&mut self, ctx: PyCtx) -> Result<Type, ErrorType> {
-        if self.token_iter.catch([IDENTIFIER]) {
-            Ok(Type::Name(Name {
-                id: match self.token_iter.previous(1).literal {
-                    Literal::Identifier(x) => x,
-                    _ => panic!("Error at get name"),
-                },
-                ctx,
-            }))
+    fn simple_statement(&mut self) -> Result<Type, ErrorType> {
+        let expr = if self.token_iter.catch([PRINT]) {
+            self.print_statement()?
+        } else if self.token_iter.catch([RETURN]) {
+            self.return_statement()?
+        } else if self.token_iter.catch([DEL]) {
+            self.del_statement()?
+        } else if self.token_iter.catch([RAISE]) {
+            self.raise_statement()?
+        } else if self.token_iter.catch([IMPORT]) {
+            self.import_statement()?
+        } else if self.token_iter.catch([FROM]) {
+            self.import_from()?
         } else {
-            panic!("error")
+            let token = self.token_iter.peek();
+            let expr = self.assigned_value()?;
+            if let Some((_, op)) = AUGMENTED_ASSIGN.iter().find(|(x, _)| self.token_iter.catch([*x])) {
+                let target = match expr {
+                    Type::Name(_) | Type::Attribute(_) | Type::Subscript(_) => {
+                        self.to_target(expr, PyCtx::Store, &token)?
+                    }
+                    _ => return Err(self.syntax_error(&token, "illegal expression for augmented assignment")),
+                };
+                let value = self.assigned_value()?;
+                Type::AugAssign(Box::from(AugAssign {
+                    target: Box::from(target),
+                    op: op.clone(),
+                    value: Box::from(value),
+                }))
+            } else if self.token_iter.check(EQUAL) {
+                // in a = b = 0 every expression but the last is a target
+                let (mut targets, mut value, mut token) = (vec![], expr, token);
+                while self.token_iter.catch([EQUAL]) {
+                    targets.push(self.to_target(value, PyCtx::Store, &token)?);
+                    token = self.token_iter.peek();
+                    value = self.assigned_value()?;
+                }
+                Type::Assign(Box::from(Assign {
+                    targets,
+                    value: Box::from(value),
+                    type_comment: "".to_string(),
+                }))
+            } else {
+                expr
+            }
+        };
+        self.token_iter.consume(NEWLINE, "".to_string())?;
+        Ok(expr)
+    }
+    /// 表达式语句和赋值的右边，可以是不在括号中的yield表达式
+    fn assigned_value(&mut self) -> Result<Type, ErrorType> {
+        if self.token_iter.catch([YIELD]) {
+            return self.yield_expression();
+        }
+        self.expression_list()
+    }
+    /// yield、`yield value`和`yield from value`，出现yield的函数是生成器函数
+    pub(crate) fn yield_expression(&mut self) -> Result<Type, ErrorType> {
+        let token = self.token_iter.previous(1);
+        if !self.in_function {
+            return Err(self.syntax_error(&token, "'yield' outside function"));
+        }
+        self.generator = true;
+        if self.token_iter.catch([FROM]) {
+            return Ok(Type::YieldFrom(Box::from(YieldFrom {
+                value: Box::from(self.expression()?),
+            })));
+        }
+        let value = if [NEWLINE, RightParen].into_iter().any(|x| self.token_iter.check(x)) {
+            None
+        } else {
+            Some(Box::from(self.expression_list()?))
+        };
+        Ok(Type::Yield(Box::from(Yield { value })))
+    }
+    /// 取出标识符的名字，名字已经经过NFKC规范化
+    pub(crate) fn identifier_name(&mut self) -> Result<String, ErrorType> {
+        let token = self.token_iter.consume(IDENTIFIER, "".to_string())?;
+        match token.literal {
+            Literal::Identifier(x) => Ok(x),
+            _ => Err(self.syntax_error(&token, "invalid syntax")),
         }
     }
     fn print_statement(&mut self) -> Result<Type, ErrorType> {
-        let expr = self.statement().unwrap();
-        self.token_iter
-            .consume(TokenType::LineBreak, "".to_string())?;
+        let expr = self.expression()?;
         Ok(Type::Print(Box::from(Print {
             arg: Box::new(expr),
         })))
     }
-    pub(crate) fn assign_statement(&mut self) -> Result<Type, ErrorType> {
-        let expr = self.identifier_statement(PyCtx::Load);
-        while self.token_iter.catch([EQUAL]) && !self.token_iter.catch_multi([[EQUAL, EQUAL]]) {
-            let right = self.statement()?;
-            let expr = match expr? {
-                Type::Name(mut x) => Type::Name(x.ctx(PyCtx::Store)),
-                Type::Attribute(_) => {
-                    todo!()
-                }
-                _ => todo!(),
-            };
-            self.token_iter
-                .consume(TokenType::LineBreak, "".to_string())?;
-            return Ok(Type::Assign(Box::from(Assign {
-                target: Box::from(expr),
-                value: Box::from(right),
-                type_comment: "".to_string(),
-            })));
+    fn del_statement(&mut self) -> Result<Type, ErrorType> {
+        let mut targets = vec![];
+        loop {
+            let token = self.token_iter.peek();
+            let expr = self.expression()?;
+            targets.push(self.to_target(expr, PyCtx::Del, &token)?);
+            if !self.token_iter.catch([Comma]) {
+                break;
+            }
         }
-        expr
-    }
-    fn sub_type(&mut self) -> Result<Vec<Box<Type>>, ErrorType> {
-        let indent;
-        let times;
-        (indent, times) = self.test_indent();
-        self.token_iter.back(times).unwrap();
-        if self.indent >= indent as u64 {
-            return Err(self.return_err());
-        }
-        let mut parent_indent:Vec<u64> = vec![];
-        parent_indent.append(&mut self.parent_indent.clone());
-        parent_indent.push(self.indent);
-        let mut parser = Parser::default()
-            .tokens(self.token_iter.vec_token.clone())
-            .indent(indent, parent_indent);
-        parser.token_iter.current = self.token_iter.current;
-        let body = parser.create_vec();
-        self.token_iter.current = parser.token_iter.current;
-        return Ok(body);
-    }
-    fn else_statement(&mut self) -> Result<Vec<Box<Type>>, ErrorType>{
-        let mut orelse: Vec<Box<Type>> = vec![];
-        if self.token_iter.catch([ELSE]) {
-            if self.token_iter.catch_multi([[COLON,LineBreak]]) {
-                orelse.append(&mut self.sub_type()?)
-            }else if self.token_iter.catch([COLON]){
-                orelse.push(Box::from(self.statement()?));
-                self.token_iter
-                    .consume(LineBreak, "".to_string())?;
-            }else {
+        Ok(Type::Delete(Box::from(Delete { targets })))
+    }
+    /// 把表达式转换为赋值或删除的目标，名字、属性、下标和它们组成的元组或列表可以作为目标
+    fn to_target(&self, expr: Type, ctx: PyCtx, token: &Token) -> Result<Type, ErrorType> {
+        match expr {
+            Type::Name(mut x) => Ok(Type::Name(x.ctx(ctx))),
+            Type::Attribute(mut x) => {
+                x.ctx = ctx;
+                Ok(Type::Attribute(x))
+            }
+            Type::Subscript(mut x) => {
+                x.ctx = ctx;
+                Ok(Type::Subscript(x))
+            }
+            Type::Tuple(x) => Ok(Type::Tuple(Box::from(Tuple {
+                elts: self.to_targets(x.elts, ctx.clone(), token)?,
+                ctx,
+            }))),
+            Type::List(x) => Ok(Type::List(Box::from(List {
+                elts: self.to_targets(x.elts, ctx.clone(), token)?,
+                ctx,
+            }))),
+            Type::Starred(_) if matches!(ctx, PyCtx::Store) => Err(self.syntax_error(
+                token,
+                "starred assignment target must be in a list or tuple",
+            )),
+            _ => Err(self.syntax_error(
+                token,
+                match ctx {
+                    PyCtx::Del => "cannot delete expression",
+                    _ => "cannot assign to expression",
+                },
+            )),
+        }
+    }
+    /// 元组或列表中的目标，赋值时其中一个可以是`*x`
+    fn to_targets(&self, elts: Vec<Type>, ctx: PyCtx, token: &Token) -> Result<Vec<Type>, ErrorType> {
+        let mut targets = vec![];
+        for elt in elts {
+            targets.push(match elt {
+                Type::Starred(x) if matches!(ctx, PyCtx::Store) => Type::Starred(Box::from(Starred {
+                    value: Box::from(self.to_target(*x.value, ctx.clone(), token)?),
+                    ctx: ctx.clone(),
+                })),
+                elt => self.to_target(elt, ctx.clone(), token)?,
+            });
+        }
+        self.check_starred(&targets, token)?;
+        Ok(targets)
+    }
+    fn check_starred(&self, targets: &[Type], token: &Token) -> Result<(), ErrorType> {
+        if targets.iter().filter(|x| matches!(x, Type::Starred(_))).count() > 1 {
+            return Err(self.syntax_error(token, "two starred expressions in assignment"));
+        }
+        Ok(())
+    }
+    fn sub_type(&mut self) -> Result<Vec<Type>, ErrorType> {
+        // the scanner has already turned indentation into INDENT/DEDENT,
+        // so a block is everything between them
+        if !self.token_iter.catch([INDENT]) {
+            return Err(self.indentation_error(&self.token_iter.peek(), "expected an indented block"));
+        }
+        let mut body: Vec<Type> = vec![];
+        while !self.token_iter.catch([DEDENT]) {
+            if self.token_iter.is_at_end() {
                 return Err(self.return_err());
             }
-        };
-        return Ok(orelse);
+            if self.token_iter.catch([NEWLINE]) {
+                continue;
+            }
+            self.check_unexpected_indent()?;
+            body.push(self.statement()?);
+        }
+        Ok(body)
+    }
+    fn suite(&mut self) -> Result<Vec<Type>, ErrorType> {
+        self.token_iter.consume(COLON, "".to_string())?;
+        if self.token_iter.catch([NEWLINE]) {
+            self.sub_type()
+        } else {
+            Ok(vec![self.simple_statement()?])
+        }
+    }
+    fn else_statement(&mut self) -> Result<Vec<Type>, ErrorType> {
+        if self.token_iter.catch([ELSE]) {
+            return self.suite();
+        }
+        Ok(vec![])
     }
     fn if_statement(&mut self) -> Result<Type, ErrorType> {
-        let test = Box::from(self.statement()?);
-        while self.token_iter.catch([COLON]) {
-            return if self.token_iter.catch([LineBreak]) {
-                let body = self.sub_type()?;
-                let mut indent= 0;
-                let times;
-                (indent, times) = self.test_indent();
-                let mut orelse: Vec<Box<Type>> = vec![];
-                if self.parent_indent.contains(&(indent as u64)) {
-                    orelse.append(&mut self.else_statement()?);
-                    self.token_iter.back(times).unwrap();
-                }else {
-                    if indent != self.indent as usize {
-                        return Err(self.return_err());
-                    } else {
-                        if self.token_iter.catch([ELIF]) {
-                            orelse.push(Box::from(self.if_statement()?))
-                        }
-                        orelse.append(&mut self.else_statement()?);
-                        if orelse.len() == 0{
-                            self.token_iter.back(times).unwrap();
-                        }
-                    }
+        let test = Box::from(self.expression()?);
+        let body = self.suite()?;
+        let orelse = if self.token_iter.catch([ELIF]) {
+            vec![self.if_statement()?]
+        } else {
+            self.else_statement()?
+        };
+        Ok(Type::If(Box::from(If { test, body, orelse })))
+    }
+    fn while_statement(&mut self) -> Result<Type, ErrorType> {
+        let test = Box::from(self.expression()?);
+        let body = self.suite()?;
+        let orelse = self.else_statement()?;
+        Ok(Type::While(Box::from(While { test, body, orelse })))
+    }
+    fn for_statement(&mut self) -> Result<Type, ErrorType> {
+        let target = Box::from(self.target_list(In)?);
+        let iter = Box::from(self.expression()?);
+        let body = self.suite()?;
+        let orelse = self.else_statement()?;
+        Ok(Type::For(Box::from(For { target, iter, body, orelse })))
+    }
+    fn try_statement(&mut self) -> Result<Type, ErrorType> {
+        let token = self.token_iter.previous(1);
+        let body = self.suite()?;
+        let mut handlers: Vec<ExceptHandler> = vec![];
+        while self.token_iter.catch([EXCEPT]) {
+            let except = self.token_iter.previous(1);
+            if handlers.last().is_some_and(|x| x.type_.is_none()) {
+                return Err(self.syntax_error(&except, "default 'except:' must be last"));
+            }
+            let (type_, name) = if self.token_iter.check(COLON) {
+                (None, None)
+            } else {
+                let type_ = self.expression()?;
+                let name = if self.token_iter.catch([AS]) {
+                    Some(self.identifier_name()?)
+                } else {
+                    None
+                };
+                (Some(type_), name)
+            };
+            let body = self.suite()?;
+            handlers.push(ExceptHandler { type_, name, body });
+        }
+        // else is only allowed after except clauses
+        let orelse = if handlers.is_empty() {
+            vec![]
+        } else {
+            self.else_statement()?
+        };
+        let finalbody = if self.token_iter.catch([FINALLY]) {
+            self.suite()?
+        } else {
+            vec![]
+        };
+        if handlers.is_empty() && finalbody.is_empty() {
+            return Err(self.syntax_error(&token, "invalid syntax"));
+        }
+        Ok(Type::Try(Box::from(Try {
+            body,
+            handlers,
+            orelse,
+            finalbody,
+        })))
+    }
+    /// with后面可以用括号把多个上下文管理器括起来，括号中可以换行，最后可以有逗号
+    fn with_statement(&mut self) -> Result<Type, ErrorType> {
+        let mut items = vec![];
+        if self.token_iter.check(LeftParen) && self.token_iter.closes_before(COLON) {
+            self.token_iter.consume(LeftParen, "".to_string())?;
+            while !self.token_iter.catch([RightParen]) {
+                items.push(self.with_item()?);
+                if !self.token_iter.catch([Comma]) {
+                    self.token_iter.consume(RightParen, "".to_string())?;
+                    break;
+                }
+            }
+        } else {
+            items.push(self.with_item()?);
+            while self.token_iter.catch([Comma]) {
+                items.push(self.with_item()?);
+            }
+        }
+        if items.is_empty() {
+            let token = self.token_iter.previous(1);
+            return Err(self.syntax_error(&token, "invalid syntax"));
+        }
+        let body = self.suite()?;
+        Ok(Type::With(Box::from(With { items, body })))
+    }
+    fn with_item(&mut self) -> Result<WithItem, ErrorType> {
+        let context_expr = self.expression()?;
+        let optional_vars = if self.token_iter.catch([AS]) {
+            let token = self.token_iter.peek();
+            let target = self.expression()?;
+            Some(self.to_target(target, PyCtx::Store, &token)?)
+        } else {
+            None
+        };
+        Ok(WithItem {
+            context_expr,
+            optional_vars,
+        })
+    }
+    fn import_statement(&mut self) -> Result<Type, ErrorType> {
+        let mut names = vec![self.import_alias(true)?];
+        while self.token_iter.catch([Comma]) {
+            names.push(self.import_alias(true)?);
+        }
+        Ok(Type::Import(Box::from(Import { names })))
+    }
+    /// from后面的模块名前可以有点，`...`被扫描为一个Ellipsis
+    fn import_from(&mut self) -> Result<Type, ErrorType> {
+        let mut level = 0;
+        while let Some(x) = [(Dot, 1), (Ellipsis, 3)].iter().find(|(x, _)| self.token_iter.catch([*x])) {
+            level += x.1;
+        }
+        let module = if level > 0 && self.token_iter.check(IMPORT) {
+            None
+        } else {
+            Some(self.dotted_name()?)
+        };
+        let token = self.token_iter.consume(IMPORT, "".to_string())?;
+        let names = if self.token_iter.catch([Star]) {
+            if self.in_function {
+                return Err(self.syntax_error(&token, "import * only allowed at module level"));
+            }
+            vec![Alias {
+                name: "*".to_string(),
+                asname: None,
+            }]
+        } else if self.token_iter.catch([LeftParen]) {
+            let mut names = vec![];
+            while !self.token_iter.catch([RightParen]) {
+                names.push(self.import_alias(false)?);
+                if !self.token_iter.catch([Comma]) {
+                    self.token_iter.consume(RightParen, "".to_string())?;
+                    break;
                 }
-                Ok(Type::If(Box::from(If {
-                    test,
-                    body,
-                    orelse,
-                })))
+            }
+            names
+        } else {
+            let mut names = vec![self.import_alias(false)?];
+            while self.token_iter.catch([Comma]) {
+                names.push(self.import_alias(false)?);
+            }
+            names
+        };
+        if names.is_empty() {
+            return Err(self.syntax_error(&token, "invalid syntax"));
+        }
+        Ok(Type::ImportFrom(Box::from(ImportFrom { module, names, level })))
+    }
+    /// import后面的名字，dotted为true时可以是a.b.c
+    fn import_alias(&mut self, dotted: bool) -> Result<Alias, ErrorType> {
+        let name = if dotted {
+            self.dotted_name()?
+        } else {
+            self.identifier_name()?
+        };
+        let asname = if self.token_iter.catch([AS]) {
+            Some(self.identifier_name()?)
+        } else {
+            None
+        };
+        Ok(Alias { name, asname })
+    }
+    fn dotted_name(&mut self) -> Result<String, ErrorType> {
+        let mut names = vec![self.identifier_name()?];
+        while self.token_iter.catch([Dot]) {
+            names.push(self.identifier_name()?);
+        }
+        Ok(names.join("."))
+    }
+    fn raise_statement(&mut self) -> Result<Type, ErrorType> {
+        if self.token_iter.check(NEWLINE) {
+            return Ok(Type::Raise(Box::from(Raise { exc: None, cause: None })));
+        }
+        let exc = Some(Box::from(self.expression()?));
+        let cause = if self.token_iter.catch([FROM]) {
+            Some(Box::from(self.expression()?))
+        } else {
+            None
+        };
+        Ok(Type::Raise(Box::from(Raise { exc, cause })))
+    }
+    /// 解析赋值目标直到end，有逗号时是元组
+    pub(crate) fn target_list(&mut self, end: TokenType) -> Result<Type, ErrorType> {
+        let token = self.token_iter.peek();
+        let (mut elts, comma) = self.targets(end)?;
+        if elts.len() == 1 && !comma {
+            if let Type::Starred(_) = elts[0] {
+                return Err(self.syntax_error(&token, "starred assignment target must be in a list or tuple"));
+            }
+            return Ok(elts.remove(0));
+        }
+        if elts.is_empty() && end == In {
+            let token = self.token_iter.previous(1);
+            return Err(self.syntax_error(&token, "invalid syntax"));
+        }
+        Ok(Type::Tuple(Box::from(Tuple { elts, ctx: PyCtx::Store })))
+    }
+    /// 逗号分隔的目标直到end，返回所有目标和是否出现过逗号
+    fn targets(&mut self, end: TokenType) -> Result<(Vec<Type>, bool), ErrorType> {
+        let token = self.token_iter.peek();
+        let mut elts = vec![];
+        let mut comma = false;
+        while !self.token_iter.catch([end]) {
+            elts.push(self.target()?);
+            if self.token_iter.catch([Comma]) {
+                comma = true;
             } else {
-                let body = self.statement()?;
-                self.token_iter
-                    .consume(LineBreak, "".to_string())?;
-                Ok(Type::If(Box::from(If {
-                    test,
-                    body: vec![Box::from(body)],
-                    orelse: vec![],
-                })))
+                self.token_iter.consume(end, "".to_string())?;
+                break;
             }
         }
-        Err(self.return_err())
+        self.check_starred(&elts, &token)?;
+        Ok((elts, comma))
     }
-    fn while_statement(&mut self) -> Result<Type, ErrorType> {
-        let test = Box::from(self.statement()?);
-        while self.token_iter.catch([COLON]) {
-            return if self.token_iter.catch([LineBreak]) {
-                let body = self.sub_type()?;
-                let indent;
-                let times;
-                (indent, times ) = self.test_indent();
-                let mut orelse: Vec<Box<Type>> = vec![];
-                if self.parent_indent.contains(&(indent as u64)) {
-                    orelse.append(&mut self.else_statement()?);
-                    self.token_iter.back(times).unwrap();
-                }else{
-                    if indent != self.indent as usize {
-                        return Err(self.return_err());
-                    }
-                    orelse.append(&mut self.else_statement()?)
+    fn target(&mut self) -> Result<Type, ErrorType> {
+        if self.token_iter.catch([Star]) {
+            return Ok(Type::Starred(Box::from(Starred {
+                value: Box::from(self.target()?),
+                ctx: PyCtx::Store,
+            })));
+        }
+        if self.token_iter.catch([LeftParen]) {
+            return self.target_list(RightParen);
+        }
+        if self.token_iter.catch([LeftBracket]) {
+            let (elts, _) = self.targets(RightBracket)?;
+            return Ok(Type::List(Box::from(List { elts, ctx: PyCtx::Store })));
+        }
+        Ok(Type::Name(Name {
+            id: self.identifier_name()?,
+            ctx: PyCtx::Store,
+        }))
+    }
+    /// 解析形参列表直到end，def以右括号结束，lambda以冒号结束
+    pub(crate) fn parameters(&mut self, end: TokenType) -> Result<Arguments, ErrorType> {
+        let mut arguments = Arguments::default();
+        let mut names: Vec<String> = vec![];
+        let mut slash = false;
+        let mut star = false;
+        // a bare '*' must be followed by at least one keyword-only parameter
+        let mut bare_star: Option<Token> = None;
+        while !self.token_iter.catch([end]) {
+            let token = self.token_iter.peek();
+            if arguments.kwarg.is_some() {
+                return Err(self.syntax_error(&token, "arguments cannot follow var-keyword argument"));
+            }
+            if self.token_iter.catch([Slash]) {
+                if star {
+                    return Err(self.syntax_error(&token, "/ must be ahead of *"));
+                }
+                if slash {
+                    return Err(self.syntax_error(&token, "/ may appear only once"));
+                }
+                if arguments.args.is_empty() {
+                    return Err(self.syntax_error(&token, "at least one argument must precede /"));
+                }
+                slash = true;
+                arguments.posonlyargs = std::mem::take(&mut arguments.args);
+            } else if self.token_iter.catch([Pow]) {
+                arguments.kwarg = Some(self.parameter_name(&mut names)?);
+            } else if self.token_iter.catch([Star]) {
+                if star {
+                    return Err(self.syntax_error(&token, "* argument may appear only once"));
+                }
+                star = true;
+                if self.token_iter.check(IDENTIFIER) {
+                    arguments.vararg = Some(self.parameter_name(&mut names)?);
+                } else {
+                    bare_star = Some(token);
                 }
-                Ok(Type::While(Box::from(While {
-                    test,
-                    body,
-                    orelse,
-                })))
             } else {
-                let body = self.statement()?;
-                self.token_iter
-                    .consume(LineBreak, "".to_string())?;
-                Ok(Type::While(Box::from(While {
-                    test,
-                    body: vec![Box::from(body)],
-                    orelse: vec![],
-                })))
+                let name = self.parameter_name(&mut names)?;
+                let default = if self.token_iter.catch([EQUAL]) {
+                    Some(self.expression()?)
+                } else {
+                    None
+                };
+                if star {
+                    arguments.kwonlyargs.push(name);
+                    arguments.kw_defaults.push(default);
+                    bare_star = None;
+                } else {
+                    match default {
+                        Some(x) => arguments.defaults.push(x),
+                        None if !arguments.defaults.is_empty() => {
+                            return Err(self.syntax_error(
+                                &token,
+                                "non-default argument follows default argument",
+                            ))
+                        }
+                        None => {}
+                    }
+                    arguments.args.push(name);
+                }
             }
+            if !self.token_iter.catch([Comma]) {
+                self.token_iter.consume(end, "".to_string())?;
+                break;
+            }
+        }
+        if let Some(token) = bare_star {
+            return Err(self.syntax_error(&token, "named arguments must follow bare *"));
         }
-        Err(self.return_err())
+        Ok(arguments)
     }
-    #[allow(dead_code)]
-    fn synchronize(&mut self) -> bool {
-        self.token_iter.advance();
-        while !self.token_iter.is_at_end() {
-            return true;
+    fn parameter_name(&mut self, names: &mut Vec<String>) -> Result<String, ErrorType> {
+        let token = self.token_iter.peek();
+        let name = self.identifier_name()?;
+        if names.contains(&name) {
+            return Err(self.syntax_error(
+                &token,
+                format!("duplicate argument '{}' in function definition", name).as_str(),
+            ));
         }
-        false
+        names.push(name.clone());
+        Ok(name)
+    }
+    fn function_def(&mut self) -> Result<Type, ErrorType> {
+        let name = self.identifier_name()?;
+        self.token_iter.consume(LeftParen, "".to_string())?;
+        let args = self.parameters(RightParen)?;
+        let in_function = std::mem::replace(&mut self.in_function, true);
+        let generator = std::mem::replace(&mut self.generator, false);
+        let body = self.suite();
+        self.in_function = in_function;
+        let generator = std::mem::replace(&mut self.generator, generator);
+        let body = body?;
+        // the names are collected before lowering, the temporaries of the lowering are not read unbound
+        let locals = local_names(&args, &body);
+        let body = match generator {
+            true => YieldLowering::default().suite(body),
+            false => body,
+        };
+        Ok(Type::FunctionDef(Box::from(FunctionDef {
+            name,
+            args,
+            body,
+            generator,
+            locals,
+        })))
+    }
+    fn class_def(&mut self) -> Result<Type, ErrorType> {
+        let name = self.identifier_name()?;
+        let mut bases = vec![];
+        if self.token_iter.catch([LeftParen]) {
+            while !self.token_iter.catch([RightParen]) {
+                bases.push(self.expression()?);
+                if !self.token_iter.catch([Comma]) {
+                    self.token_iter.consume(RightParen, "".to_string())?;
+                    break;
+                }
+            }
+        }
+        // return is not allowed directly in a class body
+        let in_function = std::mem::replace(&mut self.in_function, false);
+        let body = self.suite();
+        self.in_function = in_function;
+        Ok(Type::ClassDef(Box::from(ClassDef { name, bases, body: body? })))
+    }
+    fn return_statement(&mut self) -> Result<Type, ErrorType> {
+        let token = self.token_iter.previous(1);
+        if !self.in_function {
+            return Err(self.syntax_error(&token, "'return' outside function"));
+        }
+        let value = if self.token_iter.check(NEWLINE) {
+            None
+        } else {
+            Some(Box::from(self.expression_list()?))
+        };
+        Ok(Type::Return(Box::from(Return { value })))
     }
 }
@@ -1,18 +1,57 @@
 use crate::ast::analyze::ast_analyze::Parser;
-use crate::ast::ast_struct::{BinOp, BoolOp, Compare, Constant, Operator, Type, UnaryOp};
+use crate::ast::ast_struct::{
+    Attribute, BinOp, BoolOp, Call, Compare, Comprehension, Constant, Dict, DictComp, GeneratorExp, IfExp, Keyword, Lambda, List,
+    ListComp, Name, Operator, PyCtx, Set, SetComp, Slice, Starred, Subscript, Tuple, Type, UnaryOp,
+};
 use crate::ast::ast_struct::Operator::Not;
 use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::bytes::obj_bytes;
+use crate::ast::data_type::complex::obj_complex;
 use crate::ast::data_type::float::obj_float;
 use crate::ast::data_type::int::obj_int;
+use crate::ast::data_type::none::obj_none;
 use crate::ast::data_type::str::obj_str;
-use crate::ast::error::ErrorType;
-use crate::ast::scanner::{Literal, TokenType};
+use crate::ast::error::{BasicError, ErrorType};
+use crate::ast::error::parser_error::ParserError;
+use crate::ast::scanner::{Literal, Token, TokenType};
 use crate::ast::scanner::TokenType::{
-    AND, BangEqual, EqualEqual, GREATER, GreaterEqual, IDENTIFIER, In, Is, LeftParen, LESS, LessEqual,
-    Minus, NOT, OR, Plus, Slash, Star,
+    AND, Ampersand, At, BangEqual, COLON, Caret, Comma, Dot, ELSE, EOF, EQUAL, EqualEqual, ExactDivision, FOR, GREATER,
+    GreaterEqual, IDENTIFIER, IF, In, Is, LAMBDA, LeftBrace, LeftBracket, LeftParen, LeftShift, LESS, LessEqual, Minus,
+    Mod, NEWLINE, NOT, OR, Pipe, Plus, Pow, RightBrace, RightBracket, RightParen, RightShift, Slash, Star, Tilde, YIELD,
 };
 
 impl Parser {
+    fn string_literal(&mut self) -> Result<Type, ErrorType> {
+        // adjacent string literals are concatenated, "a" 'b' is the same as "ab"
+        let first = self.token_iter.previous(1);
+        let mut literal = first.literal.clone();
+        while self.token_iter.catch([TokenType::STRING]) {
+            let token = self.token_iter.previous(1);
+            literal = match (literal, token.literal.clone()) {
+                (Literal::Bytes(mut x), Literal::Bytes(y)) => {
+                    x.extend(y);
+                    Literal::Bytes(x)
+                }
+                (Literal::Bytes(_), _) | (_, Literal::Bytes(_)) => {
+                    // cannot mix bytes and nonbytes literals
+                    return Err(ParserError::new(
+                        BasicError::default()
+                            .lineno(token.lineno as u64)
+                            .col_offset(token.col_offset as u64)
+                            .lexeme(token.lexeme),
+                    ).into());
+                }
+                (Literal::Str(x), Literal::Str(y)) => Literal::Str(x + y.as_str()),
+                (x, y) => Literal::FormatStr(format_part(x) + format_part(y).as_str()),
+            };
+        }
+        Ok(Type::Constant(Constant::new(match literal {
+            Literal::Bytes(x) => obj_bytes(x),
+            Literal::Str(x) => obj_str(x),
+            Literal::FormatStr(x) => return self.format_string(x, &first),
+            _ => obj_str("".to_string()),
+        })))
+    }
     fn primary(&mut self) -> Result<Type, ErrorType> {
         if self.token_iter.catch([TokenType::TRUE]) {
             return Ok(Type::Constant(Constant::new(obj_bool(true))));
@@ -20,42 +59,391 @@ impl Parser {
         if self.token_iter.catch([TokenType::FALSE]) {
             return Ok(Type::Constant(Constant::new(obj_bool(false))));
         }
+        if self.token_iter.catch([TokenType::None]) {
+            return Ok(Type::Constant(Constant::new(obj_none())));
+        }
         if self.token_iter.catch([TokenType::Break]) {
             return Ok(Type::Break);
         }
         if self.token_iter.catch([TokenType::Continue]) {
             return Ok(Type::Continue);
         }
-        if self
-            .token_iter
-            .catch([TokenType::STRING, TokenType::NUMBER])
-        {
+        if self.token_iter.catch([TokenType::STRING]) {
+            return self.string_literal();
+        }
+        if self.token_iter.catch([TokenType::NUMBER]) {
+            let token = self.token_iter.previous(1);
+            if let Literal::LargeInt = token.literal {
+                return Ok(Type::LargeInt(token.lexeme));
+            }
             return Ok(Type::Constant(Constant::new(
-                match self.token_iter.previous(1).literal {
-                    Literal::Str(str) => obj_str(str),
+                match token.literal {
                     Literal::Float(float) => obj_float(float),
                     Literal::Int(int) => obj_int(int),
+                    Literal::Imaginary(imag) => obj_complex(0.0, imag),
                     _ => obj_int(0),
                 },
             )));
         }
 
-        if self.token_iter.catch([IDENTIFIER]) {
-            self.token_iter.back(1).unwrap();
-            return self.assign_statement();
+        if self.token_iter.check(IDENTIFIER) {
+            return Ok(Type::Name(Name {
+                id: self.identifier_name()?,
+                ctx: PyCtx::Load,
+            }));
         }
         if self.token_iter.catch([LeftParen]) {
-            let expr = self.expression();
-            self.token_iter
-                .consume(TokenType::RightParen, "".to_string())?;
-            return Ok(expr.unwrap());
+            return self.paren_display();
+        }
+        if self.token_iter.catch([LeftBracket]) {
+            return self.list_display();
+        }
+        if self.token_iter.catch([LeftBrace]) {
+            return self.brace_display();
         }
         Err(self.return_err())
     }
+    /// 列表、元组和集合中的一项，可以是`*x`
+    fn display_item(&mut self) -> Result<Type, ErrorType> {
+        if self.token_iter.catch([Star]) {
+            let value = self.expression()?;
+            return Ok(Type::Starred(Box::new(Starred {
+                value: Box::new(value),
+                ctx: PyCtx::Load,
+            })));
+        }
+        self.expression()
+    }
+    /// 语句中逗号分隔的表达式，有逗号时是元组，如`a, *b = 1, 2, 3`中的两边
+    pub(crate) fn expression_list(&mut self) -> Result<Type, ErrorType> {
+        let mut elts = vec![self.display_item()?];
+        let mut comma = false;
+        while self.token_iter.catch([Comma]) {
+            comma = true;
+            if [NEWLINE, EOF, EQUAL].into_iter().any(|x| self.token_iter.check(x)) {
+                break;
+            }
+            elts.push(self.display_item()?);
+        }
+        if !comma {
+            return Ok(elts.remove(0));
+        }
+        Ok(Type::Tuple(Box::new(Tuple { elts, ctx: PyCtx::Load })))
+    }
+    /// 第一项之后的元素直到end，返回所有元素和是否出现过逗号
+    fn display_rest(&mut self, first: Type, end: TokenType) -> Result<(Vec<Type>, bool), ErrorType> {
+        let mut elts = vec![first];
+        let mut comma = false;
+        while self.token_iter.catch([Comma]) {
+            comma = true;
+            if self.token_iter.check(end) {
+                break;
+            }
+            elts.push(self.display_item()?);
+        }
+        self.token_iter.consume(end, "".to_string())?;
+        Ok((elts, comma))
+    }
+    /// 推导式的for子句，elt是推导式的元素，不能是`*x`
+    fn comprehension(&mut self, elt: &Type, token: &Token) -> Result<Vec<Comprehension>, ErrorType> {
+        if let Type::Starred(_) = elt {
+            return Err(self.syntax_error(token, "iterable unpacking cannot be used in comprehension"));
+        }
+        let mut generators = vec![];
+        while self.token_iter.catch([FOR]) {
+            let target = self.target_list(In)?;
+            let iter = self.bool_operate()?;
+            let mut ifs = vec![];
+            while self.token_iter.catch([IF]) {
+                ifs.push(self.bool_operate()?);
+            }
+            generators.push(Comprehension {
+                target: Box::new(target),
+                iter: Box::new(iter),
+                ifs,
+            });
+        }
+        Ok(generators)
+    }
+    /// 括号中的表达式、元组或生成器表达式，左括号已经被消耗
+    fn paren_display(&mut self) -> Result<Type, ErrorType> {
+        if self.token_iter.catch([YIELD]) {
+            let expr = self.yield_expression()?;
+            self.token_iter.consume(RightParen, "".to_string())?;
+            return Ok(expr);
+        }
+        if self.token_iter.catch([RightParen]) {
+            return Ok(Type::Tuple(Box::new(Tuple {
+                elts: vec![],
+                ctx: PyCtx::Load,
+            })));
+        }
+        let token = self.token_iter.peek();
+        let first = self.display_item()?;
+        if self.token_iter.check(FOR) {
+            let generators = self.comprehension(&first, &token)?;
+            self.token_iter.consume(RightParen, "".to_string())?;
+            return Ok(Type::GeneratorExp(Box::new(GeneratorExp {
+                elt: Box::new(first),
+                generators,
+            })));
+        }
+        let (mut elts, comma) = self.display_rest(first, RightParen)?;
+        if !comma {
+            return Ok(elts.remove(0));
+        }
+        Ok(Type::Tuple(Box::new(Tuple {
+            elts,
+            ctx: PyCtx::Load,
+        })))
+    }
+    /// 列表或列表推导式，左括号已经被消耗
+    fn list_display(&mut self) -> Result<Type, ErrorType> {
+        if self.token_iter.catch([RightBracket]) {
+            return Ok(Type::List(Box::new(List {
+                elts: vec![],
+                ctx: PyCtx::Load,
+            })));
+        }
+        let token = self.token_iter.peek();
+        let first = self.display_item()?;
+        if self.token_iter.check(FOR) {
+            let generators = self.comprehension(&first, &token)?;
+            self.token_iter.consume(RightBracket, "".to_string())?;
+            return Ok(Type::ListComp(Box::new(ListComp {
+                elt: Box::new(first),
+                generators,
+            })));
+        }
+        Ok(Type::List(Box::new(List {
+            elts: self.display_rest(first, RightBracket)?.0,
+            ctx: PyCtx::Load,
+        })))
+    }
+    /// 字典、集合和它们的推导式，左花括号已经被消耗，`{}`是空字典
+    fn brace_display(&mut self) -> Result<Type, ErrorType> {
+        if self.token_iter.catch([RightBrace]) {
+            return Ok(Type::Dict(Box::new(Dict {
+                keys: vec![],
+                values: vec![],
+            })));
+        }
+        let token = self.token_iter.peek();
+        if self.token_iter.check(Pow) {
+            let (key, value) = self.dict_item()?;
+            if self.token_iter.check(FOR) {
+                return Err(self.syntax_error(&token, "dict unpacking cannot be used in dict comprehension"));
+            }
+            return self.dict_display(key, value);
+        }
+        let first = self.display_item()?;
+        if !matches!(first, Type::Starred(_)) && self.token_iter.catch([COLON]) {
+            let value = self.expression()?;
+            if self.token_iter.check(FOR) {
+                let generators = self.comprehension(&value, &token)?;
+                self.token_iter.consume(RightBrace, "".to_string())?;
+                return Ok(Type::DictComp(Box::new(DictComp {
+                    key: Box::new(first),
+                    value: Box::new(value),
+                    generators,
+                })));
+            }
+            return self.dict_display(Some(first), value);
+        }
+        if self.token_iter.check(FOR) {
+            let generators = self.comprehension(&first, &token)?;
+            self.token_iter.consume(RightBrace, "".to_string())?;
+            return Ok(Type::SetComp(Box::new(SetComp {
+                elt: Box::new(first),
+                generators,
+            })));
+        }
+        Ok(Type::Set(Box::new(Set {
+            elts: self.display_rest(first, RightBrace)?.0,
+        })))
+    }
+    /// 字典中的`key: value`或`**mapping`
+    fn dict_item(&mut self) -> Result<(Option<Type>, Type), ErrorType> {
+        if self.token_iter.catch([Pow]) {
+            return Ok((None, self.expression()?));
+        }
+        let key = self.expression()?;
+        self.token_iter.consume(COLON, "".to_string())?;
+        Ok((Some(key), self.expression()?))
+    }
+    /// 字典中第一项之后的部分
+    fn dict_display(&mut self, key: Option<Type>, value: Type) -> Result<Type, ErrorType> {
+        let mut keys = vec![key];
+        let mut values = vec![value];
+        while self.token_iter.catch([Comma]) {
+            if self.token_iter.check(RightBrace) {
+                break;
+            }
+            let (key, value) = self.dict_item()?;
+            keys.push(key);
+            values.push(value);
+        }
+        self.token_iter.consume(RightBrace, "".to_string())?;
+        Ok(Type::Dict(Box::new(Dict { keys, values })))
+    }
+    /// 解析调用的参数列表，左括号已经被消耗
+    fn finish_call(&mut self, func: Type) -> Result<Type, ErrorType> {
+        let mut args: Vec<Type> = vec![];
+        let mut keywords: Vec<Keyword> = vec![];
+        while !self.token_iter.catch([RightParen]) {
+            let token = self.token_iter.peek();
+            // f(a=1, *b) is allowed, but f(**a, *b) is not
+            let unpacked_mapping = keywords.iter().any(|x| x.arg.is_none());
+            if self.token_iter.catch([Pow]) {
+                let value = self.expression()?;
+                keywords.push(Keyword {
+                    arg: None,
+                    value: Box::new(value),
+                });
+            } else if self.token_iter.catch([Star]) {
+                if unpacked_mapping {
+                    return Err(self.syntax_error(
+                        &token,
+                        "iterable argument unpacking follows keyword argument unpacking",
+                    ));
+                }
+                let value = self.expression()?;
+                args.push(Type::Starred(Box::new(Starred {
+                    value: Box::new(value),
+                    ctx: PyCtx::Load,
+                })));
+            } else if self.token_iter.catch_multi([[IDENTIFIER, EQUAL]]) {
+                self.token_iter.back(2).unwrap();
+                let arg = self.identifier_name()?;
+                self.token_iter.consume(EQUAL, "".to_string())?;
+                if keywords.iter().any(|x| x.arg.as_ref() == Some(&arg)) {
+                    return Err(self.syntax_error(
+                        &token,
+                        format!("keyword argument repeated: {}", arg).as_str(),
+                    ));
+                }
+                let value = self.expression()?;
+                keywords.push(Keyword {
+                    arg: Some(arg),
+                    value: Box::new(value),
+                });
+            } else {
+                let mut value = self.expression()?;
+                if self.token_iter.check(FOR) {
+                    let generators = self.comprehension(&value, &token)?;
+                    // f(x for x in y) is fine, but the parentheses are needed with other arguments
+                    if !args.is_empty() || !keywords.is_empty() || !self.token_iter.check(RightParen) {
+                        return Err(self.syntax_error(&token, "Generator expression must be parenthesized"));
+                    }
+                    value = Type::GeneratorExp(Box::new(GeneratorExp {
+                        elt: Box::new(value),
+                        generators,
+                    }));
+                }
+                if unpacked_mapping {
+                    return Err(self.syntax_error(
+                        &token,
+                        "positional argument follows keyword argument unpacking",
+                    ));
+                }
+                if !keywords.is_empty() {
+                    return Err(self.syntax_error(
+                        &token,
+                        "positional argument follows keyword argument",
+                    ));
+                }
+                args.push(value);
+            }
+            if !self.token_iter.catch([Comma]) {
+                self.token_iter.consume(RightParen, "".to_string())?;
+                break;
+            }
+        }
+        Ok(Type::Call(Box::new(Call {
+            func: Box::new(func),
+            args,
+            keywords,
+        })))
+    }
+    /// 切片中省略的部分返回None
+    fn slice_part(&mut self) -> Result<Option<Box<Type>>, ErrorType> {
+        if [COLON, Comma, RightBracket].into_iter().any(|x| self.token_iter.check(x)) {
+            return Ok(None);
+        }
+        Ok(Some(Box::new(self.expression()?)))
+    }
+    /// 下标中的一项，可以是切片`a:b:c`
+    fn subscript_item(&mut self) -> Result<Type, ErrorType> {
+        let lower = self.slice_part()?;
+        if !self.token_iter.catch([COLON]) {
+            return match lower {
+                Some(x) => Ok(*x),
+                None => Err(self.return_err()),
+            };
+        }
+        let upper = self.slice_part()?;
+        let step = if self.token_iter.catch([COLON]) { self.slice_part()? } else { None };
+        Ok(Type::Slice(Box::new(Slice { lower, upper, step })))
+    }
+    /// 下标`value[...]`，逗号分隔的多个下标组成元组
+    fn finish_subscript(&mut self, value: Type) -> Result<Type, ErrorType> {
+        let mut items = vec![self.subscript_item()?];
+        let mut is_tuple = false;
+        while self.token_iter.catch([Comma]) {
+            is_tuple = true;
+            if self.token_iter.check(RightBracket) {
+                break;
+            }
+            items.push(self.subscript_item()?);
+        }
+        self.token_iter.consume(RightBracket, "".to_string())?;
+        let slice = match is_tuple {
+            true => Type::Tuple(Box::new(Tuple {
+                elts: items,
+                ctx: PyCtx::Load,
+            })),
+            false => items.pop().unwrap(),
+        };
+        Ok(Type::Subscript(Box::new(Subscript {
+            value: Box::new(value),
+            slice: Box::new(slice),
+            ctx: PyCtx::Load,
+        })))
+    }
+    /// 调用、属性访问和下标，如`a.b(c)[d].e`
+    fn call(&mut self) -> Result<Type, ErrorType> {
+        let mut expr = self.primary()?;
+        while self.token_iter.catch([LeftParen, Dot, LeftBracket]) {
+            expr = match self.token_iter.previous(1).token_type {
+                LeftParen => self.finish_call(expr)?,
+                LeftBracket => self.finish_subscript(expr)?,
+                _ => Type::Attribute(Attribute {
+                    value: Box::new(expr),
+                    attr: self.identifier_name()?,
+                    ctx: PyCtx::Load,
+                }),
+            };
+        }
+        Ok(expr)
+    }
+    /// `**`右结合，比左边的一元运算符优先级高，比右边的低，所以`-2**-1`是`-(2**(-1))`
+    fn power(&mut self) -> Result<Type, ErrorType> {
+        let expr = self.call()?;
+        if self.token_iter.catch([Pow]) {
+            let right = self.unary()?;
+            return Ok(Type::BinOp(BinOp {
+                left: Box::new(expr),
+                op: Operator::Pow,
+                right: Box::new(right),
+            }));
+        }
+        Ok(expr)
+    }
     fn unary(&mut self) -> Result<Type, ErrorType> {
-        if self.token_iter.catch([Minus, Plus]) {
+        if self.token_iter.catch([Minus, Plus, Tilde]) {
             let token = match self.token_iter.previous(1).token_type {
                 Plus => Operator::UAdd,
+                Tilde => Operator::Invert,
                 _ => Operator::USub,
             };
             let operand = self.unary()?;
@@ -64,45 +452,57 @@ impl Parser {
                 operand: Box::new(operand),
             }));
         }
-        let primary = self.primary()?;
-        return Ok(primary);
+        self.power()
     }
-    fn factor(&mut self) -> Result<Type, ErrorType> {
-        let expr: Type = self.unary()?;
-        //println!("{:?}", expr);
-        while self.token_iter.catch([Star, Slash]) {
-            let token = match self.token_iter.previous(1).token_type {
-                Star => Operator::Mult,
-                _ => Operator::Div,
-            };
-            let right = self.factor()?;
-            return Ok(Type::BinOp(BinOp {
+    /// 左结合的二元运算，operand是优先级高一级的规则
+    fn left_assoc(
+        &mut self,
+        operators: &[(TokenType, Operator)],
+        operand: fn(&mut Parser) -> Result<Type, ErrorType>,
+    ) -> Result<Type, ErrorType> {
+        let mut expr = operand(self)?;
+        while let Some((_, op)) = operators.iter().find(|(token, _)| self.token_iter.catch([*token])) {
+            let right = operand(self)?;
+            expr = Type::BinOp(BinOp {
                 left: Box::new(expr),
-                op: token,
+                op: op.clone(),
                 right: Box::new(right),
-            }));
+            });
         }
         Ok(expr)
     }
+    fn factor(&mut self) -> Result<Type, ErrorType> {
+        self.left_assoc(
+            &[
+                (Star, Operator::Mult),
+                (Slash, Operator::Div),
+                (ExactDivision, Operator::FloorDiv),
+                (Mod, Operator::Mod),
+                (At, Operator::MatMult),
+            ],
+            Parser::unary,
+        )
+    }
     fn term(&mut self) -> Result<Type, ErrorType> {
-        let expr: Type = self.factor()?;
-        //println!("{:?}", expr);
-        while self.token_iter.catch([Minus, Plus]) {
-            let token = match self.token_iter.previous(1).token_type {
-                Minus => Operator::Sub,
-                _ => Operator::Add,
-            };
-            let right = self.term()?;
-            return Ok(Type::BinOp(BinOp {
-                left: Box::new(expr),
-                op: token,
-                right: Box::new(right),
-            }));
-        }
-        Ok(expr)
+        self.left_assoc(&[(Plus, Operator::Add), (Minus, Operator::Sub)], Parser::factor)
+    }
+    fn shift(&mut self) -> Result<Type, ErrorType> {
+        self.left_assoc(&[(LeftShift, Operator::LShift), (RightShift, Operator::RShift)], Parser::term)
     }
+    fn bit_and(&mut self) -> Result<Type, ErrorType> {
+        self.left_assoc(&[(Ampersand, Operator::BitAnd)], Parser::shift)
+    }
+    fn bit_xor(&mut self) -> Result<Type, ErrorType> {
+        self.left_assoc(&[(Caret, Operator::BitXor)], Parser::bit_and)
+    }
+    fn bit_or(&mut self) -> Result<Type, ErrorType> {
+        self.left_assoc(&[(Pipe, Operator::BitOr)], Parser::bit_xor)
+    }
+    /// 比较运算可以连写，`a < b < c`的comparators是`[b, c]`
     fn comparison(&mut self) -> Result<Type, ErrorType> {
-        let expr: Type = self.term()?;
+        let expr: Type = self.bit_or()?;
+        let mut ops: Vec<Operator> = vec![];
+        let mut comparators: Vec<Type> = vec![];
         while self.token_iter.catch([
             BangEqual,
             EqualEqual,
@@ -111,6 +511,7 @@ impl Parser {
             LESS,
             GREATER,
         ]) || self.token_iter.catch_multi([[NOT, In], [Is, NOT]])
+            || self.token_iter.catch([In, Is])
         {
             let token = match self.token_iter.previous(1).token_type {
                 BangEqual => Operator::NotEq,
@@ -129,67 +530,84 @@ impl Parser {
                 NOT => Operator::IsNot,
                 _ => Operator::Gt,
             };
-            let comparator = self.comparison()?;
-            let mut comparators: Vec<Type> = vec![];
-            let mut ops: Vec<Operator> = vec![token];
-            match comparator {
-                Type::Compare(compare) => {
-                    comparators.push(*compare.left);
-                    comparators.extend(compare.comparators.into_iter().clone());
-                    ops.extend(compare.ops.into_iter().clone())
-                }
-                _ => comparators.push(comparator),
-            }
-            return Ok(Type::Compare(Compare {
-                left: Box::new(expr),
-                ops,
-                comparators: Box::from(comparators),
-            }));
+            ops.push(token);
+            comparators.push(self.bit_or()?);
         }
-        Ok(expr)
+        if ops.is_empty() {
+            return Ok(expr);
+        }
+        Ok(Type::Compare(Compare {
+            left: Box::new(expr),
+            ops,
+            comparators,
+        }))
     }
     fn not_operate(&mut self) -> Result<Type, ErrorType> {
         if self.token_iter.catch([NOT]) {
-            let token = match self.token_iter.previous(1).token_type {
-                NOT => Not,
-                _ => panic!("Error to parser"),
-            };
             let operand = self.not_operate()?;
             return Ok(Type::UnaryOp(UnaryOp {
-                op: token,
+                op: Not,
                 operand: Box::new(operand),
             }));
         }
-        let comparison = self.comparison()?;
-        return Ok(comparison);
+        self.comparison()
     }
-    fn bool_operate(&mut self) -> Result<Type, ErrorType> {
-        let expr = self.not_operate()?;
-        while self.token_iter.catch([AND, OR]) {
-            let operator = match self.token_iter.previous(1).token_type {
-                AND => Operator::And,
-                _ => Operator::Or,
-            };
-            let mut values: Vec<Type> = vec![expr];
-            let value = self.bool_operate()?;
-            match value {
-                Type::BoolOp(ref v) => {
-                    if v.op == operator {
-                        values.extend(v.clone().values.into_iter().clone());
-                    } else {
-                        values.push(value.clone())
-                    }
-                }
-                _ => values.push(value),
-            }
-            return Ok(Type::BoolOp(BoolOp {
-                op: operator,
-                values: Box::new(values),
-            }));
+    /// `and`和`or`的操作数合并到同一个BoolOp中，`and`的优先级更高
+    fn bool_chain(
+        &mut self,
+        token: TokenType,
+        op: Operator,
+        operand: fn(&mut Parser) -> Result<Type, ErrorType>,
+    ) -> Result<Type, ErrorType> {
+        let mut values: Vec<Type> = vec![operand(self)?];
+        while self.token_iter.catch([token]) {
+            values.push(operand(self)?);
         }
-        Ok(expr)
+        if values.len() == 1 {
+            return Ok(values.remove(0));
+        }
+        Ok(Type::BoolOp(BoolOp { op, values }))
+    }
+    fn and_operate(&mut self) -> Result<Type, ErrorType> {
+        self.bool_chain(AND, Operator::And, Parser::not_operate)
+    }
+    fn bool_operate(&mut self) -> Result<Type, ErrorType> {
+        self.bool_chain(OR, Operator::Or, Parser::and_operate)
+    }
+    fn lambda(&mut self) -> Result<Type, ErrorType> {
+        let args = self.parameters(COLON)?;
+        let body = self.expression()?;
+        Ok(Type::Lambda(Box::new(Lambda {
+            args,
+            body: Box::new(body),
+        })))
     }
     pub(crate) fn expression(&mut self) -> Result<Type, ErrorType> {
-        self.bool_operate()
+        if self.token_iter.catch([LAMBDA]) {
+            return self.lambda();
+        }
+        let body = self.bool_operate()?;
+        if !self.token_iter.catch([IF]) {
+            return Ok(body);
+        }
+        // body if test else orelse
+        let test = self.bool_operate()?;
+        if !self.token_iter.catch([ELSE]) {
+            return Err(self.syntax_error(&self.token_iter.peek(), "invalid syntax"));
+        }
+        let orelse = self.expression()?;
+        Ok(Type::IfExp(Box::new(IfExp {
+            test: Box::new(test),
+            body: Box::new(body),
+            orelse: Box::new(orelse),
+        })))
+    }
+}
+/// 字符串与f-string拼接时，普通字符串中的花括号需要转义
+fn format_part(literal: Literal) -> String {
+    match literal {
+        Literal::Str(x) => x.replace('{', "{{").replace('}', "}}"),
+        Literal::FormatStr(x) => x,
+        _ => "".to_string(),
     }
 }
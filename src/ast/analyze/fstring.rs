@@ -0,0 +1,174 @@
+use crate::ast::analyze::ast_analyze::Parser;
+use crate::ast::ast_struct::{Constant, FormattedValue, JoinedStr, Type};
+use crate::ast::data_type::str::obj_str;
+use crate::ast::error::parser_error::ParserError;
+use crate::ast::error::{BasicError, ErrorType};
+use crate::ast::scanner::TokenType::NEWLINE;
+use crate::ast::scanner::{build_scanner, Token};
+
+fn fstring_error(token: &Token) -> ErrorType {
+    ParserError::new(
+        BasicError::default()
+            .lineno(token.lineno as u64)
+            .col_offset(token.col_offset as u64)
+            .lexeme(token.lexeme.clone()),
+    ).into()
+}
+
+fn push_literal(values: &mut Vec<Type>, literal: &mut String) {
+    if !literal.is_empty() {
+        values.push(Type::Constant(Constant::new(obj_str(literal.clone()))));
+        literal.clear();
+    }
+}
+
+impl Parser {
+    /// ## fn format_string
+    /// 将f-string的内容解析为JoinedStr，body中的转义字符已经处理过
+    pub(crate) fn format_string(&mut self, body: String, token: &Token) -> Result<Type, ErrorType> {
+        let chars: Vec<char> = body.chars().collect();
+        let mut index = 0;
+        let values = self.format_values(&chars, &mut index, false, token)?;
+        if index < chars.len() {
+            return Err(fstring_error(token));
+        }
+        Ok(Type::JoinedStr(JoinedStr { values }))
+    }
+    /// 解析字符串常量和替换字段，nested为true时解析的是格式说明符，遇到'}'结束
+    fn format_values(
+        &mut self,
+        chars: &[char],
+        index: &mut usize,
+        nested: bool,
+        token: &Token,
+    ) -> Result<Vec<Type>, ErrorType> {
+        let mut values = vec![];
+        let mut literal = String::new();
+        while *index < chars.len() {
+            match chars[*index] {
+                '{' if !nested && chars.get(*index + 1) == Some(&'{') => {
+                    literal.push('{');
+                    *index += 2;
+                }
+                '{' => {
+                    push_literal(&mut values, &mut literal);
+                    *index += 1;
+                    values.extend(self.format_field(chars, index, token)?);
+                }
+                '}' if nested => break,
+                '}' => {
+                    // f-string: single '}' is not allowed
+                    if chars.get(*index + 1) != Some(&'}') {
+                        return Err(fstring_error(token));
+                    }
+                    literal.push('}');
+                    *index += 2;
+                }
+                x => {
+                    literal.push(x);
+                    *index += 1;
+                }
+            }
+        }
+        push_literal(&mut values, &mut literal);
+        Ok(values)
+    }
+    /// 解析一个替换字段：{expression[=][!conversion][:format_spec]}
+    fn format_field(
+        &mut self,
+        chars: &[char],
+        index: &mut usize,
+        token: &Token,
+    ) -> Result<Vec<Type>, ErrorType> {
+        let start = *index;
+        let mut depth = 0;
+        let mut quote: Option<char> = None;
+        loop {
+            let char = match chars.get(*index) {
+                None => return Err(fstring_error(token)),
+                Some(x) => *x,
+            };
+            let next = chars.get(*index + 1).copied();
+            if let Some(x) = quote {
+                if char == x {
+                    quote = None;
+                }
+                *index += 1;
+                continue;
+            }
+            match char {
+                '\'' | '"' => quote = Some(char),
+                '(' | '[' | '{' => depth += 1,
+                '}' if depth == 0 => break,
+                ')' | ']' | '}' if depth == 0 => return Err(fstring_error(token)),
+                ')' | ']' | '}' => depth -= 1,
+                ':' if depth == 0 => break,
+                // ==, !=, <= and >= are operators, not a conversion or a debug '='
+                '=' | '!' | '<' | '>' if next == Some('=') => {
+                    *index += 2;
+                    continue;
+                }
+                '!' | '=' if depth == 0 => break,
+                _ => {}
+            }
+            *index += 1;
+        }
+        let expression: String = chars[start..*index].iter().collect();
+        if expression.trim().is_empty() {
+            // f-string: valid expression required
+            return Err(fstring_error(token));
+        }
+        let mut values = vec![];
+        // f'{x = }' keeps the expression text and the whitespace around '='
+        let debug = chars[*index] == '=';
+        if debug {
+            let mut text = expression.clone() + "=";
+            *index += 1;
+            while let Some(x) = chars.get(*index).filter(|x| x.is_whitespace()) {
+                text.push(*x);
+                *index += 1;
+            }
+            values.push(Type::Constant(Constant::new(obj_str(text))));
+        }
+        let mut conversion = None;
+        if chars.get(*index) == Some(&'!') {
+            conversion = match chars.get(*index + 1) {
+                Some(x) if ['s', 'r', 'a'].contains(x) => Some(*x),
+                _ => return Err(fstring_error(token)),
+            };
+            *index += 2;
+        }
+        let mut format_spec = None;
+        if chars.get(*index) == Some(&':') {
+            *index += 1;
+            let spec = self.format_values(chars, index, true, token)?;
+            format_spec = Some(Box::new(Type::JoinedStr(JoinedStr { values: spec })));
+        }
+        if chars.get(*index) != Some(&'}') {
+            return Err(fstring_error(token));
+        }
+        *index += 1;
+        if debug && conversion.is_none() && format_spec.is_none() {
+            conversion = Some('r');
+        }
+        // the expression is parsed in parentheses, as CPython does
+        let mut scanner = build_scanner(format!("({})", expression));
+        if let Err(errors) = scanner.scan() {
+            return Err(errors[0].clone().into());
+        }
+        // a yield in the replacement field belongs to the enclosing function
+        let mut parser = Parser::default().tokens(scanner.token);
+        parser.in_function = self.in_function;
+        let value = parser.expression()?;
+        self.generator |= parser.generator;
+        if parser.token_iter.peek().token_type != NEWLINE {
+            return Err(fstring_error(token));
+        }
+        values.push(Type::FormattedValue(FormattedValue {
+            value: Box::new(value),
+            conversion,
+            format_spec,
+        }));
+        Ok(values)
+    }
+}
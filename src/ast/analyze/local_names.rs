@@ -0,0 +1,69 @@
+use crate::ast::ast_struct::{Arguments, Type};
+
+/// ## fn local_names
+/// 函数的局部变量：形参和函数体中赋值、删除、for、with、except、import、def、class绑定的名字。
+/// 嵌套的函数、类、lambda和推导式有自己的作用域，不在其中查找
+pub(crate) fn local_names(args: &Arguments, body: &[Type]) -> Vec<String> {
+    let mut names: Vec<String> = args.posonlyargs.iter().chain(&args.args).cloned().collect();
+    names.extend(args.vararg.iter().chain(&args.kwonlyargs).chain(&args.kwarg).cloned());
+    suite(body, &mut names);
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn suite(body: &[Type], names: &mut Vec<String>) {
+    for statement in body {
+        match statement {
+            Type::Assign(x) => x.targets.iter().for_each(|x| target(x, names)),
+            Type::AugAssign(x) => target(&x.target, names),
+            Type::Delete(x) => x.targets.iter().for_each(|x| target(x, names)),
+            Type::If(x) => {
+                suite(&x.body, names);
+                suite(&x.orelse, names);
+            }
+            Type::While(x) => {
+                suite(&x.body, names);
+                suite(&x.orelse, names);
+            }
+            Type::For(x) => {
+                target(&x.target, names);
+                suite(&x.body, names);
+                suite(&x.orelse, names);
+            }
+            Type::Try(x) => {
+                suite(&x.body, names);
+                for handler in x.handlers.iter() {
+                    names.extend(handler.name.clone());
+                    suite(&handler.body, names);
+                }
+                suite(&x.orelse, names);
+                suite(&x.finalbody, names);
+            }
+            Type::With(x) => {
+                x.items.iter().flat_map(|x| &x.optional_vars).for_each(|x| target(x, names));
+                suite(&x.body, names);
+            }
+            // import a.b binds the top-level package a
+            Type::Import(x) => names.extend(x.names.iter().map(|x| match &x.asname {
+                Some(x) => x.clone(),
+                None => x.name.split('.').next().unwrap_or_default().to_string(),
+            })),
+            Type::ImportFrom(x) => names.extend(x.names.iter().map(|x| x.asname.clone().unwrap_or(x.name.clone()))),
+            Type::FunctionDef(x) => names.push(x.name.clone()),
+            Type::ClassDef(x) => names.push(x.name.clone()),
+            _ => {}
+        }
+    }
+}
+
+/// 赋值或删除的目标中的名字，属性和下标不绑定名字
+fn target(target: &Type, names: &mut Vec<String>) {
+    match target {
+        Type::Name(x) => names.push(x.id.clone()),
+        Type::Tuple(x) => x.elts.iter().for_each(|x| self::target(x, names)),
+        Type::List(x) => x.elts.iter().for_each(|x| self::target(x, names)),
+        Type::Starred(x) => self::target(&x.value, names),
+        _ => {}
+    }
+}
@@ -1,2 +1,5 @@
 pub mod ast_analyze;
 mod expression;
+mod fstring;
+mod local_names;
+mod yield_lowering;
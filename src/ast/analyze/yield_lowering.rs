@@ -0,0 +1,409 @@
+use crate::ast::ast_struct::{
+    Assign, BinOp, BoolOp, Call, Compare, Constant, Dict, FormattedValue, If, JoinedStr, Keyword, List, Name,
+    Operator, Print, PyCtx, Raise, Return, Set, Slice, Starred, Subscript, Try, Tuple, Type, UnaryOp, While, With,
+    Yield, YieldFrom,
+};
+use crate::ast::data_type::bool::obj_bool;
+
+/// ## struct YieldLowering
+/// 把生成器函数体中嵌套在表达式里的yield提到语句的层次，如`x = 1 + (yield 2)`变成
+/// `$yield0 = yield 2`和`x = 1 + $yield0`，执行生成器时yield只会是整条语句或赋值的右边。
+/// 在yield之前计算的部分也先保存到临时变量中，计算顺序不变
+#[derive(Default)]
+pub(crate) struct YieldLowering {
+    temps: usize,
+}
+
+/// 表达式中是否有yield，lambda和推导式有自己的作用域，不在其中查找
+fn has_yield(expr: &Type) -> bool {
+    match expr {
+        Type::Yield(_) | Type::YieldFrom(_) => true,
+        Type::BinOp(x) => has_yield(&x.left) || has_yield(&x.right),
+        Type::UnaryOp(x) => has_yield(&x.operand),
+        Type::Compare(x) => has_yield(&x.left) || x.comparators.iter().any(has_yield),
+        Type::BoolOp(x) => x.values.iter().any(has_yield),
+        Type::IfExp(x) => has_yield(&x.test) || has_yield(&x.body) || has_yield(&x.orelse),
+        Type::Attribute(x) => has_yield(&x.value),
+        Type::Subscript(x) => has_yield(&x.value) || has_yield(&x.slice),
+        Type::Slice(x) => [&x.lower, &x.upper, &x.step].into_iter().flatten().any(|x| has_yield(x)),
+        Type::Tuple(x) => x.elts.iter().any(has_yield),
+        Type::List(x) => x.elts.iter().any(has_yield),
+        Type::Set(x) => x.elts.iter().any(has_yield),
+        Type::Dict(x) => x.keys.iter().flatten().any(has_yield) || x.values.iter().any(has_yield),
+        Type::JoinedStr(x) => x.values.iter().any(has_yield),
+        Type::FormattedValue(x) => has_yield(&x.value) || x.format_spec.as_deref().is_some_and(has_yield),
+        Type::Call(x) => {
+            has_yield(&x.func) || x.args.iter().any(has_yield) || x.keywords.iter().any(|x| has_yield(&x.value))
+        }
+        Type::Starred(x) => has_yield(&x.value),
+        _ => false,
+    }
+}
+
+fn store(id: &str, value: Type) -> Type {
+    Type::Assign(Box::new(Assign {
+        targets: vec![Type::Name(Name {
+            id: id.to_string(),
+            ctx: PyCtx::Store,
+        })],
+        value: Box::new(value),
+        type_comment: "".to_string(),
+    }))
+}
+
+fn load(id: &str) -> Type {
+    Type::Name(Name {
+        id: id.to_string(),
+        ctx: PyCtx::Load,
+    })
+}
+
+impl YieldLowering {
+    /// 语句块中每条语句的yield都放到这条语句之前
+    pub(crate) fn suite(&mut self, body: Vec<Type>) -> Vec<Type> {
+        let mut suite = vec![];
+        for statement in body {
+            self.statement(statement, &mut suite);
+        }
+        suite
+    }
+    fn statement(&mut self, statement: Type, suite: &mut Vec<Type>) {
+        let statement = match statement {
+            Type::Assign(x) => {
+                let value = Box::new(self.assigned(*x.value, suite));
+                Type::Assign(Box::new(Assign { value, ..*x }))
+            }
+            Type::AugAssign(mut x) => {
+                x.value = Box::new(self.assigned(*x.value, suite));
+                Type::AugAssign(x)
+            }
+            Type::Return(x) => Type::Return(Box::new(Return {
+                value: x.value.map(|x| Box::new(self.expression(*x, suite))),
+            })),
+            Type::Print(x) => Type::Print(Box::new(Print {
+                arg: Box::new(self.expression(*x.arg, suite)),
+            })),
+            Type::Raise(x) => {
+                let (exc, cause) = (x.exc.map(|x| *x), x.cause.map(|x| *x));
+                let mut values = self.operands(exc.iter().chain(&cause).cloned().collect(), suite).into_iter();
+                Type::Raise(Box::new(Raise {
+                    exc: exc.map(|_| Box::new(values.next().unwrap())),
+                    cause: cause.map(|_| Box::new(values.next().unwrap())),
+                }))
+            }
+            Type::If(x) => Type::If(Box::new(If {
+                test: Box::new(self.expression(*x.test, suite)),
+                body: self.suite(x.body),
+                orelse: self.suite(x.orelse),
+            })),
+            Type::While(x) if has_yield(&x.test) => {
+                // the test is evaluated again before every iteration, so it moves into the body:
+                // while True: if not test: orelse; break
+                let mut body = vec![];
+                let test = self.expression(*x.test, &mut body);
+                let mut orelse = self.suite(x.orelse);
+                orelse.push(Type::Break);
+                body.push(Type::If(Box::new(If {
+                    test: Box::new(Type::UnaryOp(UnaryOp {
+                        op: Operator::Not,
+                        operand: Box::new(test),
+                    })),
+                    body: orelse,
+                    orelse: vec![],
+                })));
+                body.extend(self.suite(x.body));
+                Type::While(Box::new(While {
+                    test: Box::new(Type::Constant(Constant::new(obj_bool(true)))),
+                    body,
+                    orelse: vec![],
+                }))
+            }
+            Type::While(x) => Type::While(Box::new(While {
+                test: x.test,
+                body: self.suite(x.body),
+                orelse: self.suite(x.orelse),
+            })),
+            Type::For(mut x) => {
+                x.iter = Box::new(self.expression(*x.iter, suite));
+                x.body = self.suite(x.body);
+                x.orelse = self.suite(x.orelse);
+                Type::For(x)
+            }
+            Type::Try(x) => {
+                let mut handlers = x.handlers;
+                for handler in handlers.iter_mut() {
+                    handler.body = self.suite(std::mem::take(&mut handler.body));
+                }
+                Type::Try(Box::new(Try {
+                    body: self.suite(x.body),
+                    handlers,
+                    orelse: self.suite(x.orelse),
+                    finalbody: self.suite(x.finalbody),
+                }))
+            }
+            Type::With(x) => self.with(*x, suite),
+            x => self.assigned(x, suite),
+        };
+        suite.push(statement);
+    }
+    /// with中第一个之后的上下文管理器在进入前面的之后才计算，有yield时拆成嵌套的with语句
+    fn with(&mut self, mut with: With, suite: &mut Vec<Type>) -> Type {
+        let body = match with.items.iter().skip(1).position(|x| has_yield(&x.context_expr)) {
+            Some(i) => {
+                let items = with.items.split_off(i + 1);
+                self.suite(vec![Type::With(Box::new(With { items, body: with.body }))])
+            }
+            None => self.suite(with.body),
+        };
+        let first = &mut with.items[0];
+        first.context_expr = self.expression(first.context_expr.clone(), suite);
+        Type::With(Box::new(With { items: with.items, body }))
+    }
+    /// 表达式语句和赋值的右边，yield本身可以留在这里
+    fn assigned(&mut self, value: Type, suite: &mut Vec<Type>) -> Type {
+        match value {
+            Type::Yield(x) => Type::Yield(Box::new(Yield {
+                value: x.value.map(|x| Box::new(self.expression(*x, suite))),
+            })),
+            Type::YieldFrom(x) => Type::YieldFrom(Box::new(YieldFrom {
+                value: Box::new(self.expression(*x.value, suite)),
+            })),
+            x => self.expression(x, suite),
+        }
+    }
+    /// ## fn expression
+    /// 返回不再含有yield的表达式，yield和在它之前计算的部分作为赋值语句加到suite中
+    fn expression(&mut self, expr: Type, suite: &mut Vec<Type>) -> Type {
+        if !has_yield(&expr) {
+            return expr;
+        }
+        match expr {
+            Type::Yield(_) | Type::YieldFrom(_) => {
+                let value = self.assigned(expr, suite);
+                self.hold(value, suite)
+            }
+            Type::BinOp(x) => {
+                let [left, right] = self.operand_array([*x.left, *x.right], suite);
+                Type::BinOp(BinOp {
+                    left: Box::new(left),
+                    op: x.op,
+                    right: Box::new(right),
+                })
+            }
+            Type::UnaryOp(x) => Type::UnaryOp(UnaryOp {
+                op: x.op,
+                operand: Box::new(self.expression(*x.operand, suite)),
+            }),
+            Type::Compare(x) => self.compare(*x.left, x.ops, x.comparators, suite),
+            Type::BoolOp(x) => self.bool_op(x.op, x.values, suite),
+            Type::IfExp(x) => {
+                let test = self.expression(*x.test, suite);
+                let id = self.temp();
+                let mut branches = [*x.body, *x.orelse].map(|value| {
+                    let mut branch = vec![];
+                    let value = self.expression(value, &mut branch);
+                    branch.push(store(&id, value));
+                    branch
+                });
+                suite.push(Type::If(Box::new(If {
+                    test: Box::new(test),
+                    body: std::mem::take(&mut branches[0]),
+                    orelse: std::mem::take(&mut branches[1]),
+                })));
+                load(&id)
+            }
+            Type::Attribute(mut x) => {
+                x.value = Box::new(self.expression(*x.value, suite));
+                Type::Attribute(x)
+            }
+            Type::Subscript(x) => {
+                let [value, slice] = self.operand_array([*x.value, *x.slice], suite);
+                Type::Subscript(Box::new(Subscript {
+                    value: Box::new(value),
+                    slice: Box::new(slice),
+                    ctx: x.ctx,
+                }))
+            }
+            Type::Slice(x) => {
+                let parts = [x.lower, x.upper, x.step];
+                let present = parts.iter().flatten().map(|x| *x.clone()).collect();
+                let mut values = self.operands(present, suite).into_iter();
+                let [lower, upper, step] = parts.map(|x| x.map(|_| Box::new(values.next().unwrap())));
+                Type::Slice(Box::new(Slice { lower, upper, step }))
+            }
+            Type::Tuple(x) => Type::Tuple(Box::new(Tuple {
+                elts: self.operands(x.elts, suite),
+                ctx: x.ctx,
+            })),
+            Type::List(x) => Type::List(Box::new(List {
+                elts: self.operands(x.elts, suite),
+                ctx: x.ctx,
+            })),
+            Type::Set(x) => Type::Set(Box::new(Set {
+                elts: self.operands(x.elts, suite),
+            })),
+            Type::Dict(x) => {
+                // keys and values are evaluated alternately, k1, v1, k2, v2
+                let items: Vec<(Option<Type>, Type)> = x.keys.into_iter().zip(x.values).collect();
+                let flat = items.iter().flat_map(|(k, v)| k.iter().chain([v])).cloned().collect();
+                let mut values = self.operands(flat, suite).into_iter();
+                let (keys, values) = items
+                    .into_iter()
+                    .map(|(k, _)| (k.map(|_| values.next().unwrap()), values.next().unwrap()))
+                    .unzip();
+                Type::Dict(Box::new(Dict { keys, values }))
+            }
+            Type::JoinedStr(x) => Type::JoinedStr(JoinedStr {
+                values: self.operands(x.values, suite),
+            }),
+            Type::FormattedValue(x) => {
+                let present = [*x.value].into_iter().chain(x.format_spec.map(|x| *x)).collect();
+                let mut values = self.operands(present, suite).into_iter();
+                Type::FormattedValue(FormattedValue {
+                    value: Box::new(values.next().unwrap()),
+                    conversion: x.conversion,
+                    format_spec: values.next().map(Box::new),
+                })
+            }
+            Type::Call(x) => {
+                let keywords = x.keywords.iter().map(|x| *x.value.clone());
+                let present = [*x.func].into_iter().chain(x.args.iter().cloned()).chain(keywords).collect();
+                let mut values = self.operands(present, suite).into_iter();
+                let func = Box::new(values.next().unwrap());
+                let args = x.args.iter().map(|_| values.next().unwrap()).collect();
+                let keywords = x
+                    .keywords
+                    .into_iter()
+                    .map(|x| Keyword {
+                        arg: x.arg,
+                        value: Box::new(values.next().unwrap()),
+                    })
+                    .collect();
+                Type::Call(Box::new(Call { func, args, keywords }))
+            }
+            Type::Starred(mut x) => {
+                x.value = Box::new(self.expression(*x.value, suite));
+                Type::Starred(x)
+            }
+            x => x,
+        }
+    }
+    /// ## fn operands
+    /// 按顺序计算的多个表达式，最后一个有yield的表达式之前的都先保存到临时变量中
+    fn operands(&mut self, exprs: Vec<Type>, suite: &mut Vec<Type>) -> Vec<Type> {
+        let last = exprs.iter().rposition(has_yield);
+        exprs
+            .into_iter()
+            .enumerate()
+            .map(|(i, x)| match last {
+                Some(last) if i < last => self.hoist(x, suite),
+                Some(last) if i == last => self.expression(x, suite),
+                _ => x,
+            })
+            .collect()
+    }
+    fn operand_array<const N: usize>(&mut self, exprs: [Type; N], suite: &mut Vec<Type>) -> [Type; N] {
+        self.operands(exprs.into(), suite).try_into().unwrap()
+    }
+    /// 先计算表达式并保存到临时变量中，常量不需要保存，`*x`只保存x
+    fn hoist(&mut self, expr: Type, suite: &mut Vec<Type>) -> Type {
+        match self.expression(expr, suite) {
+            Type::Constant(x) => Type::Constant(x),
+            Type::Starred(x) => Type::Starred(Box::new(Starred {
+                value: Box::new(self.hoist(*x.value, suite)),
+                ctx: x.ctx,
+            })),
+            x => self.hold(x, suite),
+        }
+    }
+    /// ## fn bool_op
+    /// and和or在有yield的值之前短路时不再计算它，改写成if语句，结果仍然是bool
+    fn bool_op(&mut self, op: Operator, mut values: Vec<Type>, suite: &mut Vec<Type>) -> Type {
+        let index = match values.iter().position(has_yield) {
+            Some(0) => {
+                let first = values.remove(0);
+                values.insert(0, self.expression(first, suite));
+                return self.bool_op(op, values, suite);
+            }
+            Some(x) => x,
+            None => {
+                return Type::BoolOp(BoolOp {
+                    op,
+                    values,
+                })
+            }
+        };
+        let rest = values.split_off(index);
+        let head = Type::BoolOp(BoolOp {
+            op: op.clone(),
+            values,
+        });
+        let id = self.temp();
+        suite.push(store(&id, head));
+        let mut body = vec![];
+        let value = self.bool_op(op.clone(), rest, &mut body);
+        body.push(store(&id, value));
+        let test = match op {
+            Operator::Or => Type::UnaryOp(UnaryOp {
+                op: Operator::Not,
+                operand: Box::new(load(&id)),
+            }),
+            _ => load(&id),
+        };
+        suite.push(Type::If(Box::new(If {
+            test: Box::new(test),
+            body,
+            orelse: vec![],
+        })));
+        load(&id)
+    }
+    /// ## fn compare
+    /// 连续比较在前一个比较为假时不再计算后面的值，后面有yield时每一环改写成if语句，中间的值只计算一次
+    fn compare(
+        &mut self,
+        left: Type,
+        mut ops: Vec<Operator>,
+        mut comparators: Vec<Type>,
+        suite: &mut Vec<Type>,
+    ) -> Type {
+        if !comparators.iter().skip(1).any(has_yield) {
+            let mut values = self.operands([left].into_iter().chain(comparators).collect(), suite);
+            let left = values.remove(0);
+            return Type::Compare(Compare {
+                left: Box::new(left),
+                ops,
+                comparators: values,
+            });
+        }
+        let (rest_ops, rest) = (ops.split_off(1), comparators.split_off(1));
+        let left = self.hoist(left, suite);
+        let right = self.hoist(comparators.remove(0), suite);
+        let id = self.temp();
+        let head = Type::Compare(Compare {
+            left: Box::new(left),
+            ops,
+            comparators: vec![right.clone()],
+        });
+        suite.push(store(&id, head));
+        let mut body = vec![];
+        let value = self.compare(right, rest_ops, rest, &mut body);
+        body.push(store(&id, value));
+        suite.push(Type::If(Box::new(If {
+            test: Box::new(load(&id)),
+            body,
+            orelse: vec![],
+        })));
+        load(&id)
+    }
+    /// 把值赋给新的临时变量，返回读取它的表达式
+    fn hold(&mut self, value: Type, suite: &mut Vec<Type>) -> Type {
+        let id = self.temp();
+        suite.push(store(&id, value));
+        load(&id)
+    }
+    /// 临时变量的名字以$开头，不会和源码中的名字冲突
+    fn temp(&mut self) -> String {
+        self.temps += 1;
+        format!("$yield{}", self.temps - 1)
+    }
+}
@@ -1,18 +1,43 @@
-use std::collections::HashMap;
 use std::fmt::Debug;
+use std::path::Path;
+
+use uuid::Uuid;
 
 use crate::ast::analyze::ast_analyze::build_parser;
-use crate::ast::ast_struct::FuncArgs::ARGS;
+use crate::ast::data_type::attribute::{obj_delattr, obj_getattr, obj_lookup, obj_setattr};
 use crate::ast::data_type::bool::obj_bool;
-use crate::ast::data_type::object::{obj_to_bool, obj_to_str, PyObjAttr, PyObject, PyResult};
-use crate::ast::error::ErrorType;
+use crate::ast::data_type::format::format_obj;
+use crate::ast::data_type::builtin::init_builtins;
+use crate::ast::data_type::class::{class_of, obj_class, type_of, PyClass};
+use crate::ast::data_type::dict::{dict_set, obj_dict_shared};
+use crate::ast::data_type::exception::{
+    exception_data, exception_matches, handled_exception, obj_syntax_error, raise, raise_exception, to_exception,
+    traceback, Exec, Handling, PyException, SyntaxLocation,
+};
+use crate::ast::data_type::file::PyFile;
+use crate::ast::data_type::module::{import_from, import_module, init_modules, public_names, resolve_name, search_path, str_value, PyModule};
+use crate::ast::data_type::iterator::{obj_iter, obj_iterate, obj_iterator, obj_next, IterState};
+use crate::ast::data_type::list::obj_list;
+use crate::ast::data_type::none::{is_not_implemented, obj_none};
+use crate::ast::data_type::function::{call_object, obj_function, PyFunction};
+use crate::ast::data_type::generator::PyGenerator;
+use crate::ast::data_type::signature::Signature;
+use crate::ast::data_type::object::{
+    obj_to_ascii, obj_to_bool, obj_to_repr, obj_to_str, PyObjAttr, PyObject, PyResult, Shared,
+};
+use crate::ast::data_type::set::{build_set, obj_set, set_add};
+use crate::ast::data_type::slice::obj_slice;
+use crate::ast::data_type::str::obj_str;
+use crate::ast::data_type::subscript::{obj_delitem, obj_getitem, obj_setitem};
+use crate::ast::data_type::tuple::obj_tuple;
 use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::ast::error::ErrorType;
 use crate::ast::scanner::build_scanner;
 
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct PyRootNode {
-    pub body: Vec<Box<Type>>,
+    pub body: Vec<Type>,
     pub py_root_env: PyNamespace,
     pub lineno: usize,
     pub end_lineno: usize,
@@ -21,14 +46,12 @@ pub struct PyRootNode {
 }
 impl Default for PyRootNode {
     fn default() -> Self {
+        let mut py_root_env = PyNamespace::default();
+        init_builtins(&mut py_root_env);
+        init_modules(&mut py_root_env);
         PyRootNode {
             body: vec![],
-            py_root_env: PyNamespace {
-                variable_pool: Default::default(),
-                builtin_namespace: HashMap::new(),
-                global_namespace: HashMap::new(),
-                enclosing_namespace: HashMap::new(),
-            },
+            py_root_env,
             lineno: 0,
             end_lineno: 0,
             col_offset: 0,
@@ -37,113 +60,244 @@ impl Default for PyRootNode {
     }
 }
 impl PyRootNode {
-    pub fn exec(&mut self) -> Type {
+    pub fn exec(&mut self) -> Exec<Type> {
         exec_commands(&self.body, &mut self.py_root_env, Namespace::Global)
     }
-    pub fn parser(&mut self, s: String) {
-        let mut scanner = build_scanner(s);
-        scanner.scan();
-        let mut parser = build_parser(scanner, PyNamespace::default());
-        self.body = parser.create_vec()
+    pub fn parser(&mut self, s: String) -> Exec<()> {
+        // the script file set by fn script, code typed in the REPL comes from stdin
+        let filename = match self.py_root_env.get_module_variable("__main__", "__file__") {
+            Some(x) => str_value(&x).unwrap_or_default(),
+            None => "<stdin>".to_string(),
+        };
+        self.body = parse_source(s, filename.as_str())?;
+        Ok(())
+    }
+    /// ## fn traceback
+    /// 没有被捕获的异常的报告，与python打印到stderr的内容相同
+    pub fn traceback(&mut self, exc: &PyObject) -> String {
+        traceback(exc, Namespace::Global, &mut self.py_root_env)
+    }
+    /// ## fn search_path
+    /// 在sys.path的最后添加导入模块时查找的目录，如PYTHONPATH中的目录
+    pub fn search_path(&mut self, dirs: Vec<String>) {
+        if let Ok(x) = search_path(&mut self.py_root_env) {
+            x.borrow_mut().extend(dirs.into_iter().map(obj_str));
+        }
+    }
+    /// ## fn script
+    /// 运行脚本文件时，sys.path的第一项是脚本所在的目录
+    pub fn script(&mut self, file: &Path) {
+        let dir = file.parent().map_or(String::new(), |x| x.to_string_lossy().to_string());
+        if let Ok(x) = search_path(&mut self.py_root_env) {
+            x.borrow_mut()[0] = obj_str(dir);
+        }
+        let file = obj_str(file.to_string_lossy().to_string());
+        self.py_root_env.set_module_variable("__main__", "__file__".to_string(), file);
     }
 }
-fn exec_commands(
-    command: &Vec<Box<Type>>,
+/// ## fn parse_source
+/// 把源代码解析为语句，有词法或语法错误时抛出带有出错位置的SyntaxError，
+/// 有多个词法错误时，之后的错误放在第一个错误的following中一起报告
+pub(crate) fn parse_source(s: String, filename: &str) -> Exec<Vec<Type>> {
+    let mut scanner = build_scanner(s.clone());
+    if let Err(errors) = scanner.scan() {
+        let mut errors = errors.iter().map(|x| {
+            let (lineno, col_offset) = x.position();
+            obj_syntax_error(x.kind_name(), x.message(), SyntaxLocation::new(filename, &s, lineno, col_offset))
+        });
+        let first = errors.next().expect("scan failed without errors");
+        if let Some(x) = exception_data(&first) {
+            x.borrow_mut().following = errors.collect();
+        }
+        return Err(Box::new(first));
+    }
+    let mut parser = build_parser(scanner, PyNamespace::default());
+    parser.create_vec().map_err(|x| match x {
+        ErrorType::ParserError(x) => {
+            let (lineno, col_offset) = x.position();
+            Box::new(obj_syntax_error(
+                x.kind_name(),
+                x.message(),
+                SyntaxLocation::new(filename, &s, lineno, col_offset),
+            ))
+        }
+        x => x.into(),
+    })
+}
+pub(crate) fn exec_commands(
+    command: &[Type],
     namespace: &mut PyNamespace,
     current_namespace: Namespace,
-) -> Type {
+) -> Exec<Type> {
     for (index, item) in command.iter().enumerate() {
-        match item.clone().exec(namespace, current_namespace.clone()) {
+        match item.clone().exec(namespace, current_namespace.clone())? {
             Type::None => {}
-            Type::Constant(x) => {
-                if index + 1 == command.len() {
-                    return Type::Constant(x);
-                }
-            }
-            Type::Break => return Type::Break,
-            Type::Continue => return Type::Continue,
+            Type::Constant(x) if index + 1 == command.len() => return Ok(Type::Constant(x)),
+            Type::Break => return Ok(Type::Break),
+            Type::Continue => return Ok(Type::Continue),
+            Type::Return(x) => return Ok(Type::Return(x)),
             _ => {}
         }
     }
-    Type::None
+    Ok(Type::None)
 }
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub enum Type {
     Assign(Box<Assign>),
+    AugAssign(Box<AugAssign>),
     Constant(Constant),
+    /// 超出i64范围的整数字面量，保留原来的写法，计算时抛出OverflowError
+    LargeInt(String),
     Name(Name),
     BinOp(BinOp),
     Compare(Compare),
     UnaryOp(UnaryOp),
     BoolOp(BoolOp),
+    IfExp(Box<IfExp>),
     Print(Box<Print>),
     Attribute(Attribute),
+    Subscript(Box<Subscript>),
+    Slice(Box<Slice>),
     If(Box<If>),
     While(Box<While>),
+    For(Box<For>),
+    Try(Box<Try>),
+    Raise(Box<Raise>),
+    With(Box<With>),
+    Import(Box<Import>),
+    ImportFrom(Box<ImportFrom>),
+    Tuple(Box<Tuple>),
+    List(Box<List>),
+    Set(Box<Set>),
+    Dict(Box<Dict>),
+    ListComp(Box<ListComp>),
+    SetComp(Box<SetComp>),
+    DictComp(Box<DictComp>),
+    GeneratorExp(Box<GeneratorExp>),
+    Delete(Box<Delete>),
+    JoinedStr(JoinedStr),
+    FormattedValue(FormattedValue),
+    FunctionDef(Box<FunctionDef>),
+    ClassDef(Box<ClassDef>),
+    Lambda(Box<Lambda>),
+    Call(Box<Call>),
+    Starred(Box<Starred>),
+    Return(Box<Return>),
+    Yield(Box<Yield>),
+    YieldFrom(Box<YieldFrom>),
     Break,
     Continue,
     None,
 }
 
 impl Type {
-    pub fn exec(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Type {
-        match self {
-            Type::Assign(x) => x.exec(env, current_namespace),
+    pub fn exec(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Type> {
+        Ok(match self {
+            Type::Assign(x) => x.exec(env, current_namespace)?,
+            Type::AugAssign(x) => x.exec(env, current_namespace)?,
             Type::Constant(x) => Type::Constant(x.clone()),
-            Type::Name(_) => {
-                todo!()
-            }
-            Type::Attribute(_) => {
-                todo!()
-            }
-            Type::BinOp(x) => Type::Constant(x.calc(env, current_namespace)),
-            Type::Compare(x) => Type::Constant(x.calc(env, current_namespace)),
-            Type::UnaryOp(x) => Type::Constant(x.calc(env, current_namespace)),
-            Type::BoolOp(x) => Type::Constant(x.calc(env, current_namespace)),
+            Type::LargeInt(x) => return large_int(x),
+            Type::Name(x) => Type::Constant(x.exec(env, current_namespace)?),
+            Type::Attribute(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::Subscript(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::Slice(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::BinOp(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::Compare(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::UnaryOp(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::BoolOp(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::IfExp(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::JoinedStr(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::FormattedValue(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::Call(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::Lambda(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::Starred(_) => return raise("SyntaxError", "can't use starred expression here"),
+            Type::FunctionDef(x) => x.exec(env, current_namespace)?,
+            Type::ClassDef(x) => x.exec(env, current_namespace)?,
+            Type::Return(x) => x.exec(env, current_namespace)?,
+            // generator bodies are run by generator.rs, which handles the yield itself
+            Type::Yield(_) | Type::YieldFrom(_) => return raise("SyntaxError", "'yield' outside function"),
             Type::Print(x) => {
-                println!(
-                    "{}",
-                    obj_to_str(deref_expression(*x.arg.clone(), env, current_namespace.clone()).value, current_namespace, env)
-                );
+                let value = deref_expression(*x.arg.clone(), env, current_namespace.clone())?.value;
+                println!("{}", obj_to_str(value, current_namespace, env)?);
                 Type::None
             }
-            Type::If(x) => x.exec(env, current_namespace),
-            Type::While(x) =>  x.exec(env, current_namespace),
+            Type::If(x) => x.exec(env, current_namespace)?,
+            Type::While(x) => x.exec(env, current_namespace)?,
+            Type::For(x) => x.exec(env, current_namespace)?,
+            Type::Try(x) => x.exec(env, current_namespace)?,
+            Type::Raise(x) => x.exec(env, current_namespace)?,
+            Type::With(x) => x.exec(env, current_namespace)?,
+            Type::Import(x) => x.exec(env, current_namespace)?,
+            Type::ImportFrom(x) => x.exec(env, current_namespace)?,
+            Type::Delete(x) => x.exec(env, current_namespace)?,
+            Type::Tuple(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::List(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::Set(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::Dict(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::ListComp(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::SetComp(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::DictComp(x) => Type::Constant(x.calc(env, current_namespace)?),
+            Type::GeneratorExp(x) => Type::Constant(x.calc(env, current_namespace)?),
             Type::Break => Type::Break,
             Type::Continue => Type::Continue,
             Type::None => Type::None,
-        }
+        })
     }
 }
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct Assign {
-    pub(crate) target: Box<Type>,
+    pub(crate) targets: Vec<Type>,
     pub(crate) value: Box<Type>,
     pub(crate) type_comment: String,
 }
 impl Assign {
-    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Type {
+    /// 值只计算一次，再从左到右绑定到每个目标上，如`a = b = 0`
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
+        let value = deref_expression(*self.value.clone(), env, namespace.clone())?.value;
+        for target in self.targets.iter() {
+            assign_target(target, value.clone(), env, namespace.clone())?;
+        }
+        Ok(Type::None)
+    }
+}
+/// ## struct AugAssign
+/// 增强赋值`target op= value`，目标中的对象和下标只计算一次
+#[derive(Debug, Clone)]
+pub struct AugAssign {
+    pub(crate) target: Box<Type>,
+    pub(crate) op: Operator,
+    pub(crate) value: Box<Type>,
+}
+impl AugAssign {
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
         match *self.target.clone() {
-            Type::Name(x) => match x.ctx {
-                PyCtx::Store => {
-                    let value = deref_expression(*self.value.clone(), env, namespace.clone());
-                    match namespace {
-                        Namespace::Builtin => {
-                            panic!("You cannot set built variable in code")
-                        }
-                        Namespace::Global => {
-                            env.set_global(x.id, value.value);
-                        }
-                        _ => todo!(),
-                    }
-                }
-                _ => panic!("Error to store name:{}", x.id),
-            },
-            _ => todo!(),
+            Type::Name(x) => {
+                let current = Name { id: x.id.clone(), ctx: PyCtx::Load }.exec(env, namespace.clone())?.value;
+                let result = self.operate(current, env, namespace.clone())?;
+                assign_target(&Type::Name(x), result, env, namespace)?;
+            }
+            Type::Attribute(x) => {
+                let obj = deref_expression(*x.value.clone(), env, namespace.clone())?.value;
+                let current = obj_getattr(&obj, x.attr.as_str(), namespace.clone(), env)?;
+                let result = self.operate(current, env, namespace.clone())?;
+                obj_setattr(&obj, x.attr.as_str(), result, namespace, env)?;
+            }
+            Type::Subscript(x) => {
+                let obj = deref_expression(*x.value.clone(), env, namespace.clone())?.value;
+                let key = deref_expression(*x.slice.clone(), env, namespace.clone())?.value;
+                let current = obj_getitem(&obj, key.clone(), namespace.clone(), env)?;
+                let result = self.operate(current, env, namespace.clone())?;
+                obj_setitem(&obj, key, result, namespace, env)?;
+            }
+            _ => return raise("SyntaxError", "illegal expression for augmented assignment"),
         }
-        Type::None
+        Ok(Type::None)
+    }
+    fn operate(&self, current: PyObject, env: &mut PyNamespace, namespace: Namespace) -> Exec<PyObject> {
+        let value = deref_expression(*self.value.clone(), env, namespace.clone())?.value;
+        inplace_operate(current, value, self.op.clone(), namespace, env)
     }
 }
 #[allow(dead_code)]
@@ -161,34 +315,10 @@ pub struct Name {
 impl Name {
     pub fn ctx(&mut self, ctx: PyCtx) -> Self {
         self.ctx = ctx;
-        return self.clone();
+        self.clone()
     }
-    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Constant {
-        match namespace {
-            Namespace::Builtin => match env.get_builtin(self.id.clone()) {
-                Ok(x) => return Constant::new(x),
-                _ => {}
-            },
-            Namespace::Global => match env.get_global(self.id.clone()) {
-                Ok(x) => return Constant::new(x),
-                _ => {}
-            },
-            Namespace::Enclosing(x) => match env.get_enclosing(x, self.id.clone()) {
-                Ok(x) => return Constant::new(x),
-                _ => {}
-            },
-            Namespace::Local(_) => {
-                todo!()
-            }
-        }
-        match env.get_builtin(self.id.clone()) {
-            Ok(x) => {
-                return Constant::new(x)
-            }
-            Err(x) => {
-                panic!("{}", x)
-            }
-        }
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Constant> {
+        Ok(Constant::new(env.get_variable(namespace, self.id.clone())?))
     }
 }
 #[allow(dead_code)]
@@ -197,21 +327,82 @@ pub struct TestEmuNamespace {
     id: String,
     cmd: Vec<Type>,
 }
-#[allow(dead_code)]
+/// ## struct Attribute
+/// 属性访问`value.attr`，ctx为Store或Del时是赋值或删除的目标
 #[derive(Clone, Debug)]
 pub struct Attribute {
-    value: Name,
-    attr: String,
-    py_ctx: PyCtx,
+    pub value: Box<Type>,
+    pub attr: String,
+    pub ctx: PyCtx,
+}
+impl Calc for Attribute {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        let value = deref_expression(*self.value.clone(), env, current_namespace.clone())?.value;
+        Ok(Constant::new(obj_getattr(&value, self.attr.as_str(), current_namespace, env)?))
+    }
+}
+/// ## struct Subscript
+/// 下标访问`value[slice]`，多个下标时slice是元组
+#[derive(Clone, Debug)]
+pub struct Subscript {
+    pub value: Box<Type>,
+    pub slice: Box<Type>,
+    pub ctx: PyCtx,
+}
+impl Calc for Subscript {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        let value = deref_expression(*self.value.clone(), env, current_namespace.clone())?.value;
+        let key = deref_expression(*self.slice.clone(), env, current_namespace.clone())?.value;
+        Ok(Constant::new(obj_getitem(&value, key, current_namespace, env)?))
+    }
+}
+/// ## struct Slice
+/// 下标中的切片`lower:upper:step`，省略的部分是None
+#[derive(Clone, Debug)]
+pub struct Slice {
+    pub lower: Option<Box<Type>>,
+    pub upper: Option<Box<Type>>,
+    pub step: Option<Box<Type>>,
+}
+impl Calc for Slice {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        let mut parts = vec![];
+        for part in [&self.lower, &self.upper, &self.step] {
+            parts.push(match part {
+                Some(x) => deref_expression(*x.clone(), env, current_namespace.clone())?.value,
+                None => obj_none(),
+            });
+        }
+        let step = parts.pop().unwrap();
+        let stop = parts.pop().unwrap();
+        Ok(Constant::new(obj_slice(parts.pop().unwrap(), stop, step)))
+    }
 }
 #[allow(dead_code)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum DataType {
     Int(i64),
     Float(f64),
+    Complex(f64, f64),
     Bool(bool),
     Str(String),
-    List(Box<Vec<PyObject>>),
+    Bytes(Vec<u8>),
+    List(Shared<Vec<PyObject>>),
+    Tuple(Vec<PyObject>),
+    Dict(Shared<Vec<(PyObject, PyObject)>>),
+    Function(Box<PyFunction>),
+    Class(Box<PyClass>),
+    BuiltinFunction(String),
+    Method(Box<PyObject>, Box<PyObject>),
+    BuiltinMethod(Box<PyObject>, String),
+    Iterator(Shared<IterState>),
+    Range(i64, i64, i64),
+    Slice(Box<PyObject>, Box<PyObject>, Box<PyObject>),
+    Set(Shared<Vec<PyObject>>),
+    Exception(Shared<PyException>),
+    File(Shared<PyFile>),
+    Module(Shared<PyModule>),
+    Generator(Shared<PyGenerator>),
     None,
 }
 #[allow(dead_code)]
@@ -223,10 +414,10 @@ pub struct Constant {
 
 impl Constant {
     pub(crate) fn new(value: PyObject) -> Constant {
-        return Constant {
+        Constant {
             value,
             type_comment: "".to_string(),
-        };
+        }
     }
 }
 #[allow(dead_code)]
@@ -236,9 +427,14 @@ pub enum Operator {
     Sub,
     Mult,
     Div,
+    FloorDiv,
     Mod,
     Pow,
+    LShift,
+    RShift,
     BitAnd,
+    BitOr,
+    BitXor,
     MatMult,
     Eq,
     NotEq,
@@ -247,6 +443,7 @@ pub enum Operator {
     LtE,
     GtE,
     Not,
+    Invert,
     UAdd,
     USub,
     In,
@@ -256,9 +453,42 @@ pub enum Operator {
     And,
     Or,
 }
+impl Operator {
+    /// 二元运算符对应的方法名和报错时显示的符号
+    fn binary_method(&self) -> (&'static str, &'static str) {
+        match self {
+            Operator::Add => ("__add__", "+"),
+            Operator::Sub => ("__sub__", "-"),
+            Operator::Mult => ("__mul__", "*"),
+            Operator::MatMult => ("__matmul__", "@"),
+            Operator::Div => ("__truediv__", "/"),
+            Operator::FloorDiv => ("__floordiv__", "//"),
+            Operator::Mod => ("__mod__", "%"),
+            Operator::Pow => ("__pow__", "** or pow()"),
+            Operator::LShift => ("__lshift__", "<<"),
+            Operator::RShift => ("__rshift__", ">>"),
+            Operator::BitAnd => ("__and__", "&"),
+            Operator::BitXor => ("__xor__", "^"),
+            Operator::BitOr => ("__or__", "|"),
+            _ => panic!("not a binary operator"),
+        }
+    }
+    /// 比较运算符的方法名、反射方法名和报错时显示的符号
+    fn compare_method(&self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            Operator::Eq => ("__eq__", "__eq__", "=="),
+            Operator::NotEq => ("__ne__", "__ne__", "!="),
+            Operator::Lt => ("__lt__", "__gt__", "<"),
+            Operator::Gt => ("__gt__", "__lt__", ">"),
+            Operator::LtE => ("__le__", "__ge__", "<="),
+            Operator::GtE => ("__ge__", "__le__", ">="),
+            _ => panic!("not a compare operator"),
+        }
+    }
+}
 
 pub trait Calc {
-    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Constant;
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant>;
 }
 #[derive(Debug, Clone)]
 pub struct BinOp {
@@ -266,185 +496,247 @@ pub struct BinOp {
     pub op: Operator,
     pub right: Box<Type>,
 }
-fn deref_expression(data: Type, env: &mut PyNamespace, namespace: Namespace) -> Constant {
+/// 整数只有i64的范围，更大的字面量在计算时才报错，和运算溢出一样
+fn large_int<T>(lexeme: &str) -> Exec<T> {
+    raise("OverflowError", format!("integer literal too large: {}", lexeme))
+}
+pub(crate) fn deref_expression(data: Type, env: &mut PyNamespace, namespace: Namespace) -> Exec<Constant> {
     let mut _x: Constant;
     match data {
         Type::Constant(x) => {
             _x = x.clone();
         }
+        Type::LargeInt(x) => return large_int(&x),
         Type::Name(mut x) => {
-            _x = x.exec(env, namespace);
+            _x = x.exec(env, namespace)?;
         }
         Type::BinOp(ref x) => {
-            _x = x.clone().calc(env, namespace);
+            _x = x.clone().calc(env, namespace)?;
         }
         Type::Compare(ref x) => {
-            _x = x.clone().calc(env, namespace);
+            _x = x.clone().calc(env, namespace)?;
         }
         Type::UnaryOp(ref x) => {
-            _x = x.clone().calc(env, namespace);
+            _x = x.clone().calc(env, namespace)?;
         }
         Type::BoolOp(ref x) => {
-            _x = x.clone().calc(env, namespace);
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::IfExp(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::JoinedStr(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::FormattedValue(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::Call(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::Lambda(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::Tuple(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::List(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::Set(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::Dict(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::ListComp(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::SetComp(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::DictComp(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
         }
-        _ => panic!("Error at calc"),
+        Type::GeneratorExp(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::Attribute(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::Subscript(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::Slice(ref x) => {
+            _x = x.clone().calc(env, namespace)?;
+        }
+        Type::Starred(_) => return raise("SyntaxError", "can't use starred expression here"),
+        // statements such as break have no value
+        _ => return raise("SyntaxError", "invalid syntax"),
     }
-    _x
+    Ok(_x)
 }
 impl Calc for BinOp {
-    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Constant {
-        let mut x: PyObject = deref_expression(*self.left.clone(), env, current_namespace.clone())
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        let x: PyObject = deref_expression(*self.left.clone(), env, current_namespace.clone())?
             .clone()
             .value;
-        let y: PyObject = deref_expression(*self.right.clone(), env, current_namespace.clone())
+        let y: PyObject = deref_expression(*self.right.clone(), env, current_namespace.clone())?
             .clone()
             .value;
-        match self.op.clone() {
-            Operator::Add => {
-                let hashmap = x.convert_vec_to_hashmap(
-                    "__add__".to_string(),
-                    vec![PyObjAttr::Interpreter(Box::from(y))],
-                );
-                match x.add(hashmap, current_namespace.clone(),env) {
-                    PyResult::Some(x) => Constant::new(x),
-                    _ => panic!(),
-                }
-            }
-            Operator::Sub => {
-                let hashmap = x.convert_vec_to_hashmap(
-                    "__sub__".to_string(),
-                    vec![PyObjAttr::Interpreter(Box::from(y))],
-                );
-                match x.sub(hashmap,current_namespace.clone(),env) {
-                    PyResult::Some(x) => Constant::new(x),
-                    _ => panic!(),
-                }
-            }
-            Operator::Mult => {
-                let hashmap = x.convert_vec_to_hashmap(
-                    "__mult__".to_string(),
-                    vec![PyObjAttr::Interpreter(Box::from(y))],
-                );
-                match x.mul(hashmap,current_namespace.clone(),env) {
-                    PyResult::Some(x) => Constant::new(x),
-                    _ => panic!(),
-                }
-            }
-            Operator::Div => {
-                let hashmap = x.convert_vec_to_hashmap(
-                    "__div__".to_string(),
-                    vec![PyObjAttr::Interpreter(Box::from(y))],
-                );
-                match x.div(hashmap,current_namespace,env) {
-                    PyResult::Some(x) => Constant::new(x),
-                    _ => panic!(),
-                }
-            }
-            _ => {
-                todo!()
-            }
-        }
+        Ok(Constant::new(binary_operate(x, y, self.op.clone(), current_namespace, env)?))
+    }
+}
+
+/// 调用运算的方法，对象没有这个方法或不支持另一个操作数时返回None，
+/// python中定义的方法返回NotImplemented表示不支持
+fn try_binary_method(
+    mut obj: PyObject,
+    method: &str,
+    other: PyObject,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<Option<PyObject>> {
+    if !obj.has_method(method) {
+        return Ok(None);
+    }
+    let hashmap = obj.convert_vec_to_hashmap(method.to_string(), vec![PyObjAttr::Interpreter(Box::new(other))])?;
+    match obj.call(method.to_string(), hashmap, namespace, env)? {
+        PyResult::Some(x) if is_not_implemented(&x) => Ok(None),
+        PyResult::Some(x) => Ok(Some(x)),
+        PyResult::Err(ErrorType::ObjMethodCallError(_)) | PyResult::None => Ok(None),
+        PyResult::Err(x) => Err(x.into()),
+        _ => panic!("Error at running"),
+    }
+}
+
+/// ## fn binary_operate
+/// 二元运算x op y：先调用x的方法，如`__add__`，不支持时再调用y的反射方法，如`__radd__`
+pub(crate) fn binary_operate(
+    x: PyObject,
+    y: PyObject,
+    op: Operator,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<PyObject> {
+    let (method, symbol) = op.binary_method();
+    if let Some(result) = try_binary_method(x.clone(), method, y.clone(), namespace.clone(), env)? {
+        return Ok(result);
+    }
+    let reflected = format!("__r{}", &method[2..]);
+    if let Some(result) = try_binary_method(y.clone(), reflected.as_str(), x.clone(), namespace, env)? {
+        return Ok(result);
     }
+    raise(
+        "TypeError",
+        format!(
+            "unsupported operand type(s) for {}: '{}' and '{}'",
+            symbol,
+            x.return_identity(),
+            y.return_identity()
+        ),
+    )
+}
+
+/// ## fn inplace_operate
+/// 增强赋值的运算：先调用x的原地方法，如`__iadd__`，没有时与二元运算相同
+pub(crate) fn inplace_operate(
+    x: PyObject,
+    y: PyObject,
+    op: Operator,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<PyObject> {
+    let inplace = format!("__i{}", &op.binary_method().0[2..]);
+    if let Some(result) = try_binary_method(x.clone(), inplace.as_str(), y.clone(), namespace.clone(), env)? {
+        return Ok(result);
+    }
+    binary_operate(x, y, op, namespace, env)
 }
 #[derive(Debug, Clone)]
 pub struct Compare {
     pub(crate) left: Box<Type>,
     pub(crate) ops: Vec<Operator>,
-    pub(crate) comparators: Box<Vec<Type>>,
+    pub(crate) comparators: Vec<Type>,
 }
 impl Compare {
-    fn compare(operator: Operator, mut left: PyObject, right: PyObject,namespace: Namespace, env: &mut PyNamespace) -> bool {
+    /// ## fn compare
+    /// 比较运算x op y：先调用x的方法，不支持时再调用y的反射方法，如`__lt__`对应`__gt__`，
+    /// 都不支持时==和!=比较是否是同一个对象，其余比较抛出TypeError
+    pub(crate) fn compare(
+        operator: Operator,
+        left: PyObject,
+        right: PyObject,
+        namespace: Namespace,
+        env: &mut PyNamespace,
+    ) -> Exec<bool> {
         match operator {
-            Operator::Eq => {
-
-                let hashmap = left.convert_vec_to_hashmap(
-                    "__eq__".to_string(),
-                    vec![PyObjAttr::Interpreter(Box::from(right))],
-                );
-                match left.py_eq(hashmap,namespace.clone(),env) {
-                    PyResult::Some(x) => obj_to_bool(x, namespace, env),
-                    _ => panic!(),
-                }
-            }
-            Operator::NotEq => {
-                let hashmap = left.convert_vec_to_hashmap(
-                    "__ne__".to_string(),
-                    vec![PyObjAttr::Interpreter(Box::from(right))],
-                );
-                match left.py_ne(hashmap, namespace.clone(),env) {
-                    PyResult::Some(x) => obj_to_bool(x, namespace, env),
-                    _ => panic!(),
-                }
-            }
-            Operator::Lt => {
-                let hashmap = left.convert_vec_to_hashmap(
-                    "__lt__".to_string(),
-                    vec![PyObjAttr::Interpreter(Box::from(right))],
-                );
-                match left.lt(hashmap, namespace.clone(), env) {
-                    PyResult::Some(x) => obj_to_bool(x, namespace, env),
-                    _ => panic!(),
-                }
-            }
-            Operator::Gt => {
-                let hashmap = left.convert_vec_to_hashmap(
-                    "__gt__".to_string(),
-                    vec![PyObjAttr::Interpreter(Box::from(right))],
-                );
-                match left.gt(hashmap, namespace.clone(), env) {
-                    PyResult::Some(x) => obj_to_bool(x, namespace.clone(), env),
-                    _ => panic!(),
-                }
-            }
-            Operator::LtE => {
-                let hashmap = left.convert_vec_to_hashmap(
-                    "__le__".to_string(),
-                    vec![PyObjAttr::Interpreter(Box::from(right))],
-                );
-                match left.le(hashmap, namespace.clone() , env) {
-                    PyResult::Some(x) => obj_to_bool(x, namespace.clone(), env),
-                    _ => panic!(),
-                }
-            }
-            Operator::GtE => {
-                let hashmap = left.convert_vec_to_hashmap(
-                    "__ge__".to_string(),
-                    vec![PyObjAttr::Interpreter(Box::from(right))],
-                );
-                match left.ge(hashmap,namespace.clone(), env) {
-                    PyResult::Some(x) => obj_to_bool(x,namespace.clone(), env),
-                    _ => panic!(),
-                }
-            }
-            _ => {
-                panic!("not a compare operator")
-            }
+            Operator::Is => return Ok(left == right),
+            Operator::IsNot => return Ok(left != right),
+            Operator::In => return contains(&right, left, namespace, env),
+            Operator::NotIn => return Ok(!contains(&right, left, namespace, env)?),
+            _ => {}
+        }
+        let (method, reflected, symbol) = operator.compare_method();
+        if let Some(x) = try_binary_method(left.clone(), method, right.clone(), namespace.clone(), env)? {
+            return obj_to_bool(x, namespace, env);
+        }
+        if let Some(x) = try_binary_method(right.clone(), reflected, left.clone(), namespace.clone(), env)? {
+            return obj_to_bool(x, namespace, env);
+        }
+        match operator {
+            Operator::Eq => Ok(left == right),
+            // without __ne__ the result is the opposite of ==
+            Operator::NotEq => Ok(!Self::compare(Operator::Eq, left, right, namespace, env)?),
+            _ => raise(
+                "TypeError",
+                format!(
+                    "'{}' not supported between instances of '{}' and '{}'",
+                    symbol,
+                    left.return_identity(),
+                    right.return_identity()
+                ),
+            ),
         }
     }
 
-    fn compare_calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> bool {
-        let mut comparators = vec![*self.left.clone()];
-        comparators.extend(*self.comparators.clone());
-        for (index, left) in comparators.iter().enumerate() {
-            let left = deref_expression(left.clone(), env, current_namespace.clone());
-            if index + 1 == comparators.len() {
-                return true;
-            }
-            let right = deref_expression(
-                comparators[index + 1].clone(),
-                env,
-                current_namespace.clone(),
-            );
-            if !Self::compare(self.ops[index].clone(), left.value, right.value, current_namespace.clone(), env) {
-                return false;
+    /// 连续比较时每个操作数只计算一次，`a < b < c`中的b在两次比较中共用
+    fn compare_calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<bool> {
+        let mut left = deref_expression(*self.left.clone(), env, current_namespace.clone())?.value;
+        for (op, comparator) in self.ops.iter().zip(self.comparators.iter()) {
+            let right = deref_expression(comparator.clone(), env, current_namespace.clone())?.value;
+            if !Self::compare(op.clone(), left, right.clone(), current_namespace.clone(), env)? {
+                return Ok(false);
             }
+            left = right;
+        }
+        Ok(true)
+    }
+}
+
+/// ## fn contains
+/// item in container：调用container的`__contains__`，没有时迭代container逐个比较
+fn contains(container: &PyObject, item: PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<bool> {
+    if let Some(x) = try_binary_method(container.clone(), "__contains__", item.clone(), namespace.clone(), env)? {
+        return obj_to_bool(x, namespace, env);
+    }
+    let iterator = match obj_iter(container, namespace.clone(), env)? {
+        Some(x) => x,
+        None => {
+            let message = format!("argument of type '{}' is not iterable", container.return_identity());
+            return raise("TypeError", message);
+        }
+    };
+    while let Some(x) = obj_next(&iterator, namespace.clone(), env)? {
+        if x == item || Compare::compare(Operator::Eq, x, item.clone(), namespace.clone(), env)? {
+            return Ok(true);
         }
-        true
     }
+    Ok(false)
 }
 impl Calc for Compare {
-    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Constant {
-        Constant::new(obj_bool(self.compare_calc(env, current_namespace.clone())))
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        Ok(Constant::new(obj_bool(self.compare_calc(env, current_namespace.clone())?)))
     }
 }
 
@@ -454,62 +746,128 @@ pub struct UnaryOp {
     pub operand: Box<Type>,
 }
 impl Calc for UnaryOp {
-    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Constant {
-        let mut x: PyObject = deref_expression(*self.operand.clone(), env, current_namespace.clone())
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        let mut x: PyObject = deref_expression(*self.operand.clone(), env, current_namespace.clone())?
             .clone()
             .value;
-        match self.op.clone() {
-            Operator::UAdd => match x.pos(current_namespace.clone(), env) {
-                PyResult::Some(x) => Constant::new(x),
-                PyResult::Err(x) => panic!("{}", x),
-                _ => panic!(),
-            },
-            Operator::USub => match x.neg(current_namespace, env) {
-                PyResult::Some(x) => Constant::new(x),
-                PyResult::Err(x) => panic!("{}", x),
-                _ => panic!(),
-            },
-            Operator::Not => match x.not(current_namespace, env) {
-                PyResult::Some(x) => Constant::new(x),
-                PyResult::Err(x) => panic!("{}", x),
-                _ => panic!(),
-            },
+        let (result, symbol) = match self.op.clone() {
+            Operator::UAdd => (x.pos(current_namespace, env)?, "unary +"),
+            Operator::USub => (x.neg(current_namespace, env)?, "unary -"),
+            Operator::Not => (x.not(current_namespace, env)?, "not"),
+            Operator::Invert => (x.invert(current_namespace, env)?, "unary ~"),
             _ => panic!("Error note"),
+        };
+        match result {
+            PyResult::Some(x) => Ok(Constant::new(x)),
+            PyResult::Err(ErrorType::ObjMethodCallError(_)) | PyResult::None => {
+                raise("TypeError", format!("bad operand type for {}: '{}'", symbol, x.return_identity()))
+            }
+            PyResult::Err(x) => Err(x.into()),
+            _ => panic!("Error at running"),
         }
     }
 }
 #[derive(Debug, Clone)]
 pub struct BoolOp {
     pub op: Operator,
-    pub values: Box<Vec<Type>>,
+    pub values: Vec<Type>,
 }
 
 impl Calc for BoolOp {
-    fn calc(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Constant {
+    fn calc(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Constant> {
         match self.op {
             Operator::And => {
-                for i in *self.values.clone() {
-                    let i_constant = deref_expression(i, env, namespace.clone());
-                    if !obj_to_bool(i_constant.value,namespace.clone(), env) {
-                        return Constant::new(obj_bool(false));
+                for i in self.values.clone() {
+                    let i_constant = deref_expression(i, env, namespace.clone())?;
+                    if !obj_to_bool(i_constant.value,namespace.clone(), env)? {
+                        return Ok(Constant::new(obj_bool(false)));
                     }
                 }
-                return Constant::new(obj_bool(true));
+                Ok(Constant::new(obj_bool(true)))
             }
             Operator::Or => {
-                for i in *self.values.clone() {
-                    let i_constant = deref_expression(i, env, namespace.clone());
-                    if obj_to_bool(i_constant.value, namespace.clone(), env) {
-                        return Constant::new(obj_bool(true));
+                for i in self.values.clone() {
+                    let i_constant = deref_expression(i, env, namespace.clone())?;
+                    if obj_to_bool(i_constant.value, namespace.clone(), env)? {
+                        return Ok(Constant::new(obj_bool(true)));
                     }
                 }
-                return Constant::new(obj_bool(false));
+                Ok(Constant::new(obj_bool(false)))
             }
             _ => panic!("Unsupported Bool Operate"),
         }
     }
 }
 
+/// ## struct IfExp
+/// 条件表达式`body if test else orelse`，只计算选中的分支
+#[derive(Debug, Clone)]
+pub struct IfExp {
+    pub test: Box<Type>,
+    pub body: Box<Type>,
+    pub orelse: Box<Type>,
+}
+
+impl Calc for IfExp {
+    fn calc(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Constant> {
+        let test = deref_expression(*self.test.clone(), env, namespace.clone())?.value;
+        if obj_to_bool(test, namespace.clone(), env)? {
+            deref_expression(*self.body.clone(), env, namespace)
+        } else {
+            deref_expression(*self.orelse.clone(), env, namespace)
+        }
+    }
+}
+
+/// ## struct JoinedStr
+/// f-string，values由字符串常量和FormattedValue组成
+#[derive(Debug, Clone)]
+pub struct JoinedStr {
+    pub values: Vec<Type>,
+}
+
+impl Calc for JoinedStr {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        let mut s = String::new();
+        for value in self.values.clone() {
+            let value = deref_expression(value, env, current_namespace.clone())?;
+            s.push_str(obj_to_str(value.value, current_namespace.clone(), env)?.as_str());
+        }
+        Ok(Constant::new(obj_str(s)))
+    }
+}
+
+/// ## struct FormattedValue
+/// f-string中的替换字段，如`{x!r:>10}`
+#[derive(Debug, Clone)]
+pub struct FormattedValue {
+    pub value: Box<Type>,
+    /// 转换标志，'s'、'r'或'a'
+    pub conversion: Option<char>,
+    pub format_spec: Option<Box<Type>>,
+}
+
+impl Calc for FormattedValue {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        let value = deref_expression(*self.value.clone(), env, current_namespace.clone())?.value;
+        let value = match self.conversion {
+            None => value,
+            Some('s') => obj_str(obj_to_str(value, current_namespace.clone(), env)?),
+            Some('r') => obj_str(obj_to_repr(value, current_namespace.clone(), env)?),
+            Some('a') => obj_str(obj_to_ascii(value, current_namespace.clone(), env)?),
+            Some(x) => return raise("SyntaxError", format!("f-string: invalid conversion character '{}'", x)),
+        };
+        let spec = match &self.format_spec {
+            None => String::new(),
+            Some(x) => {
+                let spec = deref_expression(*x.clone(), env, current_namespace.clone())?.value;
+                obj_to_str(spec, current_namespace.clone(), env)?
+            }
+        };
+        Ok(Constant::new(obj_str(format_obj(value, spec.as_str(), current_namespace, env)?)))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Print {
     pub(crate) arg: Box<Type>,
@@ -518,14 +876,14 @@ pub struct Print {
 #[derive(Clone, Debug)]
 pub struct If {
     pub test: Box<Type>,
-    pub body: Vec<Box<Type>>,
-    pub orelse: Vec<Box<Type>>,
+    pub body: Vec<Type>,
+    pub orelse: Vec<Type>,
 }
 
 impl If {
-    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Type {
-        let test = deref_expression(*self.test.clone(), env, namespace.clone());
-        return if obj_to_bool(test.value, namespace.clone(), env) {
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
+        let test = deref_expression(*self.test.clone(), env, namespace.clone())?;
+        if obj_to_bool(test.value, namespace.clone(), env)? {
             exec_commands(&self.body.clone(), env, namespace.clone())
         } else {
             exec_commands(&self.orelse.clone(), env, namespace.clone())
@@ -535,17 +893,19 @@ impl If {
 #[derive(Clone, Debug)]
 pub struct While{
     pub test:Box<Type>,
-    pub body: Vec<Box<Type>>,
-    pub orelse:Vec<Box<Type>>
+    pub body: Vec<Type>,
+    pub orelse:Vec<Type>
 }
 
 impl While {
-    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Type {
-        let mut test = deref_expression(*self.test.clone(), env, namespace.clone());
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
         let mut break_line=true;
-        while obj_to_bool(test.value.clone(), namespace.clone(), env) {
-            test = deref_expression(*self.test.clone(), env, namespace.clone());
-            match exec_commands(&self.body,env,namespace.clone()){
+        loop {
+            let test = deref_expression(*self.test.clone(), env, namespace.clone())?;
+            if !obj_to_bool(test.value, namespace.clone(), env)? {
+                break
+            }
+            match exec_commands(&self.body,env,namespace.clone())?{
                 Type::Break => {
                     break_line = false;
                     break
@@ -553,38 +913,1001 @@ impl While {
                 Type::Continue => {
                     continue
                 }
+                Type::Return(x) => return Ok(Type::Return(x)),
                 _ => {}
             }
         }
         if break_line{
-            exec_commands(&self.orelse, env, namespace.clone());
+            return exec_commands(&self.orelse, env, namespace.clone());
+        }
+        Ok(Type::None)
+    }
+}
+
+/// ## struct For
+/// for循环，通过`__iter__`和`__next__`迭代，迭代器耗尽时执行orelse
+#[derive(Debug, Clone)]
+pub struct For {
+    pub target: Box<Type>,
+    pub iter: Box<Type>,
+    pub body: Vec<Type>,
+    pub orelse: Vec<Type>,
+}
+
+impl For {
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
+        let iterable = deref_expression(*self.iter.clone(), env, namespace.clone())?.value;
+        let iterator = match obj_iter(&iterable, namespace.clone(), env)? {
+            Some(x) => x,
+            None => return raise("TypeError", format!("'{}' object is not iterable", iterable.return_identity())),
+        };
+        let mut break_line = true;
+        while let Some(item) = obj_next(&iterator, namespace.clone(), env)? {
+            assign_target(&self.target, item, env, namespace.clone())?;
+            match exec_commands(&self.body, env, namespace.clone())? {
+                Type::Break => {
+                    break_line = false;
+                    break
+                }
+                Type::Continue => continue,
+                Type::Return(x) => return Ok(Type::Return(x)),
+                _ => {}
+            }
+        }
+        if break_line {
+            return exec_commands(&self.orelse, env, namespace);
         }
-        Type::None
+        Ok(Type::None)
     }
 }
-enum FuncArgs {
-    Keywords(Keywords),
-    ARGS(Vec<Type>)
+
+/// ## struct Try
+/// try语句，body中抛出的异常依次与handlers匹配，没有异常时执行orelse，最后总是执行finalbody
+#[derive(Debug, Clone)]
+pub struct Try {
+    pub body: Vec<Type>,
+    pub handlers: Vec<ExceptHandler>,
+    pub orelse: Vec<Type>,
+    pub finalbody: Vec<Type>,
 }
-struct Keywords{
-    pub arg:String,
-    pub value:Box<Type>
+
+/// ## struct ExceptHandler
+/// except子句，type_为None时捕获所有异常，name是as绑定的名字
+#[derive(Debug, Clone)]
+pub struct ExceptHandler {
+    pub type_: Option<Type>,
+    pub name: Option<String>,
+    pub body: Vec<Type>,
 }
-impl Keywords{
-    pub fn to_hashmap(&self){
-        match ARGS {
-            _ => {}
+
+impl Try {
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
+        let result = self.handle(env, namespace.clone());
+        // break, continue and return in finally replace the exception
+        if let x @ (Type::Break | Type::Continue | Type::Return(_)) = exec_commands(&self.finalbody, env, namespace)? {
+            return Ok(x);
+        }
+        result
+    }
+    /// 执行body和匹配的except子句，没有except子句匹配时继续抛出
+    fn handle(&self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
+        let exc = match exec_commands(&self.body, env, namespace.clone()) {
+            Ok(x @ (Type::Break | Type::Continue | Type::Return(_))) => return Ok(x),
+            Ok(_) => return exec_commands(&self.orelse, env, namespace),
+            Err(x) => x,
+        };
+        for handler in self.handlers.iter() {
+            if let Some(type_) = &handler.type_ {
+                let class_info = deref_expression(type_.clone(), env, namespace.clone())?.value;
+                if !exception_matches(&exc, &class_info)? {
+                    continue;
+                }
+            }
+            let _handling = Handling::enter(*exc.clone());
+            if let Some(name) = &handler.name {
+                if let Some(x) = env.set_variable(namespace.clone(), name.clone(), *exc.clone()) {
+                    return Err(x.into());
+                }
+            }
+            let result = exec_commands(&handler.body, env, namespace.clone());
+            // the name is unbound when the handler ends, like `del name`
+            if let Some(name) = &handler.name {
+                env.delete_variable(&namespace, name.as_str());
+            }
+            return result;
         }
+        Err(exc)
     }
 }
-pub struct Call{
-    func: Box<Type>,
-    args: Box<FuncArgs>
+
+/// ## struct Raise
+/// raise语句，没有exc时重新抛出正在处理的异常，cause是from后面的异常
+#[derive(Debug, Clone)]
+pub struct Raise {
+    pub exc: Option<Box<Type>>,
+    pub cause: Option<Box<Type>>,
+}
+
+impl Raise {
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
+        let exc = match &self.exc {
+            Some(x) => {
+                let value = deref_expression(*x.clone(), env, namespace.clone())?.value;
+                match to_exception(value, namespace.clone(), env)? {
+                    Some(x) => x,
+                    None => return raise("TypeError", "exceptions must derive from BaseException"),
+                }
+            }
+            None => match handled_exception() {
+                Some(x) => x,
+                None => return raise("RuntimeError", "No active exception to reraise"),
+            },
+        };
+        if let Some(cause) = &self.cause {
+            let value = deref_expression(*cause.clone(), env, namespace.clone())?.value;
+            // raise X from None hides the exception being handled
+            let cause = match value.return_identity().as_str() {
+                "NoneType" => None,
+                _ => match to_exception(value, namespace.clone(), env)? {
+                    Some(x) => Some(x),
+                    None => return raise("TypeError", "exception causes must derive from BaseException"),
+                },
+            };
+            if let Some(x) = exception_data(&exc) {
+                let mut exception = x.borrow_mut();
+                exception.cause = cause;
+                exception.suppress_context = true;
+            }
+        }
+        raise_exception(exc)
+    }
+}
+
+/// ## struct With
+/// with语句，多个上下文管理器相当于嵌套的with语句
+#[derive(Debug, Clone)]
+pub struct With {
+    pub items: Vec<WithItem>,
+    pub body: Vec<Type>,
 }
 
-impl Call{
-    pub fn exec(&mut self,namespace: Namespace, env:&mut PyNamespace) -> Type{
-        let mut obj = deref_expression(*self.func.clone(), env, namespace.clone());
+/// ## struct WithItem
+/// with中的一个上下文管理器，optional_vars是as后面的赋值目标
+#[derive(Debug, Clone)]
+pub struct WithItem {
+    pub context_expr: Type,
+    pub optional_vars: Option<Type>,
+}
 
+impl With {
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
+        self.enter(0, env, namespace)
+    }
+    /// ## fn enter
+    /// 进入第index个上下文管理器，离开时调用`__exit__`，`__exit__`返回真值时不再抛出异常
+    fn enter(&self, index: usize, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
+        let item = match self.items.get(index) {
+            Some(x) => x,
+            None => return exec_commands(&self.body, env, namespace),
+        };
+        let (value, exit) = item.enter(env, namespace.clone())?;
+        let result = match &item.optional_vars {
+            Some(target) => assign_target(target, value, env, namespace.clone()),
+            None => Ok(()),
+        };
+        match result.and_then(|_| self.enter(index + 1, env, namespace.clone())) {
+            Ok(x) => {
+                call_object(&exit, vec![obj_none(), obj_none(), obj_none()], vec![], namespace, env)?;
+                Ok(x)
+            }
+            Err(exc) => {
+                let suppress = {
+                    let _handling = Handling::enter(*exc.clone());
+                    let args = vec![type_of(&exc), *exc.clone(), obj_none()];
+                    let result = call_object(&exit, args, vec![], namespace.clone(), env)?;
+                    obj_to_bool(result, namespace, env)?
+                };
+                if suppress {
+                    Ok(Type::None)
+                } else {
+                    Err(exc)
+                }
+            }
+        }
     }
-}
\ No newline at end of file
+}
+
+impl WithItem {
+    /// ## fn enter
+    /// 计算上下文管理器并调用`__enter__`，返回`__enter__`的结果和`__exit__`
+    pub(crate) fn enter(&self, env: &mut PyNamespace, namespace: Namespace) -> Exec<(PyObject, PyObject)> {
+        let manager = deref_expression(self.context_expr.clone(), env, namespace.clone())?.value;
+        let enter = obj_lookup(&manager, "__enter__", namespace.clone(), env)?;
+        let exit = obj_lookup(&manager, "__exit__", namespace.clone(), env)?;
+        let (enter, exit) = match (enter, exit) {
+            (Some(enter), Some(exit)) => (enter, exit),
+            (Some(_), None) => {
+                return raise(
+                    "TypeError",
+                    format!(
+                        "'{}' object does not support the context manager protocol (missed __exit__ method)",
+                        manager.return_identity()
+                    ),
+                )
+            }
+            _ => {
+                return raise(
+                    "TypeError",
+                    format!(
+                        "'{}' object does not support the context manager protocol",
+                        manager.return_identity()
+                    ),
+                )
+            }
+        };
+        Ok((call_object(&enter, vec![], vec![], namespace, env)?, exit))
+    }
+}
+
+/// ## struct Alias
+/// import中的一个名字，asname是as后面的名字
+#[derive(Debug, Clone)]
+pub struct Alias {
+    pub name: String,
+    pub asname: Option<String>,
+}
+
+/// ## struct Import
+/// import语句，`import a.b`绑定顶层的包a，`import a.b as c`把模块a.b绑定到c
+#[derive(Debug, Clone)]
+pub struct Import {
+    pub names: Vec<Alias>,
+}
+
+impl Import {
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
+        for alias in self.names.iter() {
+            let module = import_module(alias.name.as_str(), env)?;
+            let (id, value) = match &alias.asname {
+                Some(x) => (x.clone(), module),
+                None => {
+                    let top = alias.name.split('.').next().unwrap_or_default();
+                    (top.to_string(), import_module(top, env)?)
+                }
+            };
+            if let Some(x) = env.set_variable(namespace.clone(), id, value) {
+                return Err(x.into());
+            }
+        }
+        Ok(Type::None)
+    }
+}
+
+/// ## struct ImportFrom
+/// from import语句，level是模块名前面点的个数，相对导入时大于0
+#[derive(Debug, Clone)]
+pub struct ImportFrom {
+    pub module: Option<String>,
+    pub names: Vec<Alias>,
+    pub level: usize,
+}
+
+impl ImportFrom {
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
+        let name = resolve_name(self.module.as_deref(), self.level, env)?;
+        let module = import_module(name.as_str(), env)?;
+        for alias in self.names.iter() {
+            let names = match alias.name.as_str() {
+                "*" => public_names(&module, namespace.clone(), env)?
+                    .into_iter()
+                    .map(|x| (x.clone(), x))
+                    .collect(),
+                _ => vec![(alias.name.clone(), alias.asname.clone().unwrap_or(alias.name.clone()))],
+            };
+            for (name, id) in names {
+                let value = import_from(&module, name.as_str(), env)?;
+                if let Some(x) = env.set_variable(namespace.clone(), id, value) {
+                    return Err(x.into());
+                }
+            }
+        }
+        Ok(Type::None)
+    }
+}
+
+/// ## struct Tuple
+/// 元组，ctx为Store时是赋值的目标，如`for k, v in x`中的`k, v`
+#[derive(Debug, Clone)]
+pub struct Tuple {
+    pub elts: Vec<Type>,
+    pub ctx: PyCtx,
+}
+
+impl Calc for Tuple {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        Ok(Constant::new(obj_tuple(display_items(&self.elts, env, current_namespace)?)))
+    }
+}
+
+/// 计算列表、元组和集合中的元素，`*x`展开为多个元素
+fn display_items(elts: &[Type], env: &mut PyNamespace, namespace: Namespace) -> Exec<Vec<PyObject>> {
+    let mut items = vec![];
+    for elt in elts {
+        match elt {
+            Type::Starred(x) => {
+                let value = deref_expression(*x.value.clone(), env, namespace.clone())?.value;
+                match obj_iterate(&value, namespace.clone(), env)? {
+                    Some(x) => items.extend(x),
+                    None => return raise("TypeError", format!("'{}' object is not iterable", value.return_identity())),
+                }
+            }
+            x => items.push(deref_expression(x.clone(), env, namespace.clone())?.value),
+        }
+    }
+    Ok(items)
+}
+
+/// ## struct List
+/// 列表`[a, *b]`，ctx为Store时是赋值的目标
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct List {
+    pub elts: Vec<Type>,
+    pub ctx: PyCtx,
+}
+
+impl Calc for List {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        Ok(Constant::new(obj_list(display_items(&self.elts, env, current_namespace)?)))
+    }
+}
+
+/// ## struct Set
+/// 集合`{a, *b}`
+#[derive(Debug, Clone)]
+pub struct Set {
+    pub elts: Vec<Type>,
+}
+
+impl Calc for Set {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        let items = display_items(&self.elts, env, current_namespace.clone())?;
+        Ok(Constant::new(build_set(items, current_namespace, env)?))
+    }
+}
+
+/// ## struct Dict
+/// 字典`{k: v, **d}`，键为None时对应的值是被展开的字典
+#[derive(Debug, Clone)]
+pub struct Dict {
+    pub keys: Vec<Option<Type>>,
+    pub values: Vec<Type>,
+}
+
+impl Calc for Dict {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        let dict = Shared::new(vec![]);
+        for (key, value) in self.keys.iter().zip(self.values.iter()) {
+            let key = match key {
+                Some(x) => Some(deref_expression(x.clone(), env, current_namespace.clone())?.value),
+                None => None,
+            };
+            let value = deref_expression(value.clone(), env, current_namespace.clone())?.value;
+            match key {
+                Some(key) => dict_set(&dict, key, value, current_namespace.clone(), env)?,
+                None => match value.get_value("x".to_string()) {
+                    Ok(PyObjAttr::Rust(DataType::Dict(x))) => {
+                        let items = x.borrow().clone();
+                        for (key, value) in items {
+                            dict_set(&dict, key, value, current_namespace.clone(), env)?;
+                        }
+                    }
+                    _ => return raise("TypeError", format!("'{}' object is not a mapping", value.return_identity())),
+                },
+            }
+        }
+        Ok(Constant::new(obj_dict_shared(dict)))
+    }
+}
+
+/// ## struct Comprehension
+/// 推导式中的一个`for target in iter if ...`
+#[derive(Debug, Clone)]
+pub struct Comprehension {
+    pub target: Box<Type>,
+    pub iter: Box<Type>,
+    pub ifs: Vec<Type>,
+}
+
+/// ## struct ComprehensionState
+/// 推导式执行到的位置，每次next计算出一组元素
+/// - scope：推导式自己的命名空间，for的目标绑定在这里
+/// - elts：每次计算的表达式，字典推导式是键和值
+/// - iterators：每一层for正在迭代的迭代器，全部耗尽后为空
+#[derive(Debug, Clone)]
+pub struct ComprehensionState {
+    pub scope: Namespace,
+    pub generators: Vec<Comprehension>,
+    pub elts: Vec<Type>,
+    pub iterators: Vec<PyObject>,
+}
+
+impl ComprehensionState {
+    /// 第一个for的iter在外层命名空间中计算，其余部分都在新的命名空间中计算
+    pub fn new(generators: Vec<Comprehension>, elts: Vec<Type>, env: &mut PyNamespace, namespace: Namespace) -> Exec<Self> {
+        let iterator = comprehension_iter(&generators[0].iter, env, namespace.clone())?;
+        let scope = namespace.child(Uuid::new_v4().to_string());
+        let path = scope.path();
+        env.create_local_namespace(path[0].clone(), path[1..].to_vec());
+        Ok(ComprehensionState {
+            scope,
+            generators,
+            elts,
+            iterators: vec![iterator],
+        })
+    }
+    /// 计算下一组元素，全部耗尽时删除命名空间并返回None
+    pub fn next(&mut self, env: &mut PyNamespace) -> Exec<Option<Vec<PyObject>>> {
+        while let Some(iterator) = self.iterators.last().cloned() {
+            let level = self.iterators.len() - 1;
+            let item = match obj_next(&iterator, self.scope.clone(), env)? {
+                Some(x) => x,
+                None => {
+                    self.iterators.pop();
+                    if self.iterators.is_empty() {
+                        env.delete_local_namespace(&self.scope);
+                    }
+                    continue;
+                }
+            };
+            let generator = self.generators[level].clone();
+            assign_target(&generator.target, item, env, self.scope.clone())?;
+            let mut passed = true;
+            for test in generator.ifs.iter() {
+                let value = deref_expression(test.clone(), env, self.scope.clone())?.value;
+                if !obj_to_bool(value, self.scope.clone(), env)? {
+                    passed = false;
+                    break;
+                }
+            }
+            if !passed {
+                continue;
+            }
+            match self.generators.get(level + 1) {
+                Some(x) => {
+                    let iterator = comprehension_iter(&x.iter, env, self.scope.clone())?;
+                    self.iterators.push(iterator);
+                }
+                None => {
+                    let mut items = vec![];
+                    for elt in self.elts.clone() {
+                        items.push(deref_expression(elt, env, self.scope.clone())?.value);
+                    }
+                    return Ok(Some(items));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn comprehension_iter(iter: &Type, env: &mut PyNamespace, namespace: Namespace) -> Exec<PyObject> {
+    let iterable = deref_expression(iter.clone(), env, namespace.clone())?.value;
+    match obj_iter(&iterable, namespace, env)? {
+        Some(x) => Ok(x),
+        None => raise("TypeError", format!("'{}' object is not iterable", iterable.return_identity())),
+    }
+}
+
+/// ## struct ListComp
+/// 列表推导式`[elt for ...]`
+#[derive(Debug, Clone)]
+pub struct ListComp {
+    pub elt: Box<Type>,
+    pub generators: Vec<Comprehension>,
+}
+
+impl Calc for ListComp {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        let mut state = ComprehensionState::new(self.generators.clone(), vec![*self.elt.clone()], env, current_namespace)?;
+        let mut items = vec![];
+        while let Some(mut x) = state.next(env)? {
+            items.push(x.remove(0));
+        }
+        Ok(Constant::new(obj_list(items)))
+    }
+}
+
+/// ## struct SetComp
+/// 集合推导式`{elt for ...}`
+#[derive(Debug, Clone)]
+pub struct SetComp {
+    pub elt: Box<Type>,
+    pub generators: Vec<Comprehension>,
+}
+
+impl Calc for SetComp {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        let elts = vec![*self.elt.clone()];
+        let mut state = ComprehensionState::new(self.generators.clone(), elts, env, current_namespace.clone())?;
+        let items = Shared::new(vec![]);
+        while let Some(mut x) = state.next(env)? {
+            set_add(&items, x.remove(0), current_namespace.clone(), env)?;
+        }
+        Ok(Constant::new(obj_set(items)))
+    }
+}
+
+/// ## struct DictComp
+/// 字典推导式`{key: value for ...}`
+#[derive(Debug, Clone)]
+pub struct DictComp {
+    pub key: Box<Type>,
+    pub value: Box<Type>,
+    pub generators: Vec<Comprehension>,
+}
+
+impl Calc for DictComp {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        let elts = vec![*self.key.clone(), *self.value.clone()];
+        let mut state = ComprehensionState::new(self.generators.clone(), elts, env, current_namespace.clone())?;
+        let dict = Shared::new(vec![]);
+        while let Some(mut x) = state.next(env)? {
+            let value = x.pop().unwrap();
+            dict_set(&dict, x.pop().unwrap(), value, current_namespace.clone(), env)?;
+        }
+        Ok(Constant::new(obj_dict_shared(dict)))
+    }
+}
+
+/// ## struct GeneratorExp
+/// 生成器表达式`(elt for ...)`，元素在迭代时才计算
+#[derive(Debug, Clone)]
+pub struct GeneratorExp {
+    pub elt: Box<Type>,
+    pub generators: Vec<Comprehension>,
+}
+
+impl Calc for GeneratorExp {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        // the generator may outlive the function that created it
+        env.capture_namespace(&current_namespace);
+        let state = ComprehensionState::new(self.generators.clone(), vec![*self.elt.clone()], env, current_namespace)?;
+        Ok(Constant::new(obj_iterator("generator", IterState::Comprehension(Box::new(state)))))
+    }
+}
+
+/// ## fn assign_target
+/// 把值绑定到赋值目标上，目标是元组时按迭代协议解包
+pub(crate) fn assign_target(target: &Type, value: PyObject, env: &mut PyNamespace, namespace: Namespace) -> Exec<()> {
+    match target {
+        Type::Name(x) => match env.set_variable(namespace, x.id.clone(), value) {
+            Some(x) => Err(x.into()),
+            None => Ok(()),
+        },
+        Type::Tuple(x) => unpack_target(&x.elts, value, env, namespace),
+        Type::List(x) => unpack_target(&x.elts, value, env, namespace),
+        Type::Attribute(x) => {
+            let obj = deref_expression(*x.value.clone(), env, namespace.clone())?.value;
+            obj_setattr(&obj, x.attr.as_str(), value, namespace, env)
+        }
+        Type::Subscript(x) => {
+            let obj = deref_expression(*x.value.clone(), env, namespace.clone())?.value;
+            let key = deref_expression(*x.slice.clone(), env, namespace.clone())?.value;
+            obj_setitem(&obj, key, value, namespace, env)
+        }
+        _ => raise("SyntaxError", "cannot assign to expression"),
+    }
+}
+
+/// 按迭代协议解包到多个目标，最多一个目标可以是`*x`，它得到剩下的元素组成的列表
+fn unpack_target(elts: &[Type], value: PyObject, env: &mut PyNamespace, namespace: Namespace) -> Exec<()> {
+    let mut items = match obj_iterate(&value, namespace.clone(), env)? {
+        Some(x) => x,
+        None => {
+            return raise(
+                "TypeError",
+                format!("cannot unpack non-iterable {} object", value.return_identity()),
+            )
+        }
+    };
+    let star = elts.iter().position(|x| matches!(x, Type::Starred(_)));
+    let required = elts.len() - star.map_or(0, |_| 1);
+    if items.len() < required {
+        let expected = match star {
+            Some(_) => format!("at least {}", required),
+            None => required.to_string(),
+        };
+        return raise(
+            "ValueError",
+            format!("not enough values to unpack (expected {}, got {})", expected, items.len()),
+        );
+    }
+    let star = match star {
+        Some(x) => x,
+        None if items.len() > required => {
+            return raise("ValueError", format!("too many values to unpack (expected {})", required))
+        }
+        None => elts.len(),
+    };
+    // the targets after the starred one take the last items, and it takes the ones in between
+    let tail = items.split_off(items.len() - elts.len().saturating_sub(star + 1));
+    let middle = items.split_off(star);
+    if star < elts.len() {
+        items.push(obj_list(middle));
+    }
+    items.extend(tail);
+    for (elt, item) in elts.iter().zip(items) {
+        match elt {
+            Type::Starred(x) => assign_target(&x.value, item, env, namespace.clone())?,
+            _ => assign_target(elt, item, env, namespace.clone())?,
+        }
+    }
+    Ok(())
+}
+
+/// ## struct Delete
+/// del语句，按顺序删除每个目标
+#[derive(Debug, Clone)]
+pub struct Delete {
+    pub targets: Vec<Type>,
+}
+
+impl Delete {
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
+        for target in self.targets.clone() {
+            delete_target(&target, env, namespace.clone())?;
+        }
+        Ok(Type::None)
+    }
+}
+
+fn delete_target(target: &Type, env: &mut PyNamespace, namespace: Namespace) -> Exec<()> {
+    match target {
+        Type::Name(x) => {
+            if !env.delete_variable(&namespace, x.id.as_str()) {
+                return raise("NameError", format!("name '{}' is not defined", x.id));
+            }
+        }
+        Type::Tuple(x) => {
+            for elt in x.elts.iter() {
+                delete_target(elt, env, namespace.clone())?;
+            }
+        }
+        Type::List(x) => {
+            for elt in x.elts.iter() {
+                delete_target(elt, env, namespace.clone())?;
+            }
+        }
+        Type::Attribute(x) => {
+            let obj = deref_expression(*x.value.clone(), env, namespace.clone())?.value;
+            obj_delattr(&obj, x.attr.as_str(), namespace, env)?;
+        }
+        Type::Subscript(x) => {
+            let obj = deref_expression(*x.value.clone(), env, namespace.clone())?.value;
+            let key = deref_expression(*x.slice.clone(), env, namespace.clone())?.value;
+            obj_delitem(&obj, key, namespace, env)?;
+        }
+        _ => return raise("SyntaxError", "cannot delete expression"),
+    }
+    Ok(())
+}
+
+/// ## struct Arguments
+/// def的形参列表，与python ast的arguments相同，默认值在执行def时计算
+#[derive(Clone, Debug, Default)]
+pub struct Arguments {
+    pub posonlyargs: Vec<String>,
+    pub args: Vec<String>,
+    pub vararg: Option<String>,
+    pub kwonlyargs: Vec<String>,
+    /// 与kwonlyargs一一对应，None表示没有默认值
+    pub kw_defaults: Vec<Option<Type>>,
+    pub kwarg: Option<String>,
+    pub defaults: Vec<Type>,
+}
+
+impl Arguments {
+    /// 计算默认值，得到函数的Signature
+    pub fn signature(&self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Signature> {
+        let mut defaults = vec![];
+        for default in self.defaults.clone() {
+            defaults.push(deref_expression(default, env, namespace.clone())?.value);
+        }
+        let mut kw_defaults = vec![];
+        for default in self.kw_defaults.clone() {
+            kw_defaults.push(match default {
+                Some(x) => Some(deref_expression(x, env, namespace.clone())?.value),
+                None => None,
+            });
+        }
+        Ok(Signature {
+            posonlyargs: self.posonlyargs.clone(),
+            args: self.args.clone(),
+            vararg: self.vararg.clone(),
+            kwonlyargs: self.kwonlyargs.clone(),
+            kwarg: self.kwarg.clone(),
+            defaults,
+            kw_defaults,
+        })
+    }
+}
+
+/// ## struct FunctionDef
+/// def语句，执行时在当前命名空间中绑定一个函数对象
+#[derive(Clone, Debug)]
+pub struct FunctionDef {
+    pub name: String,
+    pub args: Arguments,
+    pub body: Vec<Type>,
+    /// 函数体中有yield，调用时返回生成器
+    pub generator: bool,    /// 函数的局部变量名
+    pub locals: Vec<String>,
+}
+
+impl FunctionDef {
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
+        let signature = self.args.signature(env, namespace.clone())?;
+        // the function may outlive the frame it is defined in
+        env.capture_namespace(&namespace);
+        let mut function = PyFunction::new(
+            self.name.clone(),
+            signature,
+            self.body.clone(),
+            namespace.clone(),
+            env.module.clone(),
+        );
+        function.generator = self.generator;
+        function.locals = self.locals.clone();
+        if let Some(x) = env.set_variable(namespace, self.name.clone(), obj_function(function)) {
+            return Err(x.into());
+        }
+        Ok(Type::None)
+    }
+}
+
+/// ## struct ClassDef
+/// class语句，类体在一个临时的命名空间中执行，执行完后其中的变量成为类的属性
+#[derive(Clone, Debug)]
+pub struct ClassDef {
+    pub name: String,
+    pub bases: Vec<Type>,
+    pub body: Vec<Type>,
+}
+
+impl ClassDef {
+    /// 计算基类，没有基类时继承object
+    fn bases(&self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Vec<PyObject>> {
+        let mut bases = vec![];
+        for base in self.bases.clone() {
+            let base = deref_expression(base, env, namespace.clone())?.value;
+            match base.get_value("x".to_string()) {
+                Ok(PyObjAttr::Rust(DataType::Class(x))) if x.is_builtin() && x.name == "bool" => {
+                    return raise("TypeError", "type 'bool' is not an acceptable base type")
+                }
+                Ok(PyObjAttr::Rust(DataType::Class(_))) => bases.push(base),
+                _ => return raise("TypeError", "bases must be types"),
+            }
+        }
+        if bases.is_empty() {
+            bases.push(env.get_builtin("object".to_string()).unwrap());
+        }
+        Ok(bases)
+    }
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
+        let bases = self.bases(env, namespace.clone())?;
+        let frame = namespace.child(Uuid::new_v4().to_string());
+        let path = frame.path();
+        env.create_local_namespace(path[0].clone(), path[1..].to_vec());
+        if let Err(x) = exec_commands(&self.body, env, frame.clone()) {
+            env.remove_namespace(&frame);
+            return Err(x);
+        }
+        let mut dict = vec![];
+        let mut methods = vec![];
+        for (key, value) in env.local_variables(&frame) {
+            // the class body is not an enclosing scope of its methods
+            let value = match value.get_value("x".to_string()) {
+                Ok(PyObjAttr::Rust(DataType::Function(mut x))) if x.closure.path() == path => {
+                    env.capture_namespace(&namespace);
+                    x.closure = namespace.clone();
+                    methods.push(x.id);
+                    obj_function(*x)
+                }
+                _ => value,
+            };
+            dict.push((obj_str(key), value));
+        }
+        env.remove_namespace(&frame);
+        let module = match env.get_variable(namespace.clone(), "__name__".to_string()) {
+            Ok(x) => obj_to_str(x, namespace.clone(), env)?,
+            Err(_) => "__main__".to_string(),
+        };
+        let class = match PyClass::new(self.name.clone(), module, bases, dict) {
+            Ok(x) => x,
+            Err(x) => return raise("TypeError", x),
+        };
+        let layouts: Vec<PyClass> = class
+            .bases
+            .iter()
+            .filter_map(|x| class_of(x).solid_base())
+            .collect();
+        if layouts.iter().any(|x| !class.solid_base().is_some_and(|y| y == *x)) {
+            return raise("TypeError", "multiple bases have instance lay-out conflict");
+        }
+        let class_obj = obj_class(class.clone());
+        // methods defined in the body can find their class through super()
+        for (_, value) in class.dict.borrow_mut().iter_mut() {
+            if let Ok(PyObjAttr::Rust(DataType::Function(mut x))) = value.get_value("x".to_string()) {
+                if methods.contains(&x.id) {
+                    x.owner = Some(Box::new(class_obj.clone()));
+                    *value = obj_function(*x);
+                }
+            }
+        }
+        if let Some(x) = env.set_variable(namespace, self.name.clone(), class_obj) {
+            return Err(x.into());
+        }
+        Ok(Type::None)
+    }
+}
+
+/// ## struct Lambda
+/// lambda表达式，函数体是一个表达式，相当于只有一条return语句的函数
+#[derive(Clone, Debug)]
+pub struct Lambda {
+    pub args: Arguments,
+    pub body: Box<Type>,
+}
+
+impl Calc for Lambda {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        let signature = self.args.signature(env, current_namespace.clone())?;
+        env.capture_namespace(&current_namespace);
+        let body = Type::Return(Box::new(Return {
+            value: Some(self.body.clone()),
+        }));
+        let function = PyFunction::new(
+            "<lambda>".to_string(),
+            signature,
+            vec![body],
+            current_namespace,
+            env.module.clone(),
+        );
+        Ok(Constant::new(obj_function(function)))
+    }
+}
+
+/// ## struct Starred
+/// `*value`，在调用中表示把可迭代对象展开为位置参数
+#[derive(Clone, Debug)]
+pub struct Starred {
+    pub value: Box<Type>,
+    pub ctx: PyCtx,
+}
+
+/// ## struct Keyword
+/// 调用时的关键字参数，如`f(b=c)`中的`b=c`，arg为None时是`**value`
+#[derive(Clone, Debug)]
+pub struct Keyword {
+    pub arg: Option<String>,
+    pub value: Box<Type>,
+}
+
+/// ## struct Call
+/// 函数调用，先计算位置参数，再计算关键字参数
+#[derive(Clone, Debug)]
+pub struct Call {
+    pub func: Box<Type>,
+    pub args: Vec<Type>,
+    pub keywords: Vec<Keyword>,
+}
+
+impl Call {
+    fn function_name(func: &PyObject) -> String {
+        match func.get_value("x".to_string()) {
+            Ok(PyObjAttr::Rust(DataType::Function(x))) => x.name,
+            Ok(PyObjAttr::Rust(DataType::Class(x))) => x.name,
+            Ok(PyObjAttr::Rust(DataType::Method(_, x))) => Call::function_name(&x),
+            Ok(PyObjAttr::Rust(DataType::BuiltinMethod(_, x))) => x,
+            _ => func.return_identity(),
+        }
+    }
+}
+
+impl Calc for Call {
+    fn calc(&mut self, env: &mut PyNamespace, current_namespace: Namespace) -> Exec<Constant> {
+        let func = deref_expression(*self.func.clone(), env, current_namespace.clone())?.value;
+        let name = Call::function_name(&func);
+        let mut args = vec![];
+        for arg in self.args.clone() {
+            match arg {
+                Type::Starred(x) => {
+                    let value = deref_expression(*x.value, env, current_namespace.clone())?.value;
+                    match obj_iterate(&value, current_namespace.clone(), env)? {
+                        Some(x) => args.extend(x),
+                        None => {
+                            return raise(
+                                "TypeError",
+                                format!(
+                                    "{}() argument after * must be an iterable, not {}",
+                                    name,
+                                    value.return_identity()
+                                ),
+                            )
+                        }
+                    }
+                }
+                x => args.push(deref_expression(x, env, current_namespace.clone())?.value),
+            }
+        }
+        let mut keywords: Vec<(String, PyObject)> = vec![];
+        for keyword in self.keywords.clone() {
+            let value = deref_expression(*keyword.value, env, current_namespace.clone())?.value;
+            let items = match keyword.arg {
+                Some(x) => vec![(x, value)],
+                None => match value.get_value("x".to_string()) {
+                    Ok(PyObjAttr::Rust(DataType::Dict(x))) => x
+                        .borrow()
+                        .iter()
+                        .map(|(key, value)| match key.get_value("x".to_string()) {
+                            Ok(PyObjAttr::Rust(DataType::Str(key))) => Ok((key, value.clone())),
+                            _ => raise("TypeError", format!("{}() keywords must be strings", name)),
+                        })
+                        .collect::<Exec<Vec<_>>>()?,
+                    _ => {
+                        return raise(
+                            "TypeError",
+                            format!(
+                                "{}() argument after ** must be a mapping, not {}",
+                                name,
+                                value.return_identity()
+                            ),
+                        )
+                    }
+                },
+            };
+            for (key, value) in items {
+                if keywords.iter().any(|(x, _)| *x == key) {
+                    return raise(
+                        "TypeError",
+                        format!("{}() got multiple values for keyword argument '{}'", name, key),
+                    );
+                }
+                keywords.push((key, value));
+            }
+        }
+        Ok(Constant::new(call_object(&func, args, keywords, current_namespace, env)?))
+    }
+}
+
+/// ## struct Return
+/// return语句，执行后value是计算好的Constant，经exec_commands一直传递到函数调用处
+#[derive(Clone, Debug)]
+pub struct Return {
+    pub value: Option<Box<Type>>,
+}
+
+impl Return {
+    pub fn exec(&mut self, env: &mut PyNamespace, namespace: Namespace) -> Exec<Type> {
+        let value = match &self.value {
+            None => None,
+            Some(x) => Some(Box::new(Type::Constant(deref_expression(*x.clone(), env, namespace)?))),
+        };
+        Ok(Type::Return(Box::new(Return { value })))
+    }
+}
+
+/// ## struct Yield
+/// yield表达式，value为None时产出None，send的值是表达式的值
+#[derive(Clone, Debug)]
+pub struct Yield {
+    pub value: Option<Box<Type>>,
+}
+
+/// ## struct YieldFrom
+/// `yield from value`，依次产出value中的元素，子生成器的返回值是表达式的值
+#[derive(Clone, Debug)]
+pub struct YieldFrom {
+    pub value: Box<Type>,
+}
@@ -0,0 +1,340 @@
+use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::builtin::obj_builtin_function;
+use crate::ast::data_type::class::{builtin_class, class_of, obj_class, type_of, PyClass};
+use crate::ast::data_type::dict::{dict_get_str, dict_remove_str, dict_set_str, obj_dict_shared};
+use crate::ast::data_type::exception::{error, is_instance_of, raise, stop_value, Exec, Raised};
+use crate::ast::data_type::function::call_object;
+use crate::ast::data_type::int::obj_int;
+use crate::ast::data_type::method::{obj_builtin_method, obj_method};
+use crate::ast::data_type::module::module_name;
+use crate::ast::data_type::none::obj_none;
+use crate::ast::data_type::object::{PyObjAttr, PyObject};
+use crate::ast::data_type::signature::Signature;
+use crate::ast::data_type::str::obj_str;
+use crate::ast::data_type::tuple::obj_tuple;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::tools::tokenize::TOKEN_INFO_FIELDS;
+
+/// object中定义、对所有对象都有效的方法，子类通过super()调用它们
+pub const OBJECT_SLOTS: [&str; 4] = ["__init__", "__getattribute__", "__setattr__", "__delattr__"];
+
+/// 只在python中定义的类里查找钩子，内置类型的默认行为不算
+fn hook(obj: &PyObject, name: &str) -> Option<PyObject> {
+    obj.class().and_then(|x| x.lookup(name))
+}
+
+fn attribute_error(obj: &PyObject, name: &str) -> Raised {
+    let message = match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Class(x))) => format!("type object '{}' has no attribute '{}'", x.name, name),
+        Ok(PyObjAttr::Rust(DataType::Module(x))) if x.borrow().initializing => format!(
+            "partially initialized module '{}' has no attribute '{}' (most likely due to a circular import)",
+            x.borrow().name,
+            name
+        ),
+        Ok(PyObjAttr::Rust(DataType::Module(x))) => {
+            format!("module '{}' has no attribute '{}'", x.borrow().name, name)
+        }
+        _ => format!("'{}' object has no attribute '{}'", obj.return_identity(), name),
+    };
+    error("AttributeError", message)
+}
+
+/// 类中定义的函数通过实例访问时成为绑定方法
+fn bind(obj: &PyObject, value: PyObject) -> PyObject {
+    match value.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Function(_))) => obj_method(obj.clone(), value),
+        _ => value,
+    }
+}
+
+/// ## fn obj_lookup
+/// 查找属性，找不到时返回None
+/// 类中定义了`__getattribute__`时由它处理，否则按默认规则查找，
+/// 找不到或`__getattribute__`抛出AttributeError时调用`__getattr__`
+pub fn obj_lookup(obj: &PyObject, name: &str, namespace: Namespace, env: &mut PyNamespace) -> Exec<Option<PyObject>> {
+    let name_obj = obj_str(name.to_string());
+    if let Some(x) = hook(obj, "__getattribute__") {
+        let value = call_object(&x, vec![obj.clone(), name_obj.clone()], vec![], namespace.clone(), env);
+        return match (value, hook(obj, "__getattr__")) {
+            (Err(e), Some(x)) if is_instance_of(&e, "AttributeError") => {
+                Ok(Some(call_object(&x, vec![obj.clone(), name_obj], vec![], namespace, env)?))
+            }
+            (x, _) => x.map(Some),
+        };
+    }
+    // the attributes of a module are its global variables
+    if let Some(module) = module_name(obj) {
+        return Ok(env.get_module_variable(&module, name).or_else(|| generic_getattr(obj, name)));
+    }
+    if let Some(x) = generic_getattr(obj, name) {
+        return Ok(Some(x));
+    }
+    match hook(obj, "__getattr__") {
+        Some(x) => Ok(Some(call_object(&x, vec![obj.clone(), name_obj], vec![], namespace, env)?)),
+        None => Ok(None),
+    }
+}
+
+/// ## fn obj_getattr
+/// 相当于getattr(obj, name)，找不到时抛出AttributeError
+pub fn obj_getattr(obj: &PyObject, name: &str, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyObject> {
+    match obj_lookup(obj, name, namespace, env)? {
+        Some(x) => Ok(x),
+        None => Err(attribute_error(obj, name)),
+    }
+}
+
+/// ## fn generic_getattr
+/// object.__getattribute__：依次查找实例的`__dict__`和类的mro
+pub fn generic_getattr(obj: &PyObject, name: &str) -> Option<PyObject> {
+    match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Class(x))) => return class_getattr(obj, &x, name),
+        Ok(PyObjAttr::Rust(DataType::Function(x))) if name == "__name__" => return Some(obj_str(x.name)),
+        Ok(PyObjAttr::Rust(DataType::BuiltinFunction(x))) if name == "__name__" => {
+            // the slots of object are named like object.__setattr__
+            return Some(obj_str(x.rsplit('.').next().unwrap_or_default().to_string()));
+        }
+        Ok(PyObjAttr::Rust(DataType::Tuple(x))) if obj.return_identity() == "TokenInfo" => {
+            if let Some(index) = TOKEN_INFO_FIELDS.iter().position(|x| *x == name) {
+                return x.get(index).cloned();
+            }
+        }
+        Ok(PyObjAttr::Rust(DataType::Slice(start, stop, step))) => match name {
+            "start" => return Some(*start),
+            "stop" => return Some(*stop),
+            "step" => return Some(*step),
+            _ => {}
+        },
+        Ok(PyObjAttr::Rust(DataType::Exception(x))) => {
+            let exception = x.borrow();
+            match name {
+                "args" => return Some(obj_tuple(exception.args.clone())),
+                "__cause__" => return Some(exception.cause.clone().unwrap_or_else(obj_none)),
+                "__context__" => return Some(exception.context.clone().unwrap_or_else(obj_none)),
+                "__suppress_context__" => return Some(obj_bool(exception.suppress_context)),
+                "value" if is_instance_of(obj, "StopIteration") => return Some(stop_value(obj)),
+                "msg" if is_instance_of(obj, "SyntaxError") => {
+                    return Some(exception.args.first().cloned().unwrap_or_else(obj_none))
+                }
+                _ => {}
+            }
+            if let Some(location) = &exception.location {
+                match name {
+                    "filename" => return Some(obj_str(location.filename.clone())),
+                    "lineno" => return Some(obj_int(location.lineno as i64)),
+                    "offset" => return Some(obj_int(location.offset as i64)),
+                    "text" => return Some(obj_str(location.text.clone())),
+                    _ => {}
+                }
+            }
+        }
+        Ok(PyObjAttr::Rust(DataType::File(x))) => {
+            let file = x.borrow();
+            match name {
+                "name" => return Some(obj_str(file.name.clone())),
+                "mode" => return Some(obj_str(file.mode.clone())),
+                "closed" => return Some(obj_bool(file.closed)),
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+    if obj.return_identity() == "super" {
+        return super_getattr(obj, name);
+    }
+    let dict = match obj.get_value("__dict__".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Dict(x))) => Some(x),
+        _ => None,
+    };
+    match (name, dict) {
+        ("__class__", _) => return Some(type_of(obj)),
+        ("__dict__", Some(x)) => return Some(obj_dict_shared(x)),
+        (_, Some(x)) => {
+            if let Some(x) = dict_get_str(&x, name) {
+                return Some(x);
+            }
+        }
+        _ => {}
+    }
+    mro_getattr(obj, class_of(&type_of(obj)).mro_classes(), name)
+}
+
+/// 按mro查找，遇到内置类型时查找对象自身的Rust方法
+fn mro_getattr(obj: &PyObject, classes: Vec<PyClass>, name: &str) -> Option<PyObject> {
+    for class in classes {
+        if !class.is_builtin() {
+            if let Some(x) = class.get(name) {
+                return Some(bind(obj, x));
+            }
+        } else if obj.has_behavior(name) || (class.name == "object" && OBJECT_SLOTS.contains(&name)) {
+            return Some(obj_builtin_method(obj.clone(), name.to_string()));
+        }
+    }
+    None
+}
+
+/// 类的属性，函数不绑定
+fn class_getattr(class_obj: &PyObject, class: &PyClass, name: &str) -> Option<PyObject> {
+    match name {
+        "__name__" => Some(obj_str(class.name.clone())),
+        "__module__" => Some(obj_str(class.module.clone())),
+        "__bases__" => Some(obj_tuple(class.bases.clone())),
+        "__mro__" => {
+            let mut mro = vec![class_obj.clone()];
+            mro.extend(class.mro.clone());
+            Some(obj_tuple(mro))
+        }
+        "__dict__" => Some(obj_dict_shared(class.dict.clone())),
+        "__class__" => Some(obj_class(builtin_class("type"))),
+        // every class inherits the slots of object, such as object.__setattr__(self, name, value)
+        _ if OBJECT_SLOTS.contains(&name) => class
+            .lookup(name)
+            .or_else(|| Some(obj_builtin_function(format!("object.{}", name)))),
+        _ => class.lookup(name),
+    }
+}
+
+/// super对象的属性，在`__self__`的mro中从`__thisclass__`之后开始查找
+fn super_getattr(obj: &PyObject, name: &str) -> Option<PyObject> {
+    let get = |name: &str| match obj.get_value(name.to_string()) {
+        Ok(PyObjAttr::Interpreter(x)) => *x,
+        _ => panic!("Error to get super"),
+    };
+    if name == "__thisclass__" || name == "__self__" {
+        return Some(get(name));
+    }
+    let (this_class, target) = (class_of(&get("__thisclass__")), get("__self__"));
+    let (classes, is_class) = match target.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Class(x))) => (x.mro_classes(), true),
+        _ => (class_of(&type_of(&target)).mro_classes(), false),
+    };
+    let start = classes
+        .iter()
+        .position(|x| *x == this_class)
+        .map_or(classes.len(), |x| x + 1);
+    if is_class {
+        return classes[start..].iter().find_map(|x| x.get(name));
+    }
+    mro_getattr(&target, classes[start..].to_vec(), name)
+}
+
+/// ## fn obj_setattr
+/// 相当于setattr(obj, name, value)，类中定义了`__setattr__`时由它处理
+pub fn obj_setattr(obj: &PyObject, name: &str, value: PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<()> {
+    if let Some(module) = module_name(obj) {
+        env.set_module_variable(&module, name.to_string(), value);
+        return Ok(());
+    }
+    match hook(obj, "__setattr__") {
+        Some(x) => {
+            call_object(&x, vec![obj.clone(), obj_str(name.to_string()), value], vec![], namespace, env)?;
+            Ok(())
+        }
+        None => generic_setattr(obj, name, value),
+    }
+}
+
+/// object.__setattr__：写入实例或类的`__dict__`
+pub fn generic_setattr(obj: &PyObject, name: &str, value: PyObject) -> Exec<()> {
+    match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Class(x))) if x.is_builtin() => {
+            return raise("TypeError", format!("can't set attributes of built-in/extension type '{}'", x.name))
+        }
+        Ok(PyObjAttr::Rust(DataType::Class(x))) => {
+            dict_set_str(&x.dict, name, value);
+            return Ok(());
+        }
+        _ => {}
+    }
+    match obj.get_value("__dict__".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Dict(x))) => {
+            dict_set_str(&x, name, value);
+            Ok(())
+        }
+        _ => Err(attribute_error(obj, name)),
+    }
+}
+
+/// ## fn obj_delattr
+/// 相当于delattr(obj, name)，类中定义了`__delattr__`时由它处理
+pub fn obj_delattr(obj: &PyObject, name: &str, namespace: Namespace, env: &mut PyNamespace) -> Exec<()> {
+    if let Some(module) = module_name(obj) {
+        if !env.delete_module_variable(&module, name) {
+            return Err(attribute_error(obj, name));
+        }
+        return Ok(());
+    }
+    match hook(obj, "__delattr__") {
+        Some(x) => {
+            call_object(&x, vec![obj.clone(), obj_str(name.to_string())], vec![], namespace, env)?;
+            Ok(())
+        }
+        None => generic_delattr(obj, name),
+    }
+}
+
+/// object.__delattr__：从实例或类的`__dict__`中删除
+pub fn generic_delattr(obj: &PyObject, name: &str) -> Exec<()> {
+    let dict = match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Class(x))) if x.is_builtin() => {
+            return raise("TypeError", format!("can't set attributes of built-in/extension type '{}'", x.name))
+        }
+        Ok(PyObjAttr::Rust(DataType::Class(x))) => x.dict,
+        _ => match obj.get_value("__dict__".to_string()) {
+            Ok(PyObjAttr::Rust(DataType::Dict(x))) => x,
+            _ => return Err(attribute_error(obj, name)),
+        },
+    };
+    if dict_remove_str(&dict, name).is_none() {
+        return Err(attribute_error(obj, name));
+    }
+    Ok(())
+}
+
+/// ## fn object_slot
+/// 调用object中的方法，用于`super().__setattr__(name, value)`等
+pub fn object_slot(
+    obj: &PyObject,
+    method: &str,
+    args: Vec<PyObject>,
+    keywords: Vec<(String, PyObject)>,
+    _namespace: Namespace,
+    _env: &mut PyNamespace,
+) -> Exec<PyObject> {
+    let params = match method {
+        "__init__" if !args.is_empty() || !keywords.is_empty() => {
+            return raise("TypeError", "object.__init__() takes exactly one argument (the instance to initialize)")
+        }
+        "__init__" => return Ok(obj_none()),
+        "__setattr__" => vec!["name".to_string(), "value".to_string()],
+        _ => vec!["name".to_string()],
+    };
+    let bound = match Signature::positional(params).bind(method, args, keywords) {
+        Ok(x) => x,
+        Err(x) => return raise("TypeError", x),
+    };
+    let name = match bound[0].1.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Str(x))) => x,
+        _ => {
+            return raise(
+                "TypeError",
+                format!("attribute name must be string, not '{}'", bound[0].1.return_identity()),
+            )
+        }
+    };
+    match method {
+        "__getattribute__" => match generic_getattr(obj, name.as_str()) {
+            Some(x) => Ok(x),
+            None => Err(attribute_error(obj, name.as_str())),
+        },
+        "__setattr__" => {
+            generic_setattr(obj, name.as_str(), bound[1].1.clone())?;
+            Ok(obj_none())
+        }
+        _ => {
+            generic_delattr(obj, name.as_str())?;
+            Ok(obj_none())
+        }
+    }
+}
@@ -0,0 +1,560 @@
+use std::collections::HashMap;
+
+use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::attribute::{obj_delattr, obj_getattr, obj_lookup, obj_setattr, object_slot};
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::class::{builtin_class, class_of, obj_class, obj_object, type_of, PyClass};
+use crate::ast::data_type::core_type::{build_rust_method, obj_parser};
+use crate::ast::data_type::exception::{exception_base, exception_names, obj_exception, obj_stop_iteration, raise, raise_exception, Exec};
+use crate::ast::data_type::file::open_file;
+use crate::ast::data_type::function::call_object;
+use crate::ast::data_type::float::obj_float;
+use crate::ast::data_type::int::obj_int;
+use crate::ast::data_type::none::{obj_none, obj_not_implemented};
+use crate::ast::data_type::iterator::{next_or_return, obj_iter, obj_iterator, IterState};
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{obj_to_bool, obj_to_repr, obj_to_str, HashMapAttr, PyObject, PyResult};
+use crate::ast::data_type::range::obj_range;
+use crate::ast::data_type::signature::Signature;
+use crate::ast::data_type::slice::obj_slice;
+use crate::ast::data_type::str::obj_str;
+use crate::ast::data_type::tuple::obj_tuple;
+use crate::ast::error::ErrorType;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+use crate::tools::tokenize;
+
+/// ## fn init_builtins
+/// 在builtin命名空间中注册内置类型和内置函数
+pub fn init_builtins(env: &mut PyNamespace) {
+    for name in ["object", "int", "float", "str", "bool", "super", "range", "slice", "enumerate", "zip"] {
+        env.set_builtin(name.to_string(), obj_class(builtin_class(name)));
+    }
+    for name in exception_names() {
+        env.set_builtin(name.to_string(), obj_class(builtin_class(name)));
+    }
+    for name in [
+        "isinstance", "issubclass", "iter", "next", "len", "getattr", "setattr", "hasattr", "delattr", "open",
+    ] {
+        env.set_builtin(name.to_string(), obj_builtin_function(name.to_string()));
+    }
+    env.set_builtin("NotImplemented".to_string(), obj_not_implemented());
+}
+
+pub fn obj_builtin_function(function: String) -> PyObject {
+    let name = "builtin_function_or_method".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__bool__"), vec![]),
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+    ];
+    build_method!(
+        name: name;
+        data:DataType::BuiltinFunction(function);
+        method_vec:method_vec
+    )
+}
+pub fn builtin_function_behaviour(method: String, args: HashMapAttr, _namespace: Namespace, _env: &mut PyNamespace) -> Exec<PyResult> {
+    let function = match obj_parser("self".to_string(), "x".to_string(), args) {
+        Ok(DataType::BuiltinFunction(x)) => x,
+        _ => panic!("Error to get builtin function"),
+    };
+    Ok(match method.as_str() {
+        "__bool__" => PyResult::Some(obj_bool(true)),
+        "__str__" | "__repr__" => PyResult::Some(obj_str(format!("<built-in function {}>", function))),
+        _ => PyResult::None,
+    })
+}
+
+/// ## fn call_builtin
+/// 调用内置函数
+pub fn call_builtin(
+    name: &str,
+    args: Vec<PyObject>,
+    keywords: Vec<(String, PyObject)>,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<PyObject> {
+    if name == "open" {
+        return builtin_open(args, keywords);
+    }
+    // object.__setattr__(obj, name, value) and the other slots of object take the object as the first argument
+    if let Some(method) = name.strip_prefix("object.") {
+        if args.is_empty() {
+            return raise("TypeError", format!("descriptor '{}' of 'object' object needs an argument", method));
+        }
+        return object_slot(&args[0], method, args[1..].to_vec(), keywords, namespace, env);
+    }
+    if !keywords.is_empty() {
+        return raise("TypeError", format!("{}() takes no keyword arguments", name));
+    }
+    Ok(match name {
+        "tokenize.generate_tokens" => {
+            if args.len() != 1 {
+                let message = format!("generate_tokens() takes exactly one argument ({} given)", args.len());
+                return raise("TypeError", message);
+            }
+            generate_tokens(&args[0], namespace, env)?
+        }
+        "sys.getrecursionlimit" => {
+            if !args.is_empty() {
+                return raise("TypeError", format!("getrecursionlimit() takes no arguments ({} given)", args.len()));
+            }
+            obj_int(env.recursion_limit as i64)
+        }
+        "sys.setrecursionlimit" => {
+            if args.len() != 1 {
+                return raise("TypeError", format!("setrecursionlimit expected 1 argument, got {}", args.len()));
+            }
+            let limit = match args[0].get_value("x".to_string()) {
+                Ok(PyObjAttr::Rust(DataType::Int(x))) => x,
+                _ => return raise("TypeError", "an integer is required"),
+            };
+            if limit < 1 {
+                return raise("ValueError", "recursion limit must be greater or equal than 1");
+            }
+            env.recursion_limit = limit as usize;
+            obj_none()
+        }
+        "iter" => {
+            if args.len() != 1 {
+                return raise("TypeError", format!("iter expected 1 argument, got {}", args.len()));
+            }
+            match obj_iter(&args[0], namespace, env)? {
+                Some(x) => x,
+                None => return raise("TypeError", format!("'{}' object is not iterable", args[0].return_identity())),
+            }
+        }
+        "next" => {
+            if args.is_empty() || args.len() > 2 {
+                return raise("TypeError", format!("next expected at least 1 argument, got {}", args.len()));
+            }
+            match (next_or_return(&args[0], namespace.clone(), env)?, args.get(1)) {
+                (Ok(x), _) => x,
+                (Err(_), Some(default)) => default.clone(),
+                (Err(x), None) => return raise_exception(obj_stop_iteration(x)),
+            }
+        }
+        "len" => {
+            if args.len() != 1 {
+                return raise("TypeError", format!("len() takes exactly one argument ({} given)", args.len()));
+            }
+            builtin_len(&args[0], namespace, env)?
+        }
+        "getattr" | "setattr" | "hasattr" | "delattr" => {
+            let expected = match name {
+                "setattr" => 3,
+                _ => 2,
+            };
+            if args.len() < expected || (args.len() > expected && name != "getattr") || args.len() > 3 {
+                return raise("TypeError", format!("{} expected {} arguments, got {}", name, expected, args.len()));
+            }
+            let attr = match args[1].get_value("x".to_string()) {
+                Ok(PyObjAttr::Rust(DataType::Str(x))) => x,
+                _ => return raise("TypeError", format!("{}(): attribute name must be string", name)),
+            };
+            match name {
+                "getattr" => match (obj_lookup(&args[0], attr.as_str(), namespace.clone(), env)?, args.get(2)) {
+                    (Some(x), _) => x,
+                    (None, Some(default)) => default.clone(),
+                    (None, None) => obj_getattr(&args[0], attr.as_str(), namespace, env)?,
+                },
+                "hasattr" => obj_bool(obj_lookup(&args[0], attr.as_str(), namespace, env)?.is_some()),
+                "setattr" => {
+                    obj_setattr(&args[0], attr.as_str(), args[2].clone(), namespace, env)?;
+                    obj_none()
+                }
+                _ => {
+                    obj_delattr(&args[0], attr.as_str(), namespace, env)?;
+                    obj_none()
+                }
+            }
+        }
+        "isinstance" | "issubclass" => {
+            if args.len() != 2 {
+                return raise("TypeError", format!("{} expected 2 arguments, got {}", name, args.len()));
+            }
+            let class = if name == "isinstance" {
+                class_of(&type_of(&args[0]))
+            } else {
+                match args[0].get_value("x".to_string()) {
+                    Ok(PyObjAttr::Rust(DataType::Class(x))) => *x,
+                    _ => return raise("TypeError", "issubclass() arg 1 must be a class"),
+                }
+            };
+            obj_bool(match_classinfo(name, &class, &args[1])?)
+        }
+        _ => return raise("NameError", format!("name '{}' is not defined", name)),
+    })
+}
+
+/// ## fn builtin_len
+/// len(obj)：调用`__len__`，结果必须是非负的int
+fn builtin_len(obj: &PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyObject> {
+    let length = match obj.clone().len(namespace, env)? {
+        PyResult::Some(x) => x,
+        PyResult::Err(ErrorType::ObjMethodCallError(_)) | PyResult::None => {
+            return raise("TypeError", format!("object of type '{}' has no len()", obj.return_identity()))
+        }
+        PyResult::Err(x) => return Err(x.into()),
+        _ => panic!("Error at running"),
+    };
+    match length.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Int(x))) if x < 0 => raise("ValueError", "__len__() should return >= 0"),
+        Ok(PyObjAttr::Rust(DataType::Int(x))) => Ok(obj_int(x)),
+        Ok(PyObjAttr::Rust(DataType::Bool(x))) => Ok(obj_int(x as i64)),
+        _ => raise(
+            "TypeError",
+            format!("'{}' object cannot be interpreted as an integer", length.return_identity()),
+        ),
+    }
+}
+
+/// ## fn builtin_open
+/// open(file, mode='r', encoding=None)，文件总是按UTF-8读写
+fn builtin_open(args: Vec<PyObject>, keywords: Vec<(String, PyObject)>) -> Exec<PyObject> {
+    let signature = Signature {
+        args: vec!["file".to_string(), "mode".to_string(), "encoding".to_string()],
+        defaults: vec![obj_str("r".to_string()), obj_none()],
+        ..Default::default()
+    };
+    let bound: HashMap<String, PyObject> = match signature.bind("open", args, keywords) {
+        Ok(x) => x.into_iter().collect(),
+        Err(x) => return raise("TypeError", x),
+    };
+    let text = |name: &str| match bound[name].get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Str(x))) => Ok(x),
+        _ => raise(
+            "TypeError",
+            format!("open() argument '{}' must be str, not {}", name, bound[name].return_identity()),
+        ),
+    };
+    open_file(text("file")?, text("mode")?)
+}
+
+/// isinstance和issubclass的第二个参数可以是类或者类的元组
+fn match_classinfo(name: &str, class: &PyClass, classinfo: &PyObject) -> Exec<bool> {
+    match classinfo.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Class(x))) => Ok(class.is_subclass(&x)),
+        Ok(PyObjAttr::Rust(DataType::Tuple(x))) => {
+            for item in x.iter() {
+                if match_classinfo(name, class, item)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+        _ => raise("TypeError", format!("{}() arg 2 must be a type or tuple of types", name)),
+    }
+}
+
+/// ## fn builtin_new
+/// 调用内置类型创建对象，如int("1")
+pub fn builtin_new(
+    name: &str,
+    args: Vec<PyObject>,
+    keywords: Vec<(String, PyObject)>,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<PyObject> {
+    // the keyword arguments of an exception subclass are for its __init__
+    if exception_base(name).is_some() {
+        return Ok(obj_exception(name, args));
+    }
+    if !keywords.is_empty() {
+        return raise("TypeError", format!("{}() takes no keyword arguments", name));
+    }
+    let at_most = |n: usize| match args.len() > n {
+        true => raise(
+            "TypeError",
+            format!(
+                "{}() takes at most {} argument{} ({} given)",
+                name,
+                n,
+                if n == 1 { "" } else { "s" },
+                args.len()
+            ),
+        ),
+        false => Ok(()),
+    };
+    Ok(match name {
+        "object" => {
+            if !args.is_empty() {
+                return raise("TypeError", "object() takes no arguments");
+            }
+            obj_object()
+        }
+        "int" => {
+            at_most(2)?;
+            match args.get(1) {
+                None => args.first().map_or(Ok(obj_int(0)), to_int)?,
+                Some(base) => match (args[0].get_value("x".to_string()), base.get_value("x".to_string())) {
+                    (Ok(PyObjAttr::Rust(DataType::Str(x))), Ok(PyObjAttr::Rust(DataType::Int(base)))) => {
+                        parse_int(x.as_str(), base as u32)?
+                    }
+                    _ => return raise("TypeError", "int() can't convert non-string with explicit base"),
+                },
+            }
+        }
+        "float" => {
+            at_most(1)?;
+            args.first().map_or(Ok(obj_float(0.0)), to_float)?
+        }
+        "str" => {
+            at_most(1)?;
+            match args.first() {
+                None => obj_str(String::new()),
+                Some(x) => obj_str(obj_to_str(x.clone(), namespace, env)?),
+            }
+        }
+        "bool" => {
+            at_most(1)?;
+            match args.first() {
+                None => obj_bool(false),
+                Some(x) => obj_bool(obj_to_bool(x.clone(), namespace, env)?),
+            }
+        }
+        "super" => new_super(args, namespace, env)?,
+        "range" => {
+            let mut values = vec![];
+            for arg in args.iter() {
+                match arg.get_value("x".to_string()) {
+                    Ok(PyObjAttr::Rust(DataType::Int(x))) => values.push(x),
+                    Ok(PyObjAttr::Rust(DataType::Bool(x))) => values.push(x as i64),
+                    _ => {
+                        return raise(
+                            "TypeError",
+                            format!("'{}' object cannot be interpreted as an integer", arg.return_identity()),
+                        )
+                    }
+                }
+            }
+            match values[..] {
+                [] => return raise("TypeError", "range expected 1 argument, got 0"),
+                [stop] => obj_range(0, stop, 1),
+                [start, stop] => obj_range(start, stop, 1),
+                [_, _, 0] => return raise("ValueError", "range() arg 3 must not be zero"),
+                [start, stop, step] => obj_range(start, stop, step),
+                _ => return raise("TypeError", format!("range expected at most 3 arguments, got {}", values.len())),
+            }
+        }
+        "slice" => match args.len() {
+            0 => return raise("TypeError", "slice expected at least 1 argument, got 0"),
+            1 => obj_slice(obj_none(), args[0].clone(), obj_none()),
+            2 => obj_slice(args[0].clone(), args[1].clone(), obj_none()),
+            3 => obj_slice(args[0].clone(), args[1].clone(), args[2].clone()),
+            x => return raise("TypeError", format!("slice expected at most 3 arguments, got {}", x)),
+        },
+        "enumerate" => {
+            at_most(2)?;
+            let iterable = match args.first() {
+                Some(x) => x,
+                None => return raise("TypeError", "enumerate() missing required argument 'iterable'"),
+            };
+            let iterator = match obj_iter(iterable, namespace, env)? {
+                Some(x) => x,
+                None => return raise("TypeError", format!("'{}' object is not iterable", iterable.return_identity())),
+            };
+            let start = match args.get(1).map(|x| x.get_value("x".to_string())) {
+                None => 0,
+                Some(Ok(PyObjAttr::Rust(DataType::Int(x)))) => x,
+                Some(_) => return raise("TypeError", "enumerate() start must be an integer"),
+            };
+            obj_iterator("enumerate", IterState::Enumerate(iterator, start))
+        }
+        "zip" => {
+            let mut iterators = vec![];
+            for (index, arg) in args.iter().enumerate() {
+                match obj_iter(arg, namespace.clone(), env)? {
+                    Some(x) => iterators.push(x),
+                    None => return raise("TypeError", format!("zip argument #{} must support iteration", index + 1)),
+                }
+            }
+            obj_iterator("zip", IterState::Zip(iterators))
+        }
+        _ => return raise("TypeError", format!("cannot create '{}' instances", name)),
+    })
+}
+
+fn to_int(obj: &PyObject) -> Exec<PyObject> {
+    match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Int(x))) => Ok(obj_int(x)),
+        Ok(PyObjAttr::Rust(DataType::Bool(x))) => Ok(obj_int(x as i64)),
+        Ok(PyObjAttr::Rust(DataType::Float(x))) => {
+            if x.is_nan() {
+                return raise("ValueError", "cannot convert float NaN to integer");
+            }
+            if x.is_infinite() {
+                return raise("OverflowError", "cannot convert float infinity to integer");
+            }
+            Ok(obj_int(x.trunc() as i64))
+        }
+        Ok(PyObjAttr::Rust(DataType::Str(x))) => parse_int(x.as_str(), 10),
+        _ => raise(
+            "TypeError",
+            format!(
+                "int() argument must be a string, a bytes-like object or a number, not '{}'",
+                obj.return_identity()
+            ),
+        ),
+    }
+}
+
+/// 允许首尾空白和数字之间的单个下划线
+fn parse_int(s: &str, base: u32) -> Exec<PyObject> {
+    if !(2..=36).contains(&base) {
+        return raise("ValueError", "int() base must be >= 2 and <= 36");
+    }
+    let digits = s.trim();
+    let valid = !digits.starts_with('_')
+        && !digits.ends_with('_')
+        && !digits.contains("__");
+    match i64::from_str_radix(digits.replace('_', "").as_str(), base) {
+        Ok(x) if valid => Ok(obj_int(x)),
+        _ => raise("ValueError", format!("invalid literal for int() with base {}: '{}'", base, s)),
+    }
+}
+
+fn to_float(obj: &PyObject) -> Exec<PyObject> {
+    match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Float(x))) => Ok(obj_float(x)),
+        Ok(PyObjAttr::Rust(DataType::Int(x))) => Ok(obj_float(x as f64)),
+        Ok(PyObjAttr::Rust(DataType::Bool(x))) => Ok(obj_float(if x { 1.0 } else { 0.0 })),
+        Ok(PyObjAttr::Rust(DataType::Str(x))) => match x.trim().parse::<f64>() {
+            Ok(y) => Ok(obj_float(y)),
+            Err(_) => raise("ValueError", format!("could not convert string to float: '{}'", x)),
+        },
+        _ => raise(
+            "TypeError",
+            format!("float() argument must be a string or a number, not '{}'", obj.return_identity()),
+        ),
+    }
+}
+
+/// ## fn new_super
+/// 没有参数时使用方法所在的类和第一个参数
+fn new_super(args: Vec<PyObject>, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyObject> {
+    let (class, obj) = match args.len() {
+        0 => match (
+            env.get_variable(namespace.clone(), "__class__".to_string()),
+            env.get_variable(namespace, "__self__".to_string()),
+        ) {
+            (Ok(class), Ok(obj)) => (class, obj),
+            _ => return raise("RuntimeError", "super(): __class__ cell not found"),
+        },
+        2 => (args[0].clone(), args[1].clone()),
+        n => return raise("TypeError", format!("super() takes 0 or 2 arguments ({} given)", n)),
+    };
+    let this_class = match class.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Class(x))) => *x,
+        _ => return raise("TypeError", format!("super() argument 1 must be type, not {}", class.return_identity())),
+    };
+    let obj_class = match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Class(x))) => *x,
+        _ => class_of(&type_of(&obj)),
+    };
+    if !obj_class.is_subclass(&this_class) {
+        return raise("TypeError", "super(type, obj): obj must be an instance or subtype of type");
+    }
+    Ok(obj_super(class, obj))
+}
+
+/// ## fn obj_super
+/// super对象，在`__self__`的mro中从`__thisclass__`之后开始查找
+pub fn obj_super(this_class: PyObject, obj: PyObject) -> PyObject {
+    let name = "super".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+    ];
+    let attr_vec: Vec<(String, PyObjAttr)> = vec![
+        (String::from("__thisclass__"), PyObjAttr::Interpreter(Box::new(this_class))),
+        (String::from("__self__"), PyObjAttr::Interpreter(Box::new(obj))),
+    ];
+    PyObject::default()
+        .identity(name)
+        .attr(attr_vec)
+        .extend_behavior(method_vec)
+}
+pub fn super_behaviour(method: String, args: HashMapAttr, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+    let obj = match args.get("self") {
+        Some(PyObjAttr::Interpreter(x)) => *x.clone(),
+        _ => panic!("Error to get super"),
+    };
+    let get = |name: &str| match obj.get_value(name.to_string()) {
+        Ok(PyObjAttr::Interpreter(x)) => *x,
+        _ => panic!("Error to get super"),
+    };
+    match method.as_str() {
+        "__str__" | "__repr__" => {
+            let this_class = obj_to_repr(get("__thisclass__"), namespace.clone(), env)?;
+            let obj_class = class_of(&type_of(&get("__self__"))).name;
+            Ok(PyResult::Some(obj_str(format!("<super: {}, <{} object>>", this_class, obj_class))))
+        }
+        _ => Ok(PyResult::None),
+    }
+}
+
+/// ## fn generate_tokens
+/// tokenize.generate_tokens(readline)：调用readline直到返回空字符串，
+/// 返回依次产生每个token的TokenInfo的生成器，type是token类型的编号，如tokenize.NAME
+fn generate_tokens(readline: &PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyObject> {
+    let mut source = String::new();
+    loop {
+        let line = call_object(readline, vec![], vec![], namespace.clone(), env)?;
+        match line.get_value("x".to_string()) {
+            Ok(PyObjAttr::Rust(DataType::Str(x))) if x.is_empty() => break,
+            Ok(PyObjAttr::Rust(DataType::Str(x))) => source += x.as_str(),
+            _ => {
+                let message = format!("readline() returned a non-string object: '{}'", line.return_identity());
+                return raise("TypeError", message);
+            }
+        }
+    }
+    let tokens = match tokenize::generate_tokens(source.clone()) {
+        Ok(x) => x,
+        Err(errors) => return Err(ErrorType::LexError(errors[0].clone()).into()),
+    };
+    let lines: Vec<&str> = source.split_inclusive('\n').collect();
+    let position = |(row, col): (usize, usize)| obj_tuple(vec![obj_int(row as i64), obj_int(col as i64)]);
+    let items = tokens
+        .into_iter()
+        .map(|x| {
+            // the physical lines the token is on, empty after the end of the source
+            let line = lines.get(x.start.0 - 1..x.end.0.min(lines.len())).unwrap_or_default().concat();
+            obj_token_info(vec![
+                obj_int(tokenize::token_type(x.name)),
+                obj_str(x.string),
+                position(x.start),
+                position(x.end),
+                obj_str(line),
+            ])
+        })
+        .collect();
+    Ok(obj_iterator("generator", IterState::Items(items, 0)))
+}
+
+/// ## fn obj_token_info
+/// tokenize.TokenInfo：字段是type、string、start、end和line的tuple
+fn obj_token_info(items: Vec<PyObject>) -> PyObject {
+    obj_tuple(items).identity("TokenInfo".to_string())
+}
+
+/// ## fn token_info_repr
+/// TokenInfo的repr，如`TokenInfo(type=1 (NAME), string='x', start=(1, 0), end=(1, 1), line='x\n')`
+pub fn token_info_repr(items: Vec<PyObject>, namespace: Namespace, env: &mut PyNamespace) -> Exec<String> {
+    let mut fields = vec![];
+    for (field, value) in tokenize::TOKEN_INFO_FIELDS.iter().zip(items) {
+        let name = match value.get_value("x".to_string()) {
+            Ok(PyObjAttr::Rust(DataType::Int(x))) if *field == "type" => tokenize::token_type_name(x),
+            _ => None,
+        };
+        let value = obj_to_repr(value, namespace.clone(), env)?;
+        fields.push(match name {
+            Some(name) => format!("{}={} ({})", field, value, name),
+            None => format!("{}={}", field, value),
+        });
+    }
+    Ok(format!("TokenInfo({})", fields.join(", ")))
+}
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::core_type::build_rust_method;
+use crate::ast::data_type::exception::{raise, Exec};
+use crate::ast::data_type::core_type::{custom_behaviour, obj_parser};
+use crate::ast::data_type::int::obj_int;
+use crate::ast::data_type::iterator::{obj_iterator, IterState};
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{HashMapAttr, PyObject, PyResult};
+use crate::ast::data_type::slice::{seq_index, slice_positions, SeqIndex};
+use crate::ast::data_type::str::obj_str;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+
+pub fn obj_bytes(x: Vec<u8>) -> PyObject {
+    let name = "bytes".to_string();
+    let mut method_vec: Vec<(String, PyObjBehaviors)> =
+        build_method!(name:name.clone();param:vec!["self".to_string(),"other".to_string()]);
+    method_vec.append(&mut vec![
+        build_rust_method(name.clone(), String::from("__len__"), vec![]),
+        build_rust_method(name.clone(), String::from("__bool__"), vec![]),
+        build_rust_method(name.clone(), String::from("__iter__"), vec![]),
+        build_rust_method(name.clone(), String::from("__getitem__"), vec!["self".to_string(), "key".to_string()]),
+    ]);
+    build_method!(
+        name: name;
+        data:DataType::Bytes(x);
+        method_vec:method_vec
+    )
+}
+pub fn bytes_behaviour(method: String, args: HashMapAttr,_namespace: Namespace,_env:&mut PyNamespace) -> Exec<PyResult> {
+    let obj_x: DataType = obj_parser("self".to_string(), "x".to_string(), args.clone())
+        .unwrap_or_else(|x| panic!("{}", x));
+    if let PyResult::Some(x) = custom_behaviour(obj_x.clone(), method.clone(), args.clone())? {
+        return Ok(PyResult::Some(x));
+    }
+    match method.as_str() {
+        "__len__" => {
+            if let DataType::Bytes(x) = &obj_x {
+                return Ok(PyResult::Some(obj_int(x.len() as i64)));
+            }
+        }
+        "__bool__" => return Ok(PyResult::Some(obj_bool(obj_x.bool()))),
+        "__iter__" => {
+            if let DataType::Bytes(x) = &obj_x {
+                let items = x.iter().map(|x| obj_int(*x as i64)).collect();
+                return Ok(PyResult::Some(obj_iterator("bytes_iterator", IterState::Items(items, 0))));
+            }
+        }
+        "__str__" => return Ok(PyResult::Some(obj_str(obj_x.str()))),
+        "__getitem__" => {
+            if let (DataType::Bytes(x), Some(PyObjAttr::Interpreter(key))) = (&obj_x, args.get("key")) {
+                return Ok(PyResult::Some(match seq_index(key, x.len(), "byte")? {
+                    SeqIndex::Item(Some(index)) => obj_int(x[index] as i64),
+                    SeqIndex::Item(None) => return raise("IndexError", "index out of range"),
+                    SeqIndex::Slice(start, stop, step) => {
+                        obj_bytes(slice_positions(start, stop, step).into_iter().map(|index| x[index]).collect())
+                    }
+                }));
+            }
+        }
+        _ => {}
+    }
+    Ok(PyResult::None)
+}
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+
+use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::builtin::builtin_new;
+use crate::ast::data_type::core_type::{build_rust_method, obj_parser};
+use crate::ast::data_type::exception::{exception_base, raise, Exec};
+use crate::ast::data_type::function::call_object;
+use crate::ast::data_type::none::is_not_implemented;
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{obj_to_bool, obj_to_repr, HashMapAttr, PyObject, PyResult, Shared};
+use crate::ast::data_type::str::obj_str;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+
+/// ## struct PyClass
+/// 用class定义的类，内置类型也用它表示，此时module是builtins
+/// - module：定义类时的`__name__`
+/// - bases：基类
+/// - mro：C3线性化得到的方法解析顺序，不包括类自身
+/// - dict：类体中绑定的变量，键是str对象
+#[derive(Clone, Debug)]
+pub struct PyClass {
+    pub name: String,
+    pub module: String,
+    pub bases: Vec<PyObject>,
+    pub mro: Vec<PyObject>,
+    pub dict: Shared<Vec<(PyObject, PyObject)>>,
+}
+
+/// 内置类型按名字比较，其他类只和自身相等
+impl PartialEq for PyClass {
+    fn eq(&self, other: &Self) -> bool {
+        if self.is_builtin() || other.is_builtin() {
+            self.is_builtin() && other.is_builtin() && self.name == other.name
+        } else {
+            self.dict == other.dict
+        }
+    }
+}
+
+impl PyClass {
+    /// 计算mro，不能线性化时返回TypeError的信息
+    pub fn new(
+        name: String,
+        module: String,
+        bases: Vec<PyObject>,
+        dict: Vec<(PyObject, PyObject)>,
+    ) -> Result<Self, String> {
+        for (index, base) in bases.iter().enumerate() {
+            if bases[..index].contains(base) {
+                return Err(format!("duplicate base class {}", class_of(base).name));
+            }
+        }
+        let mut sequences: Vec<Vec<PyObject>> = bases
+            .iter()
+            .map(|x| {
+                let mut sequence = vec![x.clone()];
+                sequence.extend(class_of(x).mro);
+                sequence
+            })
+            .collect();
+        sequences.push(bases.clone());
+        let mro = match c3_merge(sequences) {
+            Some(x) => x,
+            None => {
+                let names: Vec<String> = bases.iter().map(|x| class_of(x).name).collect();
+                return Err(format!(
+                    "Cannot create a consistent method resolution order (MRO) for bases {}",
+                    names.join(", ")
+                ));
+            }
+        };
+        Ok(PyClass {
+            name,
+            module,
+            bases,
+            mro,
+            dict: Shared::new(dict),
+        })
+    }
+    pub fn is_builtin(&self) -> bool {
+        self.module == "builtins"
+    }
+    /// 类自身和mro中的所有类
+    pub fn mro_classes(&self) -> Vec<PyClass> {
+        let mut classes = vec![self.clone()];
+        classes.extend(self.mro.iter().map(class_of));
+        classes
+    }
+    /// 只在类自身的dict中查找
+    pub fn get(&self, name: &str) -> Option<PyObject> {
+        let key = obj_str(name.to_string());
+        self.dict
+            .borrow()
+            .iter()
+            .find(|(x, _)| *x == key)
+            .map(|(_, value)| value.clone())
+    }
+    /// 按mro查找类属性
+    pub fn lookup(&self, name: &str) -> Option<PyObject> {
+        self.mro_classes().into_iter().find_map(|x| x.get(name))
+    }
+    /// 判断是否是other的子类
+    pub fn is_subclass(&self, other: &PyClass) -> bool {
+        self.mro_classes().contains(other)
+    }
+    /// 实例的内存布局由mro中除object外的内置类型决定，所有内置异常的布局都与BaseException相同
+    pub fn solid_base(&self) -> Option<PyClass> {
+        self.mro_classes()
+            .into_iter()
+            .find(|x| x.is_builtin() && x.name != "object" && exception_base(&x.name).is_none_or(|x| x == "object"))
+    }
+}
+
+/// C3线性化的merge：每次取第一个不出现在其他序列尾部的头
+fn c3_merge(mut sequences: Vec<Vec<PyObject>>) -> Option<Vec<PyObject>> {
+    let mut result = vec![];
+    loop {
+        sequences.retain(|x| !x.is_empty());
+        if sequences.is_empty() {
+            return Some(result);
+        }
+        let head = sequences
+            .iter()
+            .map(|x| x[0].clone())
+            .find(|head| !sequences.iter().any(|x| x[1..].contains(head)))?;
+        for sequence in sequences.iter_mut() {
+            if sequence[0] == head {
+                sequence.remove(0);
+            }
+        }
+        result.push(head);
+    }
+}
+
+/// 从类对象中取出PyClass
+pub fn class_of(class_obj: &PyObject) -> PyClass {
+    match class_obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Class(x))) => *x,
+        _ => panic!("Error to get class"),
+    }
+}
+
+/// ## fn builtin_class
+/// 内置类型，object没有基类，bool是int的子类，内置异常按异常的层次继承
+pub fn builtin_class(name: &str) -> PyClass {
+    let bases = match (name, exception_base(name)) {
+        ("object", _) => vec![],
+        ("bool", _) => vec![obj_class(builtin_class("int"))],
+        (_, Some(base)) => vec![obj_class(builtin_class(base))],
+        _ => vec![obj_class(builtin_class("object"))],
+    };
+    PyClass::new(name.to_string(), "builtins".to_string(), bases, vec![]).unwrap()
+}
+
+/// ## fn type_of
+/// 对象的类型，相当于type(obj)
+pub fn type_of(obj: &PyObject) -> PyObject {
+    match obj.get_value("__class__".to_string()) {
+        Ok(PyObjAttr::Interpreter(x)) => *x,
+        _ => obj_class(builtin_class(obj.return_identity().as_str())),
+    }
+}
+
+pub fn obj_class(class: PyClass) -> PyObject {
+    let name = "type".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__bool__"), vec![]),
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+    ];
+    build_method!(
+        name: name;
+        data:DataType::Class(Box::new(class));
+        method_vec:method_vec
+    )
+}
+pub fn class_behaviour(method: String, args: HashMapAttr, _namespace: Namespace, _env: &mut PyNamespace) -> Exec<PyResult> {
+    let class = match obj_parser("self".to_string(), "x".to_string(), args) {
+        Ok(DataType::Class(x)) => x,
+        _ => panic!("Error to get class"),
+    };
+    Ok(match method.as_str() {
+        "__bool__" => PyResult::Some(obj_bool(true)),
+        "__str__" | "__repr__" if class.is_builtin() => PyResult::Some(obj_str(format!("<class '{}'>", class.name))),
+        "__str__" | "__repr__" => PyResult::Some(obj_str(format!("<class '{}.{}'>", class.module, class.name))),
+        _ => PyResult::None,
+    })
+}
+
+/// ## fn obj_object
+/// object()，只有object的默认方法
+pub fn obj_object() -> PyObject {
+    let param = vec!["self".to_string(), "other".to_string()];
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method("object".to_string(), String::from("__str__"), vec![]),
+        build_rust_method("object".to_string(), String::from("__repr__"), vec![]),
+        build_rust_method("object".to_string(), String::from("__eq__"), param.clone()),
+        build_rust_method("object".to_string(), String::from("__ne__"), param),
+    ];
+    PyObject::default()
+        .identity("object".to_string())
+        .extend_behavior(method_vec)
+}
+
+/// ## fn obj_instance
+/// 类的实例，identity是类名，类中定义的方法通过`__class__`查找
+/// - base：内置基类创建的对象，它的值和方法会被保留
+pub fn obj_instance(class_obj: PyObject, mut base: PyObject) -> PyObject {
+    let name = class_of(&class_obj).name;
+    let mut attr = base.attr.clone();
+    attr.insert(String::from("__class__"), PyObjAttr::Interpreter(Box::new(class_obj)));
+    attr.insert(
+        String::from("__dict__"),
+        PyObjAttr::Rust(DataType::Dict(Shared::new(vec![]))),
+    );
+    base.identity(name).attr(attr)
+}
+/// object的默认方法
+pub fn object_behaviour(method: String, args: HashMapAttr, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+    let mut obj = match args.get("self") {
+        Some(PyObjAttr::Interpreter(x)) => *x.clone(),
+        _ => panic!("Error to get object"),
+    };
+    let other = args.get("other").cloned();
+    Ok(match method.as_str() {
+        // str()默认使用repr()，这样只定义了__repr__的类也能打印
+        "__str__" => PyResult::Some(obj_str(obj_to_repr(obj, namespace, env)?)),
+        "__repr__" => {
+            let class = class_of(&type_of(&obj));
+            let address = match obj.get_value("__dict__".to_string()) {
+                Ok(PyObjAttr::Rust(DataType::Dict(x))) => x.address(),
+                _ => 0,
+            };
+            PyResult::Some(obj_str(format!(
+                "<{}.{} object at {:#x}>",
+                class.module, class.name, address
+            )))
+        }
+        // 默认只和自身相等
+        "__eq__" => PyResult::Some(obj_bool(other == Some(PyObjAttr::Interpreter(Box::new(obj))))),
+        "__ne__" => {
+            let hashmap = obj.convert_vec_to_hashmap("__eq__".to_string(), other.into_iter().collect())?;
+            match obj.py_eq(hashmap, namespace.clone(), env)? {
+                // __eq__ does not support the other operand, so neither does __ne__
+                PyResult::Some(x) if is_not_implemented(&x) => PyResult::Some(x),
+                PyResult::Some(x) => PyResult::Some(obj_bool(!obj_to_bool(x, namespace, env)?)),
+                x => x,
+            }
+        }
+        _ => PyResult::None,
+    })
+}
+
+/// ## fn instantiate
+/// 创建实例并调用`__init__`，有内置基类时先用基类的构造方法创建对象
+pub fn instantiate(
+    class_obj: &PyObject,
+    class: &PyClass,
+    args: Vec<PyObject>,
+    keywords: Vec<(String, PyObject)>,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<PyObject> {
+    if class.is_builtin() {
+        return builtin_new(class.name.as_str(), args, keywords, namespace, env);
+    }
+    let init = class.lookup("__init__");
+    let base = match class.solid_base() {
+        Some(x) => builtin_new(x.name.as_str(), args.clone(), keywords.clone(), namespace.clone(), env)?,
+        None if init.is_none() && (!args.is_empty() || !keywords.is_empty()) => {
+            return raise("TypeError", format!("{}() takes no arguments", class.name));
+        }
+        None => obj_object(),
+    };
+    let instance = obj_instance(class_obj.clone(), base);
+    if let Some(init) = init {
+        let mut init_args = vec![instance.clone()];
+        init_args.extend(args);
+        let result = call_object(&init, init_args, keywords, namespace, env)?;
+        if result.return_identity() != "NoneType" {
+            return raise("TypeError", format!("__init__() should return None, not '{}'", result.return_identity()));
+        }
+    }
+    Ok(instance)
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::core_type::build_rust_method;
+use crate::ast::data_type::exception::Exec;
+use crate::ast::data_type::core_type::{custom_behaviour, obj_parser};
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{HashMapAttr, PyObject, PyResult};
+use crate::ast::data_type::str::obj_str;
+use crate::ast::error::object_error::{ObjBasicError, ObjMethodCallError};
+use crate::ast::error::ErrorType;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+
+pub fn obj_complex(real: f64, imag: f64) -> PyObject {
+    let name = "complex".to_string();
+    let mut method_vec: Vec<(String, PyObjBehaviors)> =
+        build_method!(name:name.clone();param:vec!["self".to_string(),"other".to_string()]);
+    method_vec.append(&mut build_method!(int_and_float;name:name.clone()));
+    build_method!(
+        name: name;
+        data:DataType::Complex(real, imag);
+        method_vec:method_vec
+    )
+}
+pub fn complex_behaviour(method: String, args: HashMapAttr,_namespace: Namespace,_env:&mut PyNamespace) -> Exec<PyResult> {
+    let data_type_obj_x: DataType = obj_parser("self".to_string(), "x".to_string(), args.clone())
+        .unwrap_or_else(|x| panic!("{}", x));
+    let (real, imag) = match data_type_obj_x {
+        DataType::Complex(x, y) => (x, y),
+        _ => {
+            return Ok(PyResult::Err(ErrorType::ObjMethodCallError(
+                ObjMethodCallError::default()
+                    .obj(ObjBasicError::default().identity("complex".parse().unwrap()))
+                    .method(method),
+            )))
+        }
+    };
+    if let PyResult::Some(x) = custom_behaviour(data_type_obj_x.clone(), method.clone(), args)? {
+        return Ok(PyResult::Some(x));
+    }
+    match method.as_str() {
+        "__bool__" => return Ok(PyResult::Some(obj_bool(data_type_obj_x.bool()))),
+        "__neg__" => return Ok(PyResult::Some(obj_complex(-real, -imag))),
+        "__pos__" => return Ok(PyResult::Some(obj_complex(real, imag))),
+        "__str__" => return Ok(PyResult::Some(obj_str(data_type_obj_x.str()))),
+        _ => {}
+    }
+    Ok(PyResult::None)
+}
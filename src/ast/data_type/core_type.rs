@@ -2,19 +2,37 @@ use std::error::Error;
 
 use crate::ast::ast_struct::DataType;
 use crate::ast::data_type::bool::obj_bool;
-use crate::ast::data_type::data_type_calc::CompareResult;
+use crate::ast::data_type::bytes::obj_bytes;
+use crate::ast::data_type::complex::obj_complex;
+use crate::ast::data_type::data_type_calc::{CalcError, CompareResult};
+use crate::ast::data_type::exception::{raise, Exec};
 use crate::ast::data_type::float::obj_float;
 use crate::ast::data_type::int::obj_int;
 use crate::ast::data_type::object::{
     HashMapAttr, PyObjAttr, PyObjBehaviors, PyObject, PyResult, RustObjBehavior,
 };
+use crate::ast::data_type::signature::Signature;
 use crate::ast::data_type::str::obj_str;
-use crate::define_obj_method;
 
 pub fn build_rust_method(
     name: String,
     method: String,
     args: Vec<String>,
+) -> (String, PyObjBehaviors) {
+    (
+        method.clone(),
+        PyObjBehaviors::Rust(Box::new(RustObjBehavior {
+            name,
+            method,
+            args: Signature::positional(args),
+        })),
+    )
+}
+/// 有默认值或关键字参数的方法，如str.split(sep=None, maxsplit=-1)
+pub fn build_rust_method_signature(
+    name: String,
+    method: String,
+    args: Signature,
 ) -> (String, PyObjBehaviors) {
     (
         method.clone(),
@@ -29,24 +47,12 @@ fn get_from_hashmap(name: String, args: HashMapAttr) -> PyObjAttr {
         Some(x) => x.clone(),
     }
 }
-fn get_from_obj(name: String, obj: PyObject) -> PyObjAttr {
-    match obj.attr.get(&name) {
-        None => {
-            panic!("Cannot get")
-        }
-        Some(x) => x.clone(),
-    }
-}
-fn get_obj_until_rust(obj: PyObject, name: String) -> DataType {
-    get_attr_until_rust(get_from_obj(name.clone(), obj), name)
-}
-fn get_attr_until_rust(attr: PyObjAttr, name: String) -> DataType {
+/// 一直取属性x直到得到Rust的值，没有时返回None
+fn rust_value(attr: PyObjAttr) -> Option<DataType> {
     match attr {
-        PyObjAttr::Interpreter(x) => return get_obj_until_rust(*x.clone(), name.clone()),
-        PyObjAttr::Rust(x) => return x,
-        PyObjAttr::None => {
-            panic!("Cannot get")
-        }
+        PyObjAttr::Interpreter(x) => x.attr.get("x").cloned().and_then(rust_value),
+        PyObjAttr::Rust(x) => Some(x),
+        PyObjAttr::None => None,
     }
 }
 
@@ -54,9 +60,12 @@ fn data_type_to_obj(x: DataType) -> PyObject {
     match x {
         DataType::Int(x) => obj_int(x),
         DataType::Float(x) => obj_float(x),
+        DataType::Complex(x, y) => obj_complex(x, y),
         DataType::Bool(x) => obj_bool(x),
         DataType::Str(x) => obj_str(x),
-        _ => todo!(),
+        DataType::Bytes(x) => obj_bytes(x),
+        // arithmetic on numbers, strings and bytes never produces other types
+        _ => panic!("Error to convert the result of arithmetic"),
     }
 }
 
@@ -92,7 +101,7 @@ macro_rules! build_method {
         let param: Vec<String> = $param;
         let name: String = $name;
         let data: DataType = $data;
-        let method_vec: Vec<(String, PyObjBehaviors)> = crate::build_method!(name:name.clone();param:param);
+        let method_vec: Vec<(String, PyObjBehaviors)> = $crate::build_method!(name:name.clone();param:param);
         let behavior: HashMap<String, PyObjBehaviors> = method_vec.into_iter().collect();
         PyObject::default()
             .identity(name)
@@ -105,8 +114,8 @@ macro_rules! build_method {
         vec![
             build_rust_method(name.clone(), String::from("__add__"), param.clone()),
             build_rust_method(name.clone(), String::from("__sub__"), param.clone()),
-            build_rust_method(name.clone(), String::from("__mult__"), param.clone()),
-            build_rust_method(name.clone(), String::from("__div__"), param.clone()),
+            build_rust_method(name.clone(), String::from("__mul__"), param.clone()),
+            build_rust_method(name.clone(), String::from("__truediv__"), param.clone()),
             build_rust_method(name.clone(), String::from("__eq__"), param.clone()),
             build_rust_method(name.clone(), String::from("__lt__"), param.clone()),
             build_rust_method(name.clone(), String::from("__gt__"), param.clone()),
@@ -122,136 +131,74 @@ macro_rules! build_method {
             build_rust_method(name.clone(), String::from("__bool__"), vec![]),
             build_rust_method(name.clone(), String::from("__neg__"), vec![]),
             build_rust_method(name.clone(), String::from("__pos__"), vec![]),
+            build_rust_method(name.clone(), String::from("__floordiv__"), vec!["self".to_string(), "other".to_string()]),
+            build_rust_method(name.clone(), String::from("__mod__"), vec!["self".to_string(), "other".to_string()]),
+            build_rust_method(name.clone(), String::from("__pow__"), vec!["self".to_string(), "other".to_string()]),
+        ]
+    }};
+    (int_and_bool;name:$name:expr) => {{
+        let name:String = $name;
+        let param = vec!["self".to_string(), "other".to_string()];
+        vec![
+            build_rust_method(name.clone(), String::from("__and__"), param.clone()),
+            build_rust_method(name.clone(), String::from("__or__"), param.clone()),
+            build_rust_method(name.clone(), String::from("__xor__"), param.clone()),
+            build_rust_method(name.clone(), String::from("__lshift__"), param.clone()),
+            build_rust_method(name.clone(), String::from("__rshift__"), param.clone()),
+            build_rust_method(name.clone(), String::from("__invert__"), vec![]),
         ]
     }}
 }
 
-pub(crate) fn custom_behaviour(obj_x: DataType, method: String, args: HashMapAttr) -> PyResult {
-    let method_vec = [
-        "__add__", "__sub__", "__mult__", "__div__", "__lt__", "__gt__", "__eq__", "__ne__",
-        "__le__", "__ge__"
-    ];
-    if method_vec
-        .map(|x| return x == method.clone())
-        .iter()
-        .filter(|x| **x)
-        .count()
-        == 1
-    {
-        let other = get_attr_until_rust(
-            get_from_hashmap("other".parse().unwrap(), args),
-            "x".parse().unwrap(),
-        );
-        define_obj_method!(method method;identity "__add__";content {
-            return PyResult::Some(
-                match obj_x.add(other){
-                    Ok(x) => data_type_to_obj(x.clone()),
-                    Err(_) => { panic!("Cannot Calc")}
-                }
-            )
-        });
-        define_obj_method!(method method;identity "__sub__";content {
-            return PyResult::Some(
-                match obj_x.sub(other){
-                    Ok(x) => data_type_to_obj(x.clone()),
-                    Err(_) => { panic!("Cannot Calc")}
-                }
-            )
-        });
-        define_obj_method!(method method;identity "__mult__";content {
-            return PyResult::Some(
-                match obj_x.mul(other){
-                    Ok(x) => data_type_to_obj(x.clone()),
-                    Err(_) => { panic!("Cannot Calc")}
-                }
-            )
-        });
-        define_obj_method!(method method;identity "__div__";content {
-            return PyResult::Some(
-                match obj_x.div(other){
-                    Ok(x) => data_type_to_obj(x.clone()),
-                    Err(_) => { panic!("Cannot Calc")}
-                }
-            )
-        });
-        define_obj_method!(method method;identity "__lt__";content {
-            return PyResult::Some(
-                match obj_x.cmp(other){
-                    Ok(x) => {
-                        match x {
-                            CompareResult::Less => obj_bool(true),
-                            _ => obj_bool(false)
-                        }
-                    }
-                    Err(_) => { panic!("Cannot Calc")}
-                }
-            )
-        });
-        define_obj_method!(method method;identity "__gt__";content {
-            return PyResult::Some(
-                match obj_x.cmp(other){
-                    Ok(x) => {
-                        match x {
-                            CompareResult::Great => obj_bool(true),
-                            _ => obj_bool(false)
-                        }
-                    }
-                    Err(_) => { panic!("Cannot Compare")}
-                }
-            )
-        });
-        define_obj_method!(method method;identity "__ne__";content {
-            return PyResult::Some(
-                match obj_x.cmp(other){
-                    Ok(x) => {
-                        match x {
-                            CompareResult::Equal => obj_bool(false),
-                            _ => obj_bool(true)
-                        }
-                    }
-                    Err(_) => { panic!("Cannot Calc")}
-                }
-            )
-        });
-        define_obj_method!(method method;identity "__eq__";content {
-            return PyResult::Some(
-                match obj_x.cmp(other){
-                    Ok(x) => {
-                        match x {
-                            CompareResult::Equal => obj_bool(true),
-                            _ => obj_bool(false)
-                        }
-                    }
-                    Err(_) => { panic!("Cannot Calc")}
-                }
-            )
-        });
-        define_obj_method!(method method;identity "__ge__";content {
-            return PyResult::Some(
-                match obj_x.cmp(other){
-                    Ok(x) => {
-                        match x {
-                            CompareResult::Less => obj_bool(false),
-                            _ => obj_bool(true)
-                        }
-                    }
-                    Err(_) => { panic!("Cannot Calc")}
-                }
-            )
-        });
-        define_obj_method!(method method;identity "__le__";content {
-            return PyResult::Some(
-                match obj_x.cmp(other){
-                    Ok(x) => {
-                        match x {
-                            CompareResult::Great => obj_bool(false),
-                            _ => obj_bool(true)
-                        }
-                    }
-                    Err(_) => { panic!("Cannot Calc")}
-                }
-            )
-        });
+pub(crate) fn custom_behaviour(obj_x: DataType, method: String, args: HashMapAttr) -> Exec<PyResult> {
+    let arithmetic = match method.as_str() {
+        "__add__" | "__sub__" | "__mul__" | "__truediv__" | "__floordiv__" | "__mod__" | "__pow__" | "__and__"
+        | "__or__" | "__xor__" | "__lshift__" | "__rshift__" => true,
+        "__lt__" | "__gt__" | "__eq__" | "__ne__" | "__le__" | "__ge__" => false,
+        _ => return Ok(PyResult::None),
+    };
+    // an operand without a Rust value, such as an instance, is left to its reflected method
+    let other = match rust_value(get_from_hashmap("other".parse().unwrap(), args)) {
+        Some(x) => x,
+        None => return Ok(PyResult::None),
+    };
+    if arithmetic {
+        let result = match method.as_str() {
+            "__add__" => obj_x.add(other),
+            "__sub__" => obj_x.sub(other),
+            "__mul__" => obj_x.mul(other),
+            "__truediv__" => obj_x.div(other),
+            "__floordiv__" => obj_x.floordiv(other),
+            "__mod__" => obj_x.modulo(other),
+            "__pow__" => obj_x.pow(other),
+            "__and__" => obj_x.bitwise(other, '&'),
+            "__or__" => obj_x.bitwise(other, '|'),
+            "__xor__" => obj_x.bitwise(other, '^'),
+            "__lshift__" => obj_x.shift(other, true),
+            _ => obj_x.shift(other, false),
+        };
+        // unsupported operand types are reported by the caller, which may still try the other operand
+        return match result {
+            Ok(x) => Ok(PyResult::Some(data_type_to_obj(x))),
+            Err(CalcError::Unsupported) => Ok(PyResult::None),
+            Err(CalcError::Raise(name, message)) => raise(name, message),
+        };
     }
-    PyResult::None
+    // complex numbers and values of different types cannot be ordered, the caller tries the other operand
+    let unordered = obj_x.is_complex() || other.is_complex();
+    let result = match obj_x.cmp(other) {
+        Ok(x) => x,
+        Err(_) => panic!("Cannot Compare"),
+    };
+    let value = match (method.as_str(), result) {
+        ("__eq__", x) => x == CompareResult::Equal,
+        ("__ne__", x) => x != CompareResult::Equal,
+        (_, CompareResult::NotEq) => return Ok(PyResult::None),
+        _ if unordered => return Ok(PyResult::None),
+        ("__lt__", x) => x == CompareResult::Less,
+        ("__gt__", x) => x == CompareResult::Great,
+        ("__le__", x) => x != CompareResult::Great,
+        (_, x) => x != CompareResult::Less,
+    };
+    Ok(PyResult::Some(obj_bool(value)))
 }
@@ -1,4 +1,5 @@
 use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::float::float_repr;
 use std::error::Error;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -9,6 +10,21 @@ pub enum CompareResult {
     NotEq
 }
 
+/// ## enum CalcError
+/// 运算失败的原因
+/// - Unsupported：不支持这两种操作数，调用者还可以尝试另一个操作数的反射方法
+/// - Raise：运算本身出错，如除以0，抛出对应的内置异常和信息
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalcError {
+    Unsupported,
+    Raise(&'static str, &'static str),
+}
+impl From<std::fmt::Error> for CalcError {
+    fn from(_: std::fmt::Error) -> Self {
+        CalcError::Unsupported
+    }
+}
+
 /**
 
 ### Alright, I know this pile of code has no readability。
@@ -38,12 +54,18 @@ impl DataType {
     /// a.add(DataType::Float(2.0)) // Return DataType::Float(3.0)
     /// ```
     /// ***这段源码可读性为0***
-    pub(crate) fn add(self, rhs: Self) -> Result<DataType, Box<dyn Error>> {
+    pub(crate) fn add(self, rhs: Self) -> Result<DataType, CalcError> {
+        if self.is_complex() || rhs.is_complex() {
+            return match (self.complex(), rhs.complex()) {
+                (Some((a, b)), Some((c, d))) => Ok(DataType::Complex(a + c, b + d)),
+                _ => Err(std::fmt::Error.into()),
+            };
+        }
         match self {
             DataType::Int(x) => match rhs {
-                DataType::Int(y) => Ok(DataType::Int(x + y)),
+                DataType::Int(y) => int_result(x.checked_add(y)),
                 DataType::Float(y) => Ok(DataType::Float(x as f64 + y)),
-                DataType::Bool(y) => Ok(DataType::Int(if y { x + 1 } else { x })),
+                DataType::Bool(y) => int_result(x.checked_add(y as i64)),
                 _ => Err(std::fmt::Error.into()),
             },
             DataType::Float(x) => match rhs {
@@ -54,14 +76,21 @@ impl DataType {
             },
             DataType::Bool(x) => match rhs {
                 DataType::Float(y) => Ok(DataType::Float(if x { y + 1.0 } else { y })),
-                DataType::Int(y) => Ok(DataType::Int(if x { y + 1 } else { y })),
-                DataType::Bool(y) => Ok(DataType::Int(if y { 2 } else { 1 })),
+                DataType::Int(y) => int_result(y.checked_add(x as i64)),
+                DataType::Bool(y) => Ok(DataType::Int(x as i64 + y as i64)),
                 _ => Err(std::fmt::Error.into()),
             },
             DataType::Str(x) => match rhs {
                 DataType::Str(y) => Ok(DataType::Str(format!("{}{}", x, y))),
                 _ => Err(std::fmt::Error.into()),
             },
+            DataType::Bytes(mut x) => match rhs {
+                DataType::Bytes(y) => {
+                    x.extend(y);
+                    Ok(DataType::Bytes(x))
+                }
+                _ => Err(std::fmt::Error.into()),
+            },
             _ => Err(std::fmt::Error.into()),
         }
     }
@@ -72,12 +101,18 @@ impl DataType {
     /// a.sub(DataType::Float(2.0)) // Return DataType::Float(-1.0)
     /// ```
     /// ***这段源码可读性为0***
-    pub(crate) fn sub(self, rhs: Self) -> Result<DataType, Box<dyn Error>> {
+    pub(crate) fn sub(self, rhs: Self) -> Result<DataType, CalcError> {
+        if self.is_complex() || rhs.is_complex() {
+            return match (self.complex(), rhs.complex()) {
+                (Some((a, b)), Some((c, d))) => Ok(DataType::Complex(a - c, b - d)),
+                _ => Err(std::fmt::Error.into()),
+            };
+        }
         match self {
             DataType::Int(x) => match rhs {
-                DataType::Int(y) => Ok(DataType::Int(x - y)),
+                DataType::Int(y) => int_result(x.checked_sub(y)),
                 DataType::Float(y) => Ok(DataType::Float(x as f64 - y)),
-                DataType::Bool(y) => Ok(DataType::Int(if y { x + -1 } else { x })),
+                DataType::Bool(y) => int_result(x.checked_sub(y as i64)),
                 _ => Err(std::fmt::Error.into()),
             },
             DataType::Float(x) => match rhs {
@@ -88,7 +123,7 @@ impl DataType {
             },
             DataType::Bool(x) => match rhs {
                 DataType::Float(y) => Ok(DataType::Float(if x { y - 1.0 } else { y })),
-                DataType::Int(y) => Ok(DataType::Int(if x { y - 1 } else { y })),
+                DataType::Int(y) => int_result((x as i64).checked_sub(y)),
                 DataType::Bool(y) => Ok(DataType::Int(if y && x {
                     0
                 } else if y && !x {
@@ -103,12 +138,20 @@ impl DataType {
             _ => Err(std::fmt::Error.into()),
         }
     }
-    pub(crate) fn mul(self, rhs: Self) -> Result<DataType, Box<dyn Error>> {
+    pub(crate) fn mul(self, rhs: Self) -> Result<DataType, CalcError> {
+        if self.is_complex() || rhs.is_complex() {
+            return match (self.complex(), rhs.complex()) {
+                (Some((a, b)), Some((c, d))) => Ok(DataType::Complex(a * c - b * d, a * d + b * c)),
+                _ => Err(std::fmt::Error.into()),
+            };
+        }
         match self {
             DataType::Int(x) => match rhs {
-                DataType::Int(y) => Ok(DataType::Int(x * y)),
+                DataType::Int(y) => int_result(x.checked_mul(y)),
                 DataType::Float(y) => Ok(DataType::Float(x as f64 * y)),
                 DataType::Bool(y) => Ok(DataType::Int(if y { x } else { 0 })),
+                // 3 * "ab" is the same as "ab" * 3
+                DataType::Str(_) | DataType::Bytes(_) => rhs.mul(DataType::Int(x)),
                 _ => Err(std::fmt::Error.into()),
             },
             DataType::Float(x) => match rhs {
@@ -124,50 +167,169 @@ impl DataType {
                 _ => Err(std::fmt::Error.into()),
             },
             DataType::Str(x) => match rhs {
-                DataType::Int(y) => Ok(DataType::Str(x.repeat(y as usize))),
+                DataType::Int(y) => Ok(DataType::Str(x.repeat(y.max(0) as usize))),
+                _ => Err(std::fmt::Error.into()),
+            },
+            DataType::Bytes(x) => match rhs {
+                DataType::Int(y) => Ok(DataType::Bytes(x.repeat(y.max(0) as usize))),
                 _ => Err(std::fmt::Error.into()),
             },
             _ => Err(std::fmt::Error.into()),
         }
     }
-    pub(crate) fn div(self, rhs: Self) -> Result<DataType, Box<dyn Error>> {
-        match self {
-            DataType::Int(x) => match rhs {
-                DataType::Int(y) => Ok(DataType::Float(x as f64 / y as f64)),
-                DataType::Float(y) => Ok(DataType::Float(x as f64 / y)),
-                DataType::Bool(y) => Ok(DataType::Int(if y {
-                    x
-                } else {
-                    panic!("You cannot div 0")
-                })),
-                _ => Err(std::fmt::Error.into()),
-            },
-            DataType::Float(x) => match rhs {
-                DataType::Float(y) => Ok(DataType::Float(x / y)),
-                DataType::Int(y) => Ok(DataType::Float(x / y as f64)),
-                DataType::Bool(y) => Ok(DataType::Float(if y {
-                    x
-                } else {
-                    panic!("You cannot div 0")
-                })),
+    pub(crate) fn div(self, rhs: Self) -> Result<DataType, CalcError> {
+        if self.is_complex() || rhs.is_complex() {
+            return match (self.complex(), rhs.complex()) {
+                (Some((a, b)), Some((c, d))) => {
+                    let denominator = c * c + d * d;
+                    if denominator == 0.0 {
+                        return Err(CalcError::Raise("ZeroDivisionError", "complex division by zero"))
+                    }
+                    Ok(DataType::Complex(
+                        (a * c + b * d) / denominator,
+                        (b * c - a * d) / denominator,
+                    ))
+                },
                 _ => Err(std::fmt::Error.into()),
+            };
+        }
+        match (self.real(), rhs.real()) {
+            (Some(_), Some(0.0)) => match (self.integer(), rhs.integer()) {
+                (Some(_), Some(_)) => Err(CalcError::Raise("ZeroDivisionError", "division by zero")),
+                _ => Err(CalcError::Raise("ZeroDivisionError", "float division by zero")),
             },
-            DataType::Bool(x) => match rhs {
-                DataType::Float(y) => Ok(DataType::Float(if x { y - 1.0 } else { y })),
-                DataType::Int(y) => Ok(DataType::Int(if x { y - 1 } else { y })),
-                DataType::Bool(y) => Ok(DataType::Int(if y && x {
-                    1
-                } else if !y && x {
-                    0
-                } else {
-                    panic!("You cannot div 0")
-                })),
+            (Some(x), Some(y)) => Ok(DataType::Float(x / y)),
+            _ => Err(std::fmt::Error.into()),
+        }
+    }
+    /// ## fn floordiv
+    /// 整除，结果向负无穷取整，与python的`//`相同
+    pub(crate) fn floordiv(self, rhs: Self) -> Result<DataType, CalcError> {
+        if self.is_complex() || rhs.is_complex() {
+            return Err(CalcError::Raise("TypeError", "can't take floor of complex number."))
+        }
+        if let (Some(x), Some(y)) = (self.integer(), rhs.integer()) {
+            if y == 0 {
+                return Err(CalcError::Raise("ZeroDivisionError", "integer division or modulo by zero"))
+            }
+            // i64::MIN // -1 is the only quotient that does not fit
+            let (quotient, remainder) = match x.checked_div(y) {
+                Some(quotient) => (quotient, x % y),
+                None => return Err(CalcError::Raise("OverflowError", "integer overflow")),
+            };
+            return Ok(DataType::Int(if remainder != 0 && (remainder < 0) != (y < 0) {
+                quotient - 1
+            } else {
+                quotient
+            }));
+        }
+        match (self.real(), rhs.real()) {
+            (Some(_), Some(0.0)) => Err(CalcError::Raise("ZeroDivisionError", "float divmod()")),
+            (Some(x), Some(y)) => Ok(DataType::Float(float_divmod(x, y).0)),
+            _ => Err(std::fmt::Error.into()),
+        }
+    }
+    /// ## fn modulo
+    /// 取余，结果的符号与除数相同，与python的`%`相同
+    pub(crate) fn modulo(self, rhs: Self) -> Result<DataType, CalcError> {
+        if self.is_complex() || rhs.is_complex() {
+            return Err(CalcError::Raise("TypeError", "can't mod complex numbers."))
+        }
+        if let (Some(x), Some(y)) = (self.integer(), rhs.integer()) {
+            if y == 0 {
+                return Err(CalcError::Raise("ZeroDivisionError", "integer division or modulo by zero"))
+            }
+            let remainder = x.wrapping_rem(y);
+            return Ok(DataType::Int(if remainder != 0 && (remainder < 0) != (y < 0) {
+                remainder + y
+            } else {
+                remainder
+            }));
+        }
+        match (self.real(), rhs.real()) {
+            (Some(_), Some(0.0)) => Err(CalcError::Raise("ZeroDivisionError", "float modulo")),
+            (Some(x), Some(y)) => Ok(DataType::Float(float_divmod(x, y).1)),
+            _ => Err(std::fmt::Error.into()),
+        }
+    }
+    /// ## fn pow
+    /// 乘方，整数的负数次方是浮点数，负数的小数次方是复数
+    pub(crate) fn pow(self, rhs: Self) -> Result<DataType, CalcError> {
+        if self.is_complex() || rhs.is_complex() {
+            return match (self.complex(), rhs.complex()) {
+                (Some(x), Some(y)) => {
+                    let (real, imag) = complex_pow(x, y)?;
+                    Ok(DataType::Complex(real, imag))
+                }
                 _ => Err(std::fmt::Error.into()),
-            },
+            };
+        }
+        if let (Some(x), Some(y)) = (self.integer(), rhs.integer()) {
+            if y >= 0 {
+                return match u32::try_from(y).ok().and_then(|y| x.checked_pow(y)) {
+                    Some(x) => Ok(DataType::Int(x)),
+                    None => return Err(CalcError::Raise("OverflowError", "integer overflow")),
+                };
+            }
+        }
+        match (self.real(), rhs.real()) {
+            (Some(x), Some(y)) if x == 0.0 && y < 0.0 => {
+                Err(CalcError::Raise("ZeroDivisionError", "0.0 cannot be raised to a negative power"))
+            }
+            (Some(x), Some(y)) if x < 0.0 && y.fract() != 0.0 => {
+                let (real, imag) = complex_pow((x, 0.0), (y, 0.0))?;
+                Ok(DataType::Complex(real, imag))
+            }
+            (Some(x), Some(y)) => Ok(DataType::Float(x.powf(y))),
             _ => Err(std::fmt::Error.into()),
         }
     }
+    /// ## fn bitwise
+    /// 按位运算`&`、`|`和`^`，两个bool的结果仍然是bool
+    pub(crate) fn bitwise(self, rhs: Self, op: char) -> Result<DataType, CalcError> {
+        let (x, y) = match (self.integer(), rhs.integer()) {
+            (Some(x), Some(y)) => (x, y),
+            _ => return Err(std::fmt::Error.into()),
+        };
+        let result = match op {
+            '&' => x & y,
+            '|' => x | y,
+            _ => x ^ y,
+        };
+        match (self, rhs) {
+            (DataType::Bool(_), DataType::Bool(_)) => Ok(DataType::Bool(result != 0)),
+            _ => Ok(DataType::Int(result)),
+        }
+    }
+    /// ## fn shift
+    /// 移位运算，left为true时是`<<`
+    pub(crate) fn shift(self, rhs: Self, left: bool) -> Result<DataType, CalcError> {
+        let (x, y) = match (self.integer(), rhs.integer()) {
+            (Some(x), Some(y)) => (x, y),
+            _ => return Err(std::fmt::Error.into()),
+        };
+        if y < 0 {
+            return Err(CalcError::Raise("ValueError", "negative shift count"))
+        }
+        if !left {
+            return Ok(DataType::Int(x >> y.min(63)));
+        }
+        if x == 0 {
+            return Ok(DataType::Int(0));
+        }
+        match i64::try_from((x as i128) << y.min(64)) {
+            Ok(x) if y < 64 => Ok(DataType::Int(x)),
+            _ => Err(CalcError::Raise("OverflowError", "integer overflow")),
+        }
+    }
     pub fn cmp(self, rhs: Self) -> Result<CompareResult, Box<dyn Error>> {
+        // complex numbers are only equal or not equal, they cannot be ordered
+        if self.is_complex() || rhs.is_complex() {
+            return match (self.complex(), rhs.complex()) {
+                (Some(x), Some(y)) if x == y => Ok(CompareResult::Equal),
+                _ => Ok(CompareResult::NotEq),
+            };
+        }
         match self {
             DataType::Int(x) => match rhs {
                 DataType::Int(y) => {
@@ -212,7 +374,7 @@ impl DataType {
                 DataType::Float(y) => {
                     if x == y {
                         Ok(CompareResult::Equal)
-                    } else if (x as f64) < y {
+                    } else if x < y {
                         Ok(CompareResult::Less)
                     } else {
                         Ok(CompareResult::Great)
@@ -265,9 +427,9 @@ impl DataType {
                     DataType::Str(y) => {
                         let y_ascii = y.into_bytes();
                         for (index, item) in x_ascii.iter().enumerate() {
-                            if item.clone() > y_ascii[index] {
+                            if *item > y_ascii[index] {
                                 return Ok(CompareResult::Great);
-                            } else if item.clone() < y_ascii[index] {
+                            } else if *item < y_ascii[index] {
                                 return Ok(CompareResult::Less);
                             }
                             if index + 2 > y_ascii.len() {
@@ -278,49 +440,78 @@ impl DataType {
                                 };
                             }
                         }
-                        return if x_ascii.len() != y_ascii.len() {
+                        if x_ascii.len() != y_ascii.len() {
                             Ok(CompareResult::Less)
                         } else {
                             Ok(CompareResult::Equal)
-                        };
+                        }
                     }
                     _ => Ok(CompareResult::NotEq),
                 }
             }
+            DataType::Bytes(x) => match rhs {
+                DataType::Bytes(y) => Ok(match x.cmp(&y) {
+                    std::cmp::Ordering::Less => CompareResult::Less,
+                    std::cmp::Ordering::Equal => CompareResult::Equal,
+                    std::cmp::Ordering::Greater => CompareResult::Great,
+                }),
+                _ => Ok(CompareResult::NotEq),
+            },
             _ => Ok(CompareResult::NotEq),
         }
     }
+    /// 转换为复数的实部和虚部，用于复数运算
+    fn complex(&self) -> Option<(f64, f64)> {
+        match self {
+            DataType::Int(x) => Some((*x as f64, 0.0)),
+            DataType::Float(x) => Some((*x, 0.0)),
+            DataType::Bool(x) => Some((if *x { 1.0 } else { 0.0 }, 0.0)),
+            DataType::Complex(x, y) => Some((*x, *y)),
+            _ => None,
+        }
+    }
+    /// 整数和bool的值
+    fn integer(&self) -> Option<i64> {
+        match self {
+            DataType::Int(x) => Some(*x),
+            DataType::Bool(x) => Some(*x as i64),
+            _ => None,
+        }
+    }
+    /// 整数、bool和浮点数的值
+    fn real(&self) -> Option<f64> {
+        match self {
+            DataType::Complex(_, _) => None,
+            x => x.complex().map(|x| x.0),
+        }
+    }
+    pub(crate) fn is_complex(&self) -> bool {
+        matches!(self, DataType::Complex(_, _))
+    }
     pub fn bool(&self) -> bool {
         match self {
-            DataType::Int(x) => {
-                if *x != 0 {
-                    true
-                } else {
-                    false
-                }
-            }
-            DataType::Float(x) => {
-                if *x != 0.0 {
-                    true
-                } else {
-                    false
-                }
-            }
+            DataType::Int(x) => *x != 0,
+            DataType::Float(x) => *x != 0.0,
+            DataType::Complex(x, y) => *x != 0.0 || *y != 0.0,
             DataType::Bool(x) => *x,
-            DataType::Str(x) => {
-                if x.as_str() != "" {
-                    true
-                } else {
-                    false
-                }
-            }
-            DataType::List(x) => {
-                if x.len() != 0 {
-                    true
-                } else {
-                    false
-                }
-            }
+            DataType::Str(x) => !x.is_empty(),
+            DataType::Bytes(x) => !x.is_empty(),
+            DataType::List(x) => !x.borrow().is_empty(),
+            DataType::Tuple(x) => !x.is_empty(),
+            DataType::Dict(x) => !x.borrow().is_empty(),
+            DataType::Function(_) => true,
+            DataType::Class(_) => true,
+            DataType::BuiltinFunction(_) => true,
+            DataType::Method(_, _) => true,
+            DataType::BuiltinMethod(_, _) => true,
+            DataType::Iterator(_) => true,
+            DataType::Range(x, y, z) => (*z > 0 && x < y) || (*z < 0 && x > y),
+            DataType::Slice(..) => true,
+            DataType::Set(x) => !x.borrow().is_empty(),
+            DataType::Exception(_) => true,
+            DataType::File(_) => true,
+            DataType::Module(_) => true,
+            DataType::Generator(_) => true,
             DataType::None => false,
         }
     }
@@ -330,18 +521,108 @@ impl DataType {
                 x.to_string()
             }
             DataType::Float(x) => {
-                x.to_string()
+                float_repr(*x)
+            }
+            DataType::Complex(x, y) => {
+                if *x == 0.0 && x.is_sign_positive() {
+                    format!("{}j", y)
+                } else {
+                    format!("({}{}{}j)", x, if y.is_sign_negative() { "-" } else { "+" }, y.abs())
+                }
             }
             DataType::Bool(x) => {
-                x.to_string()
+                if *x { "True" } else { "False" }.to_string()
             }
             DataType::Str(x) => {
                 x.clone()
             }
-            DataType::List(x) => {
-                todo!()
+            DataType::Bytes(x) => {
+                // same as the repr of bytes in python
+                let quote = if x.contains(&b'\'') && !x.contains(&b'"') { '"' } else { '\'' };
+                let mut s = format!("b{}", quote);
+                for byte in x {
+                    match *byte {
+                        b'\t' => s += "\\t",
+                        b'\n' => s += "\\n",
+                        b'\r' => s += "\\r",
+                        b'\\' => s += "\\\\",
+                        x if x as char == quote => s += format!("\\{}", quote).as_str(),
+                        0x20..=0x7e => s.push(*byte as char),
+                        x => s += format!("\\x{:02x}", x).as_str(),
+                    }
+                }
+                s.push(quote);
+                s
             }
+            DataType::None => "None".to_string(),
             _ => panic!("Error to convert to str")
         }
     }
 }
+
+/// 整数运算的结果，溢出时抛出OverflowError
+fn int_result(x: Option<i64>) -> Result<DataType, CalcError> {
+    x.map(DataType::Int)
+        .ok_or(CalcError::Raise("OverflowError", "integer overflow"))
+}
+/// 与CPython的float_divmod相同，返回向下取整的商和余数
+fn float_divmod(x: f64, y: f64) -> (f64, f64) {
+    let mut remainder = x % y;
+    let mut quotient = (x - remainder) / y;
+    if remainder != 0.0 {
+        if (y < 0.0) != (remainder < 0.0) {
+            remainder += y;
+            quotient -= 1.0;
+        }
+    } else {
+        remainder = 0.0_f64.copysign(y);
+    }
+    if quotient != 0.0 {
+        let floor = quotient.floor();
+        quotient = if quotient - floor > 0.5 { floor + 1.0 } else { floor };
+    } else {
+        quotient = 0.0_f64.copysign(x / y);
+    }
+    (quotient, remainder)
+}
+
+/// 与CPython的复数乘方相同，小的整数次方用连乘，其他情况用极坐标计算
+fn complex_pow(x: (f64, f64), y: (f64, f64)) -> Result<(f64, f64), CalcError> {
+    let multiply = |a: (f64, f64), b: (f64, f64)| (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0);
+    if y.1 == 0.0 && y.0 == y.0.trunc() && y.0.abs() <= 100.0 {
+        let (mut result, mut base, mut n) = ((1.0, 0.0), x, y.0.abs() as u32);
+        while n > 0 {
+            if n & 1 == 1 {
+                result = multiply(result, base);
+            }
+            base = multiply(base, base);
+            n >>= 1;
+        }
+        if y.0 >= 0.0 {
+            return Ok(result);
+        }
+        let denominator = result.0 * result.0 + result.1 * result.1;
+        if denominator == 0.0 {
+            return Err(CalcError::Raise("ZeroDivisionError", "0.0 to a negative or complex power"));
+        }
+        return Ok((result.0 / denominator, -result.1 / denominator));
+    }
+    if y == (0.0, 0.0) {
+        return Ok((1.0, 0.0));
+    }
+    if x == (0.0, 0.0) {
+        if y.1 != 0.0 || y.0 < 0.0 {
+            return Err(CalcError::Raise("ZeroDivisionError", "0.0 to a negative or complex power"));
+        }
+        return Ok((0.0, 0.0));
+    }
+    let modulus = x.0.hypot(x.1);
+    let argument = x.1.atan2(x.0);
+    let mut length = modulus.powf(y.0);
+    let mut phase = argument * y.0;
+    if y.1 != 0.0 {
+        length /= (argument * y.1).exp();
+        phase += y.1 * modulus.ln();
+    }
+    Ok((length * phase.cos(), length * phase.sin()))
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::core_type::{build_rust_method, build_rust_method_signature, obj_parser};
+use crate::ast::data_type::exception::{obj_exception, raise_exception, Exec};
+use crate::ast::data_type::hash::key_index;
+use crate::ast::data_type::int::obj_int;
+use crate::ast::data_type::iterator::{obj_iterator, IterState};
+use crate::ast::data_type::list::obj_list;
+use crate::ast::data_type::none::obj_none;
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{obj_to_repr, HashMapAttr, PyObject, PyResult, Shared};
+use crate::ast::data_type::signature::Signature;
+use crate::ast::data_type::str::obj_str;
+use crate::ast::data_type::tuple::obj_tuple;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+
+/// 键值对按插入顺序保存
+pub fn obj_dict(x: Vec<(PyObject, PyObject)>) -> PyObject {
+    obj_dict_shared(Shared::new(x))
+}
+/// 与已有的键值对共享数据，如实例的`__dict__`
+pub fn obj_dict_shared(x: Shared<Vec<(PyObject, PyObject)>>) -> PyObject {
+    let name = "dict".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__len__"), vec![]),
+        build_rust_method(name.clone(), String::from("__bool__"), vec![]),
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+        build_rust_method(name.clone(), String::from("__iter__"), vec![]),
+        build_rust_method(name.clone(), String::from("__contains__"), vec!["self".to_string(), "key".to_string()]),
+        build_rust_method(name.clone(), String::from("__getitem__"), vec!["self".to_string(), "key".to_string()]),
+        build_rust_method(
+            name.clone(),
+            String::from("__setitem__"),
+            vec!["self".to_string(), "key".to_string(), "value".to_string()],
+        ),
+        build_rust_method(name.clone(), String::from("__delitem__"), vec!["self".to_string(), "key".to_string()]),
+        build_rust_method(name.clone(), String::from("keys"), vec![]),
+        build_rust_method(name.clone(), String::from("values"), vec![]),
+        build_rust_method(name.clone(), String::from("items"), vec![]),
+        build_rust_method_signature(
+            name.clone(),
+            String::from("get"),
+            Signature {
+                args: vec!["key".to_string(), "default".to_string()],
+                defaults: vec![obj_none()],
+                ..Default::default()
+            },
+        ),
+    ];
+    build_method!(
+        name: name;
+        data:DataType::Dict(x);
+        method_vec:method_vec
+    )
+}
+pub fn dict_behaviour(method: String, args: HashMapAttr, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+    let dict = match obj_parser("self".to_string(), "x".to_string(), args.clone()) {
+        Ok(DataType::Dict(x)) => x,
+        _ => panic!("Error to get dict"),
+    };
+    let items = dict.borrow().clone();
+    let arg = |name: &str| match args.get(name) {
+        Some(PyObjAttr::Interpreter(x)) => *x.clone(),
+        _ => panic!("Error to get dict"),
+    };
+    Ok(match method.as_str() {
+        "__len__" => PyResult::Some(obj_int(items.len() as i64)),
+        "__bool__" => PyResult::Some(obj_bool(!items.is_empty())),
+        "__iter__" => PyResult::Some(obj_iterator("dict_keyiterator", IterState::Items(items.into_iter().map(|(key, _)| key).collect(), 0))),
+        "keys" => PyResult::Some(obj_list(items.into_iter().map(|(key, _)| key).collect())),
+        "values" => PyResult::Some(obj_list(items.into_iter().map(|(_, value)| value).collect())),
+        "items" => PyResult::Some(obj_list(
+            items.into_iter().map(|(key, value)| obj_tuple(vec![key, value])).collect(),
+        )),
+        "get" => PyResult::Some(dict_get(&dict, &arg("key"), namespace, env)?.unwrap_or_else(|| arg("default"))),
+        "__contains__" => PyResult::Some(obj_bool(dict_get(&dict, &arg("key"), namespace, env)?.is_some())),
+        "__getitem__" => match dict_get(&dict, &arg("key"), namespace, env)? {
+            Some(x) => PyResult::Some(x),
+            None => return raise_exception(obj_exception("KeyError", vec![arg("key")])),
+        },
+        "__setitem__" => {
+            dict_set(&dict, arg("key"), arg("value"), namespace, env)?;
+            PyResult::Some(obj_none())
+        }
+        "__delitem__" => match dict_remove(&dict, &arg("key"), namespace, env)? {
+            Some(_) => PyResult::Some(obj_none()),
+            None => return raise_exception(obj_exception("KeyError", vec![arg("key")])),
+        },
+        "__str__" | "__repr__" => {
+            let items: Vec<String> = items
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = obj_to_repr(key, namespace.clone(), env)?;
+                    Ok(format!("{}: {}", key, obj_to_repr(value, namespace.clone(), env)?))
+                })
+                .collect::<Exec<_>>()?;
+            PyResult::Some(obj_str(format!("{{{}}}", items.join(", "))))
+        }
+        _ => PyResult::None,
+    })
+}
+
+/// ## fn dict_get
+/// 按键查找，键的哈希值相同并且用==比较相等，键不能哈希时抛出TypeError
+pub(crate) fn dict_get(
+    dict: &Shared<Vec<(PyObject, PyObject)>>,
+    key: &PyObject,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<Option<PyObject>> {
+    let keys = dict.borrow().iter().map(|(x, _)| x.clone()).collect();
+    Ok(key_index(keys, key, namespace, env)?.map(|x| dict.borrow()[x].1.clone()))
+}
+/// ## fn dict_set
+/// 键已存在时替换值，保留原来的键和顺序
+pub(crate) fn dict_set(
+    dict: &Shared<Vec<(PyObject, PyObject)>>,
+    key: PyObject,
+    value: PyObject,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<()> {
+    let keys = dict.borrow().iter().map(|(x, _)| x.clone()).collect();
+    match key_index(keys, &key, namespace, env)? {
+        Some(x) => dict.borrow_mut()[x].1 = value,
+        None => dict.borrow_mut().push((key, value)),
+    }
+    Ok(())
+}
+/// ## fn dict_remove
+/// 删除键，返回被删除的值
+pub(crate) fn dict_remove(
+    dict: &Shared<Vec<(PyObject, PyObject)>>,
+    key: &PyObject,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<Option<PyObject>> {
+    let keys = dict.borrow().iter().map(|(x, _)| x.clone()).collect();
+    Ok(key_index(keys, key, namespace, env)?.map(|x| dict.borrow_mut().remove(x).1))
+}
+
+/// 按str键查找，用于`__dict__`和sys.modules等键都是str的字典，不需要调用python的方法
+pub(crate) fn dict_get_str(dict: &Shared<Vec<(PyObject, PyObject)>>, key: &str) -> Option<PyObject> {
+    let key = obj_str(key.to_string());
+    dict.borrow()
+        .iter()
+        .find(|(x, _)| *x == key)
+        .map(|(_, value)| value.clone())
+}
+/// 设置str键的值，键已存在时替换值，保持原来的顺序
+pub(crate) fn dict_set_str(dict: &Shared<Vec<(PyObject, PyObject)>>, key: &str, value: PyObject) {
+    let key = obj_str(key.to_string());
+    let mut items = dict.borrow_mut();
+    match items.iter_mut().find(|(x, _)| *x == key) {
+        Some(x) => x.1 = value,
+        None => items.push((key, value)),
+    }
+}
+/// 删除str键，返回被删除的值
+pub(crate) fn dict_remove_str(dict: &Shared<Vec<(PyObject, PyObject)>>, key: &str) -> Option<PyObject> {
+    let key = obj_str(key.to_string());
+    let mut items = dict.borrow_mut();
+    let index = items.iter().position(|(x, _)| *x == key)?;
+    Some(items.remove(index).1)
+}
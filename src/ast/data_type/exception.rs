@@ -0,0 +1,386 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::class::{builtin_class, class_of, type_of, PyClass};
+use crate::ast::data_type::core_type::{build_rust_method, build_rust_method_signature};
+use crate::ast::data_type::function::call_object;
+use crate::ast::data_type::none::obj_none;
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{obj_to_repr, obj_to_str, HashMapAttr, PyObject, PyResult, Shared};
+use crate::ast::data_type::signature::Signature;
+use crate::ast::data_type::str::obj_str;
+use crate::ast::error::ErrorType;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+
+/// 内置异常和它们的基类，BaseException的基类是object
+const EXCEPTIONS: [(&str, &str); 33] = [
+    ("BaseException", "object"),
+    ("SystemExit", "BaseException"),
+    ("KeyboardInterrupt", "BaseException"),
+    ("GeneratorExit", "BaseException"),
+    ("Exception", "BaseException"),
+    ("StopIteration", "Exception"),
+    ("ArithmeticError", "Exception"),
+    ("OverflowError", "ArithmeticError"),
+    ("ZeroDivisionError", "ArithmeticError"),
+    ("AssertionError", "Exception"),
+    ("AttributeError", "Exception"),
+    ("ImportError", "Exception"),
+    ("ModuleNotFoundError", "ImportError"),
+    ("LookupError", "Exception"),
+    ("IndexError", "LookupError"),
+    ("KeyError", "LookupError"),
+    ("NameError", "Exception"),
+    ("UnboundLocalError", "NameError"),
+    ("OSError", "Exception"),
+    ("FileNotFoundError", "OSError"),
+    ("FileExistsError", "OSError"),
+    ("PermissionError", "OSError"),
+    ("IsADirectoryError", "OSError"),
+    ("RuntimeError", "Exception"),
+    ("NotImplementedError", "RuntimeError"),
+    ("RecursionError", "RuntimeError"),
+    ("SyntaxError", "Exception"),
+    ("IndentationError", "SyntaxError"),
+    ("TabError", "IndentationError"),
+    ("SystemError", "Exception"),
+    ("TypeError", "Exception"),
+    ("ValueError", "Exception"),
+    ("UnicodeError", "ValueError"),
+];
+
+/// 所有内置异常的名字
+pub fn exception_names() -> impl Iterator<Item = &'static str> {
+    EXCEPTIONS.iter().map(|(name, _)| *name)
+}
+
+/// 内置异常的基类，不是内置异常时返回None
+pub fn exception_base(name: &str) -> Option<&'static str> {
+    EXCEPTIONS.iter().find(|(x, _)| *x == name).map(|(_, base)| *base)
+}
+
+/// ## struct PyException
+/// 异常对象的内容，clone之后仍然是同一个异常
+/// - args：创建异常时的参数
+/// - cause：`raise X from Y`中的Y
+/// - context：抛出时正在处理的异常
+/// - suppress_context：有from时为true，打印时不显示context
+/// - location：SyntaxError出错的位置
+/// - following：同一段源码中之后的语法错误，打印在这个异常之后
+#[derive(Clone, Debug, Default)]
+pub struct PyException {
+    pub args: Vec<PyObject>,
+    pub cause: Option<PyObject>,
+    pub context: Option<PyObject>,
+    pub suppress_context: bool,
+    pub location: Option<SyntaxLocation>,
+    pub following: Vec<PyObject>,
+}
+
+/// ## struct SyntaxLocation
+/// SyntaxError的filename、lineno、offset和text属性，lineno和offset从1开始
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyntaxLocation {
+    pub filename: String,
+    pub lineno: usize,
+    pub offset: usize,
+    pub text: String,
+}
+impl SyntaxLocation {
+    /// lineno和col_offset从0开始，与扫描器和语法分析器的位置相同
+    pub fn new(filename: &str, source: &str, lineno: u64, col_offset: u64) -> Self {
+        SyntaxLocation {
+            filename: filename.to_string(),
+            lineno: lineno as usize + 1,
+            offset: col_offset as usize + 1,
+            text: source.lines().nth(lineno as usize).unwrap_or_default().to_string(),
+        }
+    }
+}
+
+/// ## fn obj_syntax_error
+/// 带有出错位置的SyntaxError或它的子类IndentationError、TabError
+pub fn obj_syntax_error(name: &str, message: &str, location: SyntaxLocation) -> PyObject {
+    let exc = obj_exception(name, vec![obj_str(message.to_string())]);
+    if let Some(x) = exception_data(&exc) {
+        x.borrow_mut().location = Some(location);
+    }
+    exc
+}
+
+/// ## fn obj_exception
+/// 内置异常的实例，如ValueError("x")
+pub fn obj_exception(name: &str, args: Vec<PyObject>) -> PyObject {
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method_signature(
+            "BaseException".to_string(),
+            String::from("__init__"),
+            Signature {
+                vararg: Some("args".to_string()),
+                ..Default::default()
+            },
+        ),
+        build_rust_method("BaseException".to_string(), String::from("__str__"), vec![]),
+        build_rust_method("BaseException".to_string(), String::from("__repr__"), vec![]),
+    ];
+    build_method!(
+        name: name.to_string();
+        data:DataType::Exception(Shared::new(PyException {
+            args,
+            ..Default::default()
+        }));
+        method_vec:method_vec
+    )
+}
+pub fn exception_behaviour(method: String, args: HashMapAttr, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+    let obj = match args.get("self") {
+        Some(PyObjAttr::Interpreter(x)) => *x.clone(),
+        _ => panic!("Error to get exception"),
+    };
+    let exception = match exception_data(&obj) {
+        Some(x) => x,
+        None => panic!("Error to get exception"),
+    };
+    let items = exception.borrow().args.clone();
+    Ok(match method.as_str() {
+        // super().__init__(*args) replaces the arguments given when the exception was created
+        "__init__" => {
+            if let Some(PyObjAttr::Interpreter(x)) = args.get("args") {
+                if let Ok(PyObjAttr::Rust(DataType::Tuple(x))) = x.get_value("x".to_string()) {
+                    exception.borrow_mut().args = x;
+                }
+            }
+            PyResult::Some(obj_none())
+        }
+        "__str__" => PyResult::Some(obj_str(match items.len() {
+            0 => String::new(),
+            // a missing key is shown with its repr, like the key itself
+            1 if is_instance_of(&obj, "KeyError") => {
+                obj_to_repr(items[0].clone(), namespace, env)?
+            }
+            1 => obj_to_str(items[0].clone(), namespace, env)?,
+            _ => args_repr(items, namespace, env)?,
+        })),
+        "__repr__" => PyResult::Some(obj_str(format!(
+            "{}({})",
+            class_of(&type_of(&obj)).name,
+            items_repr(items, namespace, env)?.join(", ")
+        ))),
+        _ => PyResult::None,
+    })
+}
+
+fn items_repr(items: Vec<PyObject>, namespace: Namespace, env: &mut PyNamespace) -> Exec<Vec<String>> {
+    items
+        .into_iter()
+        .map(|x| obj_to_repr(x, namespace.clone(), env))
+        .collect()
+}
+
+/// 多个参数时str()显示参数的元组
+fn args_repr(items: Vec<PyObject>, namespace: Namespace, env: &mut PyNamespace) -> Exec<String> {
+    Ok(format!("({})", items_repr(items, namespace, env)?.join(", ")))
+}
+
+/// 取出异常对象的内容，用户定义的子类的实例也有
+pub fn exception_data(obj: &PyObject) -> Option<Shared<PyException>> {
+    match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Exception(x))) => Some(x),
+        _ => None,
+    }
+}
+
+/// 异常是否是这个内置异常或它的子类的实例
+pub fn is_instance_of(exc: &PyObject, name: &str) -> bool {
+    class_of(&type_of(exc)).is_subclass(&builtin_class(name))
+}
+
+/// 迭代器耗尽时抛出的StopIteration，value为None时没有参数
+pub fn obj_stop_iteration(value: PyObject) -> PyObject {
+    match value.return_identity().as_str() {
+        "NoneType" => obj_exception("StopIteration", vec![]),
+        _ => obj_exception("StopIteration", vec![value]),
+    }
+}
+
+/// StopIteration的value属性，即生成器的返回值，没有参数时是None
+pub fn stop_value(exc: &PyObject) -> PyObject {
+    exception_data(exc)
+        .and_then(|x| x.borrow().args.first().cloned())
+        .unwrap_or_else(obj_none)
+}
+
+pub fn is_exception_class(class: &PyClass) -> bool {
+    class.is_subclass(&builtin_class("BaseException"))
+}
+
+/// ## fn to_exception
+/// raise的参数：异常类会被实例化，不是异常时返回None
+pub fn to_exception(value: PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<Option<PyObject>> {
+    Ok(match value.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Class(x))) if is_exception_class(&x) => {
+            Some(call_object(&value, vec![], vec![], namespace, env)?)
+        }
+        _ if exception_data(&value).is_some() => Some(value),
+        _ => None,
+    })
+}
+
+/// ## fn exception_matches
+/// except子句是否捕获这个异常，class_info可以是异常类或异常类的元组
+pub fn exception_matches(exc: &PyObject, class_info: &PyObject) -> Exec<bool> {
+    match class_info.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Class(x))) if is_exception_class(&x) => {
+            Ok(class_of(&type_of(exc)).is_subclass(&x))
+        }
+        Ok(PyObjAttr::Rust(DataType::Tuple(x))) => {
+            for class_info in x.iter() {
+                if exception_matches(exc, class_info)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+        _ => raise("TypeError", "catching classes that do not inherit from BaseException is not allowed"),
+    }
+}
+
+thread_local! {
+    /// 正在执行的except子句处理的异常，用于不带参数的raise和`__context__`
+    static HANDLING: RefCell<Vec<PyObject>> = const { RefCell::new(vec![]) };
+}
+
+/// ## type Raised
+/// 被抛出的异常对象，作为Err一直传递到捕获它的except子句或顶层
+pub type Raised = Box<PyObject>;
+
+/// ## type Exec
+/// 可能抛出异常的执行结果
+pub type Exec<T> = Result<T, Raised>;
+
+/// 正在处理的异常，相当于sys.exc_info()[1]
+pub fn handled_exception() -> Option<PyObject> {
+    HANDLING.with(|x| x.borrow().last().cloned())
+}
+
+/// ## struct Handling
+/// except子句执行期间把异常放在栈顶，离开时（包括抛出异常）出栈
+pub struct Handling;
+impl Handling {
+    pub fn enter(exc: PyObject) -> Self {
+        HANDLING.with(|x| x.borrow_mut().push(exc));
+        Handling
+    }
+}
+impl Drop for Handling {
+    fn drop(&mut self) {
+        HANDLING.with(|x| x.borrow_mut().pop());
+    }
+}
+
+/// 异常被抛出时的信息，与python打印的最后一行相同，如"ValueError: x"
+fn exception_message(exc: &PyObject, namespace: Namespace, env: &mut PyNamespace) -> String {
+    let class = class_of(&type_of(exc));
+    let name = if class.is_builtin() || class.module == "__main__" {
+        class.name
+    } else {
+        format!("{}.{}", class.module, class.name)
+    };
+    match obj_to_str(exc.clone(), namespace, env) {
+        Ok(x) if x.is_empty() => name,
+        Ok(x) => format!("{}: {}", name, x),
+        Err(_) => format!("{}: <exception str() failed>", name),
+    }
+}
+
+/// SyntaxError出错的那一行，与python相同，去掉缩进后在出错的列下面画`^`
+fn syntax_location(location: &SyntaxLocation) -> String {
+    let mut text = format!("  File \"{}\", line {}\n", location.filename, location.lineno);
+    let line = location.text.trim_start();
+    let indent = location.text.chars().count() - line.chars().count();
+    if !line.is_empty() {
+        let offset = location.offset.saturating_sub(indent + 1).min(line.chars().count());
+        text += format!("    {}\n    {}^\n", line.trim_end(), " ".repeat(offset)).as_str();
+    }
+    text
+}
+
+/// ## fn traceback
+/// 没有被捕获的异常的报告，先打印它的`__cause__`或`__context__`
+pub fn traceback(exc: &PyObject, namespace: Namespace, env: &mut PyNamespace) -> String {
+    let mut text = String::new();
+    if let Some(x) = exception_data(exc) {
+        let exception = x.borrow().clone();
+        // a syntax error found before the code runs has no frames, only the position in the source
+        if let Some(location) = &exception.location {
+            text += syntax_location(location).as_str();
+            text += exception_message(exc, namespace.clone(), env).as_str();
+            text.push('\n');
+            for x in exception.following.iter() {
+                text += traceback(x, namespace.clone(), env).as_str();
+            }
+            return text;
+        }
+        match (exception.cause, exception.context) {
+            (Some(cause), _) => {
+                text += traceback(&cause, namespace.clone(), env).as_str();
+                text += "\nThe above exception was the direct cause of the following exception:\n\n";
+            }
+            (None, Some(context)) if !exception.suppress_context => {
+                text += traceback(&context, namespace.clone(), env).as_str();
+                text += "\nDuring handling of the above exception, another exception occurred:\n\n";
+            }
+            _ => {}
+        }
+    }
+    text += "Traceback (most recent call last):\n";
+    text += exception_message(exc, namespace, env).as_str();
+    text.push('\n');
+    text
+}
+
+/// ## fn error
+/// 内置异常的实例，信息为空时没有参数，抛出时正在处理的异常作为`__context__`
+pub fn error(name: &str, message: impl Into<String>) -> Raised {
+    let message = message.into();
+    let args = match message.is_empty() {
+        true => vec![],
+        false => vec![obj_str(message)],
+    };
+    let exc = obj_exception(name, args);
+    set_context(&exc);
+    Box::new(exc)
+}
+
+/// 抛出内置异常，如`raise("TypeError", "...")`
+pub fn raise<T>(name: &str, message: impl Into<String>) -> Exec<T> {
+    Err(error(name, message))
+}
+
+/// ## fn raise_exception
+/// 抛出异常，正在处理另一个异常时把它作为`__context__`
+pub fn raise_exception<T>(exc: PyObject) -> Exec<T> {
+    set_context(&exc);
+    Err(Box::new(exc))
+}
+
+fn set_context(exc: &PyObject) {
+    let (data, handled) = match (exception_data(exc), handled_exception()) {
+        (Some(x), Some(y)) => (x, y),
+        _ => return,
+    };
+    if exception_data(&handled).is_some_and(|x| x != data) {
+        data.borrow_mut().context = Some(handled);
+    }
+}
+
+/// 解释器内部的错误转换为对应的内置异常，如找不到变量时是NameError
+impl From<ErrorType> for Raised {
+    fn from(value: ErrorType) -> Self {
+        let (name, message) = value.exception();
+        error(name, message)
+    }
+}
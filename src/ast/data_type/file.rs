@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Write};
+
+use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::core_type::{build_rust_method, build_rust_method_signature, obj_parser};
+use crate::ast::data_type::exception::{raise, Exec};
+use crate::ast::data_type::int::obj_int;
+use crate::ast::data_type::iterator::{obj_iterator, IterState};
+use crate::ast::data_type::list::obj_list;
+use crate::ast::data_type::none::obj_none;
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{HashMapAttr, PyObject, PyResult, Shared};
+use crate::ast::data_type::signature::Signature;
+use crate::ast::data_type::str::obj_str;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+
+/// ## struct PyFile
+/// open()打开的文本文件
+/// - text、position：读模式下打开时读入的全部内容和读到的位置（按字符）
+/// - file：写模式下的文件，写入的内容直接写到文件中
+#[derive(Debug)]
+pub struct PyFile {
+    pub name: String,
+    pub mode: String,
+    text: Vec<char>,
+    position: usize,
+    file: Option<File>,
+    pub closed: bool,
+}
+
+/// 与python相同的OSError信息，如"FileNotFoundError: [Errno 2] No such file or directory: 'a.txt'"
+fn os_error<T>(kind: ErrorKind, name: &str) -> Exec<T> {
+    match kind {
+        ErrorKind::NotFound => raise("FileNotFoundError", format!("[Errno 2] No such file or directory: '{}'", name)),
+        ErrorKind::AlreadyExists => raise("FileExistsError", format!("[Errno 17] File exists: '{}'", name)),
+        ErrorKind::PermissionDenied => raise("PermissionError", format!("[Errno 13] Permission denied: '{}'", name)),
+        ErrorKind::IsADirectory => raise("IsADirectoryError", format!("[Errno 21] Is a directory: '{}'", name)),
+        x => raise("OSError", format!("{}: '{}'", x, name)),
+    }
+}
+
+/// ## fn open_file
+/// open(file, mode='r')，只支持文本模式的r、w、a、x
+pub fn open_file(name: String, mode: String) -> Exec<PyObject> {
+    let kind = mode.replace('t', "");
+    let mut options = OpenOptions::new();
+    match kind.as_str() {
+        "r" => options.read(true),
+        "w" => options.write(true).create(true).truncate(true),
+        "a" => options.append(true).create(true),
+        "x" => options.write(true).create_new(true),
+        _ => return raise("ValueError", format!("invalid mode: '{}'", mode)),
+    };
+    let mut file = PyFile {
+        name: name.clone(),
+        mode,
+        text: vec![],
+        position: 0,
+        file: None,
+        closed: false,
+    };
+    match options.open(&name) {
+        // universal newlines: \r\n is read as \n
+        Ok(_) if kind == "r" => match std::fs::read_to_string(&name) {
+            Ok(x) => file.text = x.replace("\r\n", "\n").chars().collect(),
+            Err(x) => return os_error(x.kind(), name.as_str()),
+        },
+        Ok(x) => file.file = Some(x),
+        Err(x) => return os_error(x.kind(), name.as_str()),
+    }
+    Ok(obj_file(file))
+}
+
+pub fn obj_file(file: PyFile) -> PyObject {
+    let name = "TextIOWrapper".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+        build_rust_method(name.clone(), String::from("__iter__"), vec![]),
+        build_rust_method(name.clone(), String::from("__enter__"), vec![]),
+        build_rust_method_signature(
+            name.clone(),
+            String::from("__exit__"),
+            Signature {
+                vararg: Some("args".to_string()),
+                ..Default::default()
+            },
+        ),
+        build_rust_method_signature(
+            name.clone(),
+            String::from("read"),
+            Signature {
+                args: vec!["size".to_string()],
+                defaults: vec![obj_int(-1)],
+                ..Default::default()
+            },
+        ),
+        build_rust_method(name.clone(), String::from("readline"), vec![]),
+        build_rust_method(name.clone(), String::from("readlines"), vec![]),
+        build_rust_method(name.clone(), String::from("write"), vec!["self".to_string(), "s".to_string()]),
+        build_rust_method(name.clone(), String::from("close"), vec![]),
+    ];
+    build_method!(
+        name: name;
+        data:DataType::File(Shared::new(file));
+        method_vec:method_vec
+    )
+}
+pub fn file_behaviour(method: String, args: HashMapAttr, _namespace: Namespace, _env: &mut PyNamespace) -> Exec<PyResult> {
+    let file = match obj_parser("self".to_string(), "x".to_string(), args.clone()) {
+        Ok(DataType::File(x)) => x,
+        _ => panic!("Error to get file"),
+    };
+    let arg = |name: &str| match args.get(name) {
+        Some(PyObjAttr::Interpreter(x)) => *x.clone(),
+        _ => panic!("Error to get file"),
+    };
+    let mut file = file.borrow_mut();
+    match method.as_str() {
+        "__str__" | "__repr__" => {
+            return Ok(PyResult::Some(obj_str(format!(
+                "<_io.TextIOWrapper name='{}' mode='{}' encoding='UTF-8'>",
+                file.name, file.mode
+            ))))
+        }
+        // closing twice is allowed, so the with statement can close a file closed in its body
+        "close" | "__exit__" => {
+            file.closed = true;
+            file.file = None;
+            return Ok(PyResult::Some(match method.as_str() {
+                "close" => obj_none(),
+                _ => obj_bool(false),
+            }));
+        }
+        _ if file.closed => return raise("ValueError", "I/O operation on closed file."),
+        _ => {}
+    }
+    let readable = file.file.is_none();
+    match method.as_str() {
+        "__enter__" => return Ok(PyResult::Some(arg("self"))),
+        "write" => {
+            let s = match arg("s").get_value("x".to_string()) {
+                Ok(PyObjAttr::Rust(DataType::Str(x))) => x,
+                _ => {
+                    return raise(
+                        "TypeError",
+                        format!("write() argument must be str, not {}", arg("s").return_identity()),
+                    )
+                }
+            };
+            let name = file.name.clone();
+            match file.file.as_mut().map(|x| x.write_all(s.as_bytes())) {
+                Some(Ok(_)) => return Ok(PyResult::Some(obj_int(s.chars().count() as i64))),
+                Some(Err(x)) => return os_error(x.kind(), name.as_str()),
+                None => return raise("OSError", "not writable"),
+            }
+        }
+        _ if !readable => return raise("OSError", "not readable"),
+        _ => {}
+    }
+    let lines = |file: &mut PyFile, limit: Option<usize>| {
+        let mut lines = vec![];
+        while file.position < file.text.len() && limit.is_none_or(|x| lines.len() < x) {
+            let rest = &file.text[file.position..];
+            let end = rest.iter().position(|x| *x == '\n').map_or(rest.len(), |x| x + 1);
+            lines.push(obj_str(rest[..end].iter().collect()));
+            file.position += end;
+        }
+        lines
+    };
+    Ok(match method.as_str() {
+        "read" => {
+            let rest = file.text.len() - file.position;
+            let size = match arg("size").get_value("x".to_string()) {
+                Ok(PyObjAttr::Rust(DataType::Int(x))) if x >= 0 => (x as usize).min(rest),
+                Ok(PyObjAttr::Rust(DataType::Int(_))) | Ok(PyObjAttr::Rust(DataType::None)) => rest,
+                _ => {
+                    return raise(
+                        "TypeError",
+                        format!("argument should be integer or None, not '{}'", arg("size").return_identity()),
+                    )
+                }
+            };
+            let start = file.position;
+            file.position += size;
+            PyResult::Some(obj_str(file.text[start..start + size].iter().collect()))
+        }
+        "readline" => PyResult::Some(lines(&mut file, Some(1)).pop().unwrap_or_else(|| obj_str(String::new()))),
+        "readlines" => PyResult::Some(obj_list(lines(&mut file, None))),
+        // iterating reads the remaining lines
+        "__iter__" => PyResult::Some(obj_iterator("TextIOWrapper", IterState::Items(lines(&mut file, None), 0))),
+        _ => PyResult::None,
+    })
+}
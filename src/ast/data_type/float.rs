@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use crate::ast::ast_struct::DataType;
 use crate::ast::data_type::bool::obj_bool;
 use crate::ast::data_type::core_type::build_rust_method;
+use crate::ast::data_type::exception::Exec;
 use crate::ast::data_type::core_type::{custom_behaviour, obj_parser};
 use crate::ast::data_type::object::PyObjAttr;
 use crate::ast::data_type::object::PyObjBehaviors;
@@ -24,32 +25,56 @@ pub fn obj_float(x: f64) -> PyObject {
         method_vec:method_vec
     )
 }
-pub fn float_behaviour(method: String, args: HashMapAttr,namespace: Namespace,env:&mut PyNamespace) -> PyResult {
+pub fn float_behaviour(method: String, args: HashMapAttr,_namespace: Namespace,_env:&mut PyNamespace) -> Exec<PyResult> {
     let data_type_obj_x: DataType = obj_parser("self".to_string(), "x".to_string(), args.clone())
         .unwrap_or_else(|x| panic!("{}", x));
     let float_x: f64;
     match data_type_obj_x {
         DataType::Float(x) => float_x = x,
         _ => {
-            return PyResult::Err(ErrorType::ObjMethodCallError(
+            return Ok(PyResult::Err(ErrorType::ObjMethodCallError(
                 ObjMethodCallError::default()
                     .obj(ObjBasicError::default().identity("float".parse().unwrap()))
                     .method(method),
-            ))
+            )))
         }
     }
-    match custom_behaviour(data_type_obj_x.clone(), method.clone(), args) {
-        PyResult::Some(x) => {
-            return PyResult::Some(x);
-        }
-        _ => {}
+    if let PyResult::Some(x) = custom_behaviour(data_type_obj_x.clone(), method.clone(), args)? {
+        return Ok(PyResult::Some(x));
     }
     match method.as_str() {
-        "__bool__" => return PyResult::Some(obj_bool(data_type_obj_x.bool())),
-        "__neg__" => return PyResult::Some(obj_float(-float_x)),
-        "__pos__" => return PyResult::Some(obj_float(float_x)),
-        "__str__" => return PyResult::Some(obj_str(float_x.to_string())),
+        "__bool__" => return Ok(PyResult::Some(obj_bool(data_type_obj_x.bool()))),
+        "__neg__" => return Ok(PyResult::Some(obj_float(-float_x))),
+        "__pos__" => return Ok(PyResult::Some(obj_float(float_x))),
+        "__str__" => return Ok(PyResult::Some(obj_str(float_repr(float_x)))),
         _ => {}
     }
-    PyResult::None
+    Ok(PyResult::None)
+}
+/// 与python的repr(float)相同，1.0输出为1.0而不是1，很大或很小的数使用科学计数法
+pub fn float_repr(x: f64) -> String {
+    if x.is_nan() {
+        return "nan".to_string();
+    }
+    if x.is_infinite() {
+        return if x > 0.0 { "inf" } else { "-inf" }.to_string();
+    }
+    let scientific = format!("{:e}", x);
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    if (-4..16).contains(&exponent) {
+        let fixed = x.to_string();
+        if fixed.contains('.') {
+            fixed
+        } else {
+            fixed + ".0"
+        }
+    } else {
+        format!(
+            "{}e{}{:02}",
+            mantissa,
+            if exponent < 0 { "-" } else { "+" },
+            exponent.abs()
+        )
+    }
 }
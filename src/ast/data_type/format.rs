@@ -0,0 +1,344 @@
+use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::exception::{raise, Exec};
+use crate::ast::data_type::float::float_repr;
+use crate::ast::data_type::object::{obj_to_str, PyObjAttr, PyObject};
+use crate::ast::namespace::{Namespace, PyNamespace};
+
+/// ## struct FormatSpec
+/// 格式说明符，[[fill]align][sign][z][#][0][width][grouping][.precision][type]
+struct FormatSpec {
+    fill: char,
+    align: Option<char>,
+    sign: Option<char>,
+    alternate: bool,
+    zero: bool,
+    width: usize,
+    grouping: Option<char>,
+    precision: Option<usize>,
+    format_type: Option<char>,
+}
+
+fn parse_spec(spec: &str) -> Result<FormatSpec, String> {
+    let chars: Vec<char> = spec.chars().collect();
+    let mut index = 0;
+    let mut fill = ' ';
+    let mut align = None;
+    if chars.len() >= 2 && "<>=^".contains(chars[1]) {
+        fill = chars[0];
+        align = Some(chars[1]);
+        index = 2;
+    } else if !chars.is_empty() && "<>=^".contains(chars[0]) {
+        align = Some(chars[0]);
+        index = 1;
+    }
+    let mut sign = None;
+    if index < chars.len() && "+- ".contains(chars[index]) {
+        sign = Some(chars[index]);
+        index += 1;
+    }
+    // negative zero coercion, -0.0 is always printed as it is here
+    if index < chars.len() && chars[index] == 'z' {
+        index += 1;
+    }
+    let alternate = index < chars.len() && chars[index] == '#';
+    if alternate {
+        index += 1;
+    }
+    let zero = index < chars.len() && chars[index] == '0';
+    if zero {
+        index += 1;
+    }
+    let mut width = String::new();
+    while index < chars.len() && chars[index].is_ascii_digit() {
+        width.push(chars[index]);
+        index += 1;
+    }
+    let mut grouping = None;
+    if index < chars.len() && (chars[index] == ',' || chars[index] == '_') {
+        grouping = Some(chars[index]);
+        index += 1;
+    }
+    let mut precision = None;
+    if index < chars.len() && chars[index] == '.' {
+        index += 1;
+        let mut digits = String::new();
+        while index < chars.len() && chars[index].is_ascii_digit() {
+            digits.push(chars[index]);
+            index += 1;
+        }
+        if digits.is_empty() {
+            return Err("Format specifier missing precision".to_string());
+        }
+        precision = Some(digits.parse().map_err(|_| "Too many decimal digits in format string")?);
+    }
+    if chars.len() > index + 1 {
+        return Err("Invalid format specifier".to_string());
+    }
+    Ok(FormatSpec {
+        fill,
+        align,
+        sign,
+        alternate,
+        zero,
+        width: if width.is_empty() {
+            0
+        } else {
+            width.parse().map_err(|_| "Too many decimal digits in format string")?
+        },
+        grouping,
+        precision,
+        format_type: chars.get(index).copied(),
+    })
+}
+
+/// 填充到指定宽度，prefix是符号和进制前缀，'='对齐时填充在prefix之后
+fn pad(prefix: &str, body: &str, spec: &FormatSpec, default_align: char) -> String {
+    let (fill, align) = if spec.zero && spec.align.is_none() {
+        ('0', '=')
+    } else {
+        (spec.fill, spec.align.unwrap_or(default_align))
+    };
+    let len = prefix.chars().count() + body.chars().count();
+    if len >= spec.width {
+        return format!("{}{}", prefix, body);
+    }
+    let padding = spec.width - len;
+    let fill = |count: usize| fill.to_string().repeat(count);
+    match align {
+        '<' => format!("{}{}{}", prefix, body, fill(padding)),
+        '^' => format!(
+            "{}{}{}{}",
+            fill(padding / 2),
+            prefix,
+            body,
+            fill(padding - padding / 2)
+        ),
+        '=' => format!("{}{}{}", prefix, fill(padding), body),
+        _ => format!("{}{}{}", fill(padding), prefix, body),
+    }
+}
+
+/// 在整数部分插入千位分隔符
+fn group(digits: &str, separator: Option<char>, interval: usize) -> String {
+    let separator = match separator {
+        None => return digits.to_string(),
+        Some(x) => x,
+    };
+    let end = digits
+        .find(|x: char| !x.is_ascii_digit())
+        .unwrap_or(digits.len());
+    let (integer, rest) = digits.split_at(end);
+    let mut grouped = String::new();
+    for (index, char) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index) % interval == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(char);
+    }
+    grouped + rest
+}
+
+/// 用'0'填充并分组时，填充的0也要分组，如format(255, '08,d') == '0,000,255'
+fn zero_group(digits: &str, prefix: &str, spec: &FormatSpec, interval: usize) -> String {
+    let mut digits = digits.to_string();
+    let mut grouped = group(digits.as_str(), spec.grouping, interval);
+    if spec.zero && spec.align.is_none() && spec.grouping.is_some() {
+        let width = spec.width.saturating_sub(prefix.chars().count());
+        while grouped.chars().count() < width {
+            digits.insert(0, '0');
+            grouped = group(digits.as_str(), spec.grouping, interval);
+        }
+    }
+    grouped
+}
+
+fn sign_prefix(negative: bool, spec: &FormatSpec) -> &'static str {
+    if negative {
+        "-"
+    } else {
+        match spec.sign {
+            Some('+') => "+",
+            Some(' ') => " ",
+            _ => "",
+        }
+    }
+}
+
+fn format_str(x: String, spec: FormatSpec) -> Result<String, String> {
+    if spec.sign.is_some() {
+        return Err("Sign not allowed in string format specifier".to_string());
+    }
+    if spec.alternate {
+        return Err("Alternate form (#) not allowed in string format specifier".to_string());
+    }
+    if spec.align == Some('=') {
+        return Err("'=' alignment not allowed in string format specifier".to_string());
+    }
+    match spec.format_type {
+        None | Some('s') => {}
+        Some(x) => return Err(format!("Unknown format code '{}' for object of type 'str'", x)),
+    }
+    let body: String = match spec.precision {
+        None => x,
+        Some(precision) => x.chars().take(precision).collect(),
+    };
+    Ok(pad("", body.as_str(), &spec, '<'))
+}
+
+fn format_int(x: i64, spec: FormatSpec) -> Result<String, String> {
+    let format_type = spec.format_type.unwrap_or('d');
+    if "eEfFgG%".contains(format_type) {
+        return format_float(x as f64, spec);
+    }
+    if spec.precision.is_some() {
+        return Err("Precision not allowed in integer format specifier".to_string());
+    }
+    let abs = x.unsigned_abs();
+    let (base_prefix, digits, interval) = match format_type {
+        'd' | 'n' => ("", abs.to_string(), 3),
+        'b' => ("0b", format!("{:b}", abs), 4),
+        'o' => ("0o", format!("{:o}", abs), 4),
+        'x' => ("0x", format!("{:x}", abs), 4),
+        'X' => ("0X", format!("{:X}", abs), 4),
+        'c' => {
+            return match char::from_u32(x as u32) {
+                Some(char) if x >= 0 => Ok(pad("", char.to_string().as_str(), &spec, '<')),
+                _ => Err("%c arg not in range(0x110000)".to_string()),
+            }
+        }
+        x => return Err(format!("Unknown format code '{}' for object of type 'int'", x)),
+    };
+    let prefix = format!(
+        "{}{}",
+        sign_prefix(x < 0, &spec),
+        if spec.alternate { base_prefix } else { "" }
+    );
+    let digits = zero_group(digits.as_str(), prefix.as_str(), &spec, interval);
+    Ok(pad(prefix.as_str(), digits.as_str(), &spec, '>'))
+}
+
+/// 科学计数法，指数至少两位，如1.5e-05
+fn scientific(x: f64, precision: usize) -> String {
+    let s = format!("{:.*e}", precision, x);
+    let (mantissa, exponent) = s.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    format!(
+        "{}e{}{:02}",
+        mantissa,
+        if exponent < 0 { "-" } else { "+" },
+        exponent.abs()
+    )
+}
+
+fn strip_zeros(s: String) -> String {
+    let (number, exponent) = match s.find('e') {
+        None => (s.as_str(), ""),
+        Some(index) => s.split_at(index),
+    };
+    if !number.contains('.') {
+        return s;
+    }
+    format!(
+        "{}{}",
+        number.trim_end_matches('0').trim_end_matches('.'),
+        exponent
+    )
+}
+
+/// 'g'格式，threshold之前使用定点表示
+fn general(x: f64, precision: usize, threshold: i32, alternate: bool) -> String {
+    let precision = precision.max(1);
+    let exponent: i32 = if x == 0.0 {
+        0
+    } else {
+        let s = format!("{:.*e}", precision - 1, x);
+        s.split_once('e').unwrap().1.parse().unwrap()
+    };
+    let s = if (-4..threshold).contains(&exponent) {
+        format!("{:.*}", (precision as i32 - 1 - exponent).max(0) as usize, x)
+    } else {
+        scientific(x, precision - 1)
+    };
+    if alternate {
+        s
+    } else {
+        strip_zeros(s)
+    }
+}
+
+fn format_float(x: f64, spec: FormatSpec) -> Result<String, String> {
+    let abs = x.abs();
+    let upper = spec.format_type.map(|x| x.is_ascii_uppercase()).unwrap_or(false);
+    let body = if x.is_nan() || x.is_infinite() {
+        let body = if x.is_nan() { "nan" } else { "inf" };
+        let body = if upper { body.to_uppercase() } else { body.to_string() };
+        if spec.format_type == Some('%') {
+            body + "%"
+        } else {
+            body
+        }
+    } else {
+        match spec.format_type {
+            Some('f') | Some('F') => format!("{:.*}", spec.precision.unwrap_or(6), abs),
+            Some('e') | Some('E') => scientific(abs, spec.precision.unwrap_or(6)),
+            Some('g') | Some('G') | Some('n') => {
+                let precision = spec.precision.unwrap_or(6).max(1);
+                general(abs, precision, precision as i32, spec.alternate)
+            }
+            Some('%') => format!("{:.*}%", spec.precision.unwrap_or(6), abs * 100.0),
+            None => match spec.precision {
+                None => float_repr(abs),
+                Some(precision) => {
+                    // like 'g', but a fixed-point number keeps at least one digit after the point
+                    let precision = precision.max(1);
+                    let s = general(abs, precision, precision as i32 - 1, spec.alternate);
+                    if s.contains('.') || s.contains('e') {
+                        s
+                    } else {
+                        s + ".0"
+                    }
+                }
+            },
+            Some(x) => {
+                return Err(format!(
+                    "Unknown format code '{}' for object of type 'float'",
+                    x
+                ))
+            }
+        }
+    };
+    let body = if upper { body.to_uppercase() } else { body };
+    let prefix = sign_prefix(x.is_sign_negative() && !x.is_nan(), &spec);
+    let body = zero_group(body.as_str(), prefix, &spec, 3);
+    Ok(pad(prefix, body.as_str(), &spec, '>'))
+}
+
+/// ## fn format_obj
+/// 与python的format(value, format_spec)相同
+pub fn format_obj(
+    obj: PyObject,
+    spec: &str,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<String> {
+    if spec.is_empty() {
+        return obj_to_str(obj, namespace, env);
+    }
+    let format_spec = match parse_spec(spec) {
+        Ok(x) => x,
+        Err(x) => return raise("ValueError", x),
+    };
+    let result = match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Str(x))) => format_str(x, format_spec),
+        Ok(PyObjAttr::Rust(DataType::Int(x))) => format_int(x, format_spec),
+        Ok(PyObjAttr::Rust(DataType::Bool(x))) => format_int(x as i64, format_spec),
+        Ok(PyObjAttr::Rust(DataType::Float(x))) => format_float(x, format_spec),
+        _ => {
+            return raise(
+                "TypeError",
+                format!("unsupported format string passed to {}.__format__", obj.return_identity()),
+            )
+        }
+    };
+    result.or_else(|x| raise("ValueError", x))
+}
@@ -1,6 +1,177 @@
-use crate::ast::data_type::object::{HashMapAttr, PyObject, PyResult};
+use std::collections::HashMap;
 
-#[allow(dead_code)]
-pub fn obj_function(_x: Box<dyn Fn(HashMapAttr) -> PyResult>, _param: Vec<String>) -> PyObject {
-    todo!()
+use uuid::Uuid;
+
+use crate::ast::ast_struct::{exec_commands, DataType, Type};
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::builtin::call_builtin;
+use crate::ast::data_type::class::instantiate;
+use crate::ast::data_type::core_type::{build_rust_method, obj_parser};
+use crate::ast::data_type::exception::{raise, Exec};
+use crate::ast::data_type::generator::{obj_generator, PyGenerator};
+use crate::ast::data_type::method::call_builtin_method;
+use crate::ast::data_type::none::obj_none;
+use crate::ast::data_type::object::{HashMapAttr, PyObjAttr, PyObjBehaviors, PyObject, PyResult};
+use crate::ast::data_type::signature::Signature;
+use crate::ast::data_type::str::obj_str;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+
+/// 剩余的栈小于这个大小时，函数体在新分配的栈上执行
+const STACK_RED_ZONE: usize = 1024 * 1024;
+/// 每次新分配的栈的大小
+const STACK_SEGMENT: usize = 16 * 1024 * 1024;
+
+/// ## struct PyFunction
+/// 用def定义的函数
+/// - closure：定义函数时所在的命名空间，调用时在它下面新建一层函数帧
+/// - owner：在类体中定义时所在的类，用于无参数的super()
+#[derive(Clone, Debug)]
+pub struct PyFunction {
+    pub id: Uuid,
+    pub name: String,
+    pub signature: Signature,
+    pub body: Vec<Type>,
+    pub closure: Namespace,
+    pub owner: Option<Box<PyObject>>,
+    /// 定义函数的模块，函数体中的全局变量在这个模块中查找
+    pub module: String,
+    /// 生成器函数，调用时不执行函数体，而是返回生成器
+    pub generator: bool,
+    /// 函数体中赋值过的名字，在绑定之前读取时抛出UnboundLocalError
+    pub locals: Vec<String>,
+}
+
+/// 函数对象只和自身相等
+impl PartialEq for PyFunction {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl PyFunction {
+    pub fn new(name: String, signature: Signature, body: Vec<Type>, closure: Namespace, module: String) -> Self {
+        PyFunction {
+            id: Uuid::new_v4(),
+            name,
+            signature,
+            body,
+            closure,
+            owner: None,
+            module,
+            generator: false,
+            locals: vec![],
+        }
+    }
+    /// ## fn call
+    /// 在新的函数帧中执行函数体，没有return时返回None
+    /// 生成器函数的函数帧由生成器保留，直到生成器结束
+    pub fn call(&self, args: Vec<PyObject>, keywords: Vec<(String, PyObject)>, env: &mut PyNamespace) -> Exec<PyObject> {
+        if env.depth >= env.recursion_limit {
+            return raise("RecursionError", "maximum recursion depth exceeded");
+        }
+        let bound = match self.signature.bind(self.name.as_str(), args, keywords) {
+            Ok(x) => x,
+            Err(x) => return raise("TypeError", x),
+        };
+        let namespace = self.closure.child(Uuid::new_v4().to_string());
+        let path = namespace.path();
+        env.create_local_namespace(path[0].clone(), path[1..].to_vec());
+        env.declare_locals(&namespace, self.locals.clone());
+        if let Some(owner) = &self.owner {
+            // super() finds the class and the first argument through these names
+            let first = bound.first().filter(|_| !self.signature.positional_names().is_empty());
+            let mut hidden = vec![("__class__".to_string(), *owner.clone())];
+            hidden.extend(first.map(|(_, x)| ("__self__".to_string(), x.clone())));
+            for (id, value) in hidden {
+                env.set_variable(namespace.clone(), id, value);
+            }
+        }
+        for (id, value) in bound {
+            if let Some(x) = env.set_variable(namespace.clone(), id, value) {
+                return Err(x.into());
+            }
+        }
+        if self.generator {
+            return Ok(obj_generator(PyGenerator::new(self, namespace)));
+        }
+        let module = std::mem::replace(&mut env.module, self.module.clone());
+        env.depth += 1;
+        // a Python call takes many Rust frames, the stack grows on the heap so the recursion limit is reached first
+        let result = stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || {
+            exec_commands(&self.body, env, namespace.clone())
+        });
+        // the frame is removed even when an exception leaves the function
+        env.depth -= 1;
+        env.module = module;
+        env.delete_local_namespace(&namespace);
+        Ok(match result? {
+            Type::Return(x) => match x.value.map(|x| *x) {
+                Some(Type::Constant(x)) => x.value,
+                _ => obj_none(),
+            },
+            _ => obj_none(),
+        })
+    }
+}
+
+/// ## fn call_object
+/// 调用对象：函数直接调用，类创建实例，绑定方法把实例作为第一个参数，实例调用类中定义的`__call__`
+pub fn call_object(
+    func: &PyObject,
+    args: Vec<PyObject>,
+    keywords: Vec<(String, PyObject)>,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<PyObject> {
+    match func.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Function(x))) => x.call(args, keywords, env),
+        Ok(PyObjAttr::Rust(DataType::BuiltinFunction(x))) => call_builtin(x.as_str(), args, keywords, namespace, env),
+        Ok(PyObjAttr::Rust(DataType::Class(x))) => instantiate(func, &x, args, keywords, namespace, env),
+        Ok(PyObjAttr::Rust(DataType::Method(instance, function))) => {
+            let mut method_args = vec![*instance];
+            method_args.extend(args);
+            call_object(&function, method_args, keywords, namespace, env)
+        }
+        Ok(PyObjAttr::Rust(DataType::BuiltinMethod(instance, method))) => {
+            call_builtin_method(&instance, method.as_str(), args, keywords, namespace, env)
+        }
+        _ => match func.class().and_then(|x| x.lookup("__call__")) {
+            Some(x) => {
+                let mut method_args = vec![func.clone()];
+                method_args.extend(args);
+                call_object(&x, method_args, keywords, namespace, env)
+            }
+            None => raise("TypeError", format!("'{}' object is not callable", func.return_identity())),
+        },
+    }
+}
+
+pub fn obj_function(function: PyFunction) -> PyObject {
+    let name = "function".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__bool__"), vec![]),
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+    ];
+    build_method!(
+        name: name;
+        data:DataType::Function(Box::new(function));
+        method_vec:method_vec
+    )
+}
+pub fn function_behaviour(method: String, args: HashMapAttr, _namespace: Namespace, _env: &mut PyNamespace) -> Exec<PyResult> {
+    let function = match obj_parser("self".to_string(), "x".to_string(), args) {
+        Ok(DataType::Function(x)) => x,
+        _ => panic!("Error to get function"),
+    };
+    Ok(match method.as_str() {
+        "__bool__" => PyResult::Some(obj_bool(true)),
+        "__str__" | "__repr__" => PyResult::Some(obj_str(format!(
+            "<function {} at 0x{}>",
+            function.name,
+            &function.id.simple().to_string()[..12]
+        ))),
+        _ => PyResult::None,
+    })
 }
@@ -0,0 +1,666 @@
+use std::collections::HashMap;
+
+use crate::ast::ast_struct::{
+    assign_target, deref_expression, Assign, AugAssign, Constant, DataType, For, Try, Type, While, With,
+};
+use crate::ast::data_type::attribute::{obj_getattr, obj_lookup};
+use crate::ast::data_type::class::type_of;
+use crate::ast::data_type::core_type::{build_rust_method, build_rust_method_signature, obj_parser};
+use crate::ast::data_type::exception::{
+    error, exception_data, exception_matches, is_exception_class, is_instance_of, obj_exception, obj_stop_iteration, raise,
+    raise_exception, stop_value, Exec, Handling, Raised,
+};
+use crate::ast::data_type::function::{call_object, PyFunction};
+use crate::ast::data_type::iterator::{next_or_return, obj_iter, obj_next};
+use crate::ast::data_type::none::obj_none;
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{obj_to_bool, HashMapAttr, PyObject, PyResult, Shared};
+use crate::ast::data_type::signature::Signature;
+use crate::ast::data_type::str::obj_str;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+
+/// ## struct PyGenerator
+/// 调用生成器函数得到的生成器，函数帧在生成器结束之前一直保留
+/// - frame：函数帧的命名空间
+/// - module：定义生成器函数的模块，执行时在这个模块中查找全局变量
+#[derive(Debug)]
+pub struct PyGenerator {
+    pub name: String,
+    frame: Namespace,
+    module: String,
+    state: State,
+}
+
+/// 执行期间Frame被取出，此时再次进入生成器会报错
+#[derive(Debug)]
+enum State {
+    Ready(Box<Frame>),
+    Running,
+    Finished,
+}
+
+/// ## struct Frame
+/// 生成器暂停的位置
+/// - blocks：正在执行的语句块，最后一个是最内层的
+/// - resume：恢复执行时首先要完成的语句
+#[derive(Debug)]
+struct Frame {
+    blocks: Vec<Block>,
+    resume: Resume,
+}
+
+#[derive(Debug)]
+enum Resume {
+    /// 还没有开始执行
+    Start,
+    /// 暂停在yield处，send的值是yield表达式的值
+    Yield(Type),
+    /// 暂停在yield from处，send和throw交给子迭代器
+    Delegate(Type, PyObject),
+}
+
+/// ## enum Block
+/// 执行到一半的语句块，代替tree walker中exec_commands的递归调用
+/// - Suite：语句序列和下一条语句的下标
+/// - For：for语句和它的迭代器
+/// - Try：try语句和执行到的部分
+/// - With：with语句中的`__exit__`
+#[derive(Debug)]
+enum Block {
+    Suite(Vec<Type>, usize),
+    While(Box<While>),
+    For(Box<For>, PyObject),
+    Try(Box<Try>, Stage),
+    With(PyObject),
+}
+
+/// try语句执行到的部分
+/// - Handler：正在处理的异常和as绑定的名字
+/// - Finally：finally执行完之后继续传递的信号
+#[derive(Debug)]
+enum Stage {
+    Body,
+    Handler(PyObject, Option<String>),
+    Else,
+    Finally(Option<Signal>),
+}
+
+/// 提前离开语句块的原因
+#[derive(Debug)]
+enum Signal {
+    Break,
+    Continue,
+    Return(Box<PyObject>),
+    Raise(Raised),
+}
+
+impl From<Raised> for Signal {
+    fn from(value: Raised) -> Self {
+        Signal::Raise(value)
+    }
+}
+
+/// 执行一步之后的去向：继续执行、暂停并产出值或者结束并返回值
+enum Flow {
+    Next,
+    Suspend(PyObject),
+    Finish(PyObject),
+}
+
+/// 恢复生成器时传入的值：send的值或throw的异常
+pub enum Input {
+    Send(PyObject),
+    Throw(PyObject),
+}
+
+impl PyGenerator {
+    /// frame是已经绑定好参数的函数帧
+    pub fn new(function: &PyFunction, frame: Namespace) -> Self {
+        PyGenerator {
+            name: function.name.clone(),
+            frame,
+            module: function.module.clone(),
+            state: State::Ready(Box::new(Frame {
+                blocks: vec![Block::Suite(function.body.clone(), 0)],
+                resume: Resume::Start,
+            })),
+        }
+    }
+}
+
+fn evaluate(expr: &Type, env: &mut PyNamespace, namespace: Namespace) -> Result<PyObject, Signal> {
+    Ok(deref_expression(expr.clone(), env, namespace)?.value)
+}
+
+fn condition(expr: &Type, env: &mut PyNamespace, namespace: Namespace) -> Result<bool, Signal> {
+    let value = deref_expression(expr.clone(), env, namespace.clone())?.value;
+    Ok(obj_to_bool(value, namespace, env)?)
+}
+
+/// 可迭代对象的迭代器
+fn iterate(iterable: &PyObject, env: &mut PyNamespace, namespace: Namespace) -> Result<PyObject, Signal> {
+    match obj_iter(iterable, namespace, env)? {
+        Some(x) => Ok(x),
+        None => Err(error("TypeError", format!("'{}' object is not iterable", iterable.return_identity())).into()),
+    }
+}
+
+/// 用tree walker执行没有yield的语句
+fn execute(mut statement: Type, env: &mut PyNamespace, namespace: Namespace) -> Result<Flow, Signal> {
+    match statement.exec(env, namespace)? {
+        Type::Break => Err(Signal::Break),
+        Type::Continue => Err(Signal::Continue),
+        Type::Return(x) => Err(Signal::Return(Box::new(match x.value.map(|x| *x) {
+            Some(Type::Constant(x)) => x.value,
+            _ => obj_none(),
+        }))),
+        _ => Ok(Flow::Next),
+    }
+}
+
+/// ## fn complete
+/// 用yield表达式的值完成暂停时的语句，赋值语句把它赋给目标
+fn complete(statement: &Type, value: PyObject, env: &mut PyNamespace, namespace: Namespace) -> Result<Flow, Signal> {
+    let value = Box::new(Type::Constant(Constant::new(value)));
+    let statement = match statement {
+        Type::Assign(x) => Type::Assign(Box::new(Assign { value, ..*x.clone() })),
+        Type::AugAssign(x) => Type::AugAssign(Box::new(AugAssign { value, ..*x.clone() })),
+        _ => return Ok(Flow::Next),
+    };
+    execute(statement, env, namespace)
+}
+
+fn is_yield(expr: &Type) -> bool {
+    matches!(expr, Type::Yield(_) | Type::YieldFrom(_))
+}
+
+/// 语句中的yield表达式，只能是整条语句或赋值的右边
+fn yield_of(statement: &Type) -> Option<&Type> {
+    match statement {
+        Type::Assign(x) => Some(x.value.as_ref()).filter(|x| is_yield(x)),
+        Type::AugAssign(x) => Some(x.value.as_ref()).filter(|x| is_yield(x)),
+        x => Some(x).filter(|x| is_yield(x)),
+    }
+}
+
+/// 语句中是否有yield，没有yield的语句直接由tree walker执行
+fn contains_yield(statement: &Type) -> bool {
+    let suite = |body: &[Type]| body.iter().any(contains_yield);
+    match statement {
+        Type::If(x) => suite(&x.body) || suite(&x.orelse),
+        Type::While(x) => suite(&x.body) || suite(&x.orelse),
+        Type::For(x) => suite(&x.body) || suite(&x.orelse),
+        Type::Try(x) => {
+            suite(&x.body) || x.handlers.iter().any(|x| suite(&x.body)) || suite(&x.orelse) || suite(&x.finalbody)
+        }
+        Type::With(x) => suite(&x.body),
+        x => yield_of(x).is_some(),
+    }
+}
+
+/// 在暂停处抛出throw传入的异常
+fn thrown(exc: PyObject) -> Signal {
+    Signal::Raise(raise_exception::<()>(exc).unwrap_err())
+}
+
+impl Frame {
+    /// ## fn run
+    /// 从暂停处执行到下一个yield，返回Ok(产出的值)；生成器结束时返回Err(返回值)，异常直接抛出
+    fn run(&mut self, input: Input, env: &mut PyNamespace, namespace: Namespace) -> Exec<Result<PyObject, PyObject>> {
+        // the except clauses suspended at a yield are handling their exceptions again
+        let mut handling: Vec<Handling> = self
+            .blocks
+            .iter()
+            .filter_map(|x| match x {
+                Block::Try(_, Stage::Handler(exc, _)) => Some(Handling::enter(exc.clone())),
+                _ => None,
+            })
+            .collect();
+        let mut step = match (std::mem::replace(&mut self.resume, Resume::Start), input) {
+            (Resume::Delegate(statement, iterator), input) => {
+                self.delegate(statement, iterator, input, env, namespace.clone())
+            }
+            (_, Input::Throw(exc)) => Err(thrown(exc)),
+            (Resume::Yield(statement), Input::Send(value)) => complete(&statement, value, env, namespace.clone()),
+            (Resume::Start, Input::Send(_)) => Ok(Flow::Next),
+        };
+        loop {
+            step = match step {
+                Ok(Flow::Next) => self.step(&mut handling, env, namespace.clone()),
+                Ok(Flow::Suspend(x)) => return Ok(Ok(x)),
+                Ok(Flow::Finish(x)) => return Ok(Err(x)),
+                Err(Signal::Raise(x)) if self.blocks.is_empty() => return Err(x),
+                Err(x) => self.unwind(x, &mut handling, env, namespace.clone()),
+            };
+        }
+    }
+    /// 执行最内层语句序列中的下一条语句
+    fn step(&mut self, handling: &mut Vec<Handling>, env: &mut PyNamespace, namespace: Namespace) -> Result<Flow, Signal> {
+        let statement = match self.blocks.last_mut() {
+            Some(Block::Suite(body, index)) if *index < body.len() => {
+                *index += 1;
+                body[*index - 1].clone()
+            }
+            Some(Block::Suite(..)) => {
+                self.blocks.pop();
+                return self.complete_block(handling, env, namespace);
+            }
+            _ => return Ok(Flow::Finish(obj_none())),
+        };
+        if !contains_yield(&statement) {
+            return execute(statement, env, namespace);
+        }
+        match yield_of(&statement).cloned() {
+            Some(Type::Yield(x)) => {
+                let value = match &x.value {
+                    Some(x) => evaluate(x, env, namespace)?,
+                    None => obj_none(),
+                };
+                self.resume = Resume::Yield(statement);
+                return Ok(Flow::Suspend(value));
+            }
+            Some(Type::YieldFrom(x)) => {
+                let iterable = evaluate(&x.value, env, namespace.clone())?;
+                let iterator = iterate(&iterable, env, namespace.clone())?;
+                return self.delegate(statement, iterator, Input::Send(obj_none()), env, namespace);
+            }
+            _ => {}
+        }
+        match statement {
+            Type::If(x) => {
+                let body = match condition(&x.test, env, namespace)? {
+                    true => x.body,
+                    false => x.orelse,
+                };
+                self.blocks.push(Block::Suite(body, 0));
+            }
+            Type::While(x) => {
+                self.blocks.push(Block::While(x));
+                return self.complete_block(handling, env, namespace);
+            }
+            Type::For(x) => {
+                let iterable = evaluate(&x.iter, env, namespace.clone())?;
+                let iterator = iterate(&iterable, env, namespace.clone())?;
+                self.blocks.push(Block::For(x, iterator));
+                return self.complete_block(handling, env, namespace);
+            }
+            Type::Try(x) => {
+                let body = x.body.clone();
+                self.blocks.push(Block::Try(x, Stage::Body));
+                self.blocks.push(Block::Suite(body, 0));
+            }
+            Type::With(x) => {
+                let item = x.items[0].clone();
+                let (value, exit) = item.enter(env, namespace.clone())?;
+                self.blocks.push(Block::With(exit));
+                // the other context managers are entered by a nested with statement
+                let body = match x.items.len() {
+                    1 => x.body,
+                    _ => vec![Type::With(Box::new(With {
+                        items: x.items[1..].to_vec(),
+                        body: x.body,
+                    }))],
+                };
+                self.blocks.push(Block::Suite(body, 0));
+                if let Some(target) = &item.optional_vars {
+                    assign_target(target, value, env, namespace)?;
+                }
+            }
+            x => return execute(x, env, namespace),
+        }
+        Ok(Flow::Next)
+    }
+    /// ## fn complete_block
+    /// 最内层的语句序列执行完后，决定包含它的语句块接下来执行什么
+    fn complete_block(&mut self, handling: &mut Vec<Handling>, env: &mut PyNamespace, namespace: Namespace) -> Result<Flow, Signal> {
+        let block = match self.blocks.pop() {
+            Some(x) => x,
+            None => return Ok(Flow::Finish(obj_none())),
+        };
+        match block {
+            // the branch of an if statement is part of the enclosing suite
+            Block::Suite(..) => self.blocks.push(block),
+            Block::While(x) => {
+                if condition(&x.test, env, namespace)? {
+                    let body = x.body.clone();
+                    self.blocks.push(Block::While(x));
+                    self.blocks.push(Block::Suite(body, 0));
+                } else {
+                    self.blocks.push(Block::Suite(x.orelse, 0));
+                }
+            }
+            Block::For(x, iterator) => match obj_next(&iterator, namespace.clone(), env)? {
+                Some(item) => {
+                    let (target, body) = (x.target.clone(), x.body.clone());
+                    self.blocks.push(Block::For(x, iterator));
+                    self.blocks.push(Block::Suite(body, 0));
+                    assign_target(&target, item, env, namespace)?;
+                }
+                None => self.blocks.push(Block::Suite(x.orelse, 0)),
+            },
+            Block::Try(x, Stage::Body) => {
+                let orelse = x.orelse.clone();
+                self.blocks.push(Block::Try(x, Stage::Else));
+                self.blocks.push(Block::Suite(orelse, 0));
+            }
+            Block::Try(x, Stage::Handler(_, name)) => {
+                leave_handler(name, handling, env, namespace);
+                self.finally(x, None);
+            }
+            Block::Try(x, Stage::Else) => self.finally(x, None),
+            Block::Try(_, Stage::Finally(signal)) => {
+                if let Some(x) = signal {
+                    return Err(x);
+                }
+            }
+            Block::With(exit) => {
+                call_object(&exit, vec![obj_none(), obj_none(), obj_none()], vec![], namespace, env)?;
+            }
+        }
+        Ok(Flow::Next)
+    }
+    /// ## fn unwind
+    /// 信号从内向外离开语句块，直到被循环、except子句或with处理，经过的finally都会执行
+    fn unwind(
+        &mut self,
+        mut signal: Signal,
+        handling: &mut Vec<Handling>,
+        env: &mut PyNamespace,
+        namespace: Namespace,
+    ) -> Result<Flow, Signal> {
+        loop {
+            let block = match self.blocks.pop() {
+                Some(x) => x,
+                None => {
+                    return match signal {
+                        Signal::Raise(x) => Err(Signal::Raise(x)),
+                        Signal::Return(x) => Ok(Flow::Finish(*x)),
+                        _ => Ok(Flow::Finish(obj_none())),
+                    }
+                }
+            };
+            signal = match (block, signal) {
+                (Block::Suite(..), x) => x,
+                (Block::While(_) | Block::For(..), Signal::Break) => return Ok(Flow::Next),
+                (x @ (Block::While(_) | Block::For(..)), Signal::Continue) => {
+                    self.blocks.push(x);
+                    return self.complete_block(handling, env, namespace);
+                }
+                (Block::While(_) | Block::For(..), x) => x,
+                (Block::Try(x, Stage::Body), Signal::Raise(error)) => {
+                    return self.handle(x, error, handling, env, namespace);
+                }
+                (Block::Try(x, Stage::Handler(_, name)), signal) => {
+                    leave_handler(name, handling, env, namespace);
+                    self.finally(x, Some(signal));
+                    return Ok(Flow::Next);
+                }
+                (Block::Try(x, Stage::Body | Stage::Else), signal) => {
+                    self.finally(x, Some(signal));
+                    return Ok(Flow::Next);
+                }
+                // break, continue and return in finally replace the pending signal
+                (Block::Try(_, Stage::Finally(_)), x) => x,
+                (Block::With(exit), Signal::Raise(error)) => {
+                    let exc = *error.clone();
+                    let suppress = {
+                        let _handling = Handling::enter(exc.clone());
+                        let args = vec![type_of(&exc), exc.clone(), obj_none()];
+                        call_object(&exit, args, vec![], namespace.clone(), env)
+                            .and_then(|x| obj_to_bool(x, namespace.clone(), env))
+                    };
+                    match suppress {
+                        Ok(true) => return Ok(Flow::Next),
+                        Ok(false) => Signal::Raise(error),
+                        Err(x) => Signal::Raise(x),
+                    }
+                }
+                (Block::With(exit), signal) => {
+                    let args = vec![obj_none(), obj_none(), obj_none()];
+                    match call_object(&exit, args, vec![], namespace.clone(), env) {
+                        Ok(_) => signal,
+                        Err(x) => Signal::Raise(x),
+                    }
+                }
+            };
+        }
+    }
+    /// 异常离开try的body时依次匹配except子句，没有匹配的子句时执行finally后继续抛出
+    fn handle(
+        &mut self,
+        node: Box<Try>,
+        error: Raised,
+        handling: &mut Vec<Handling>,
+        env: &mut PyNamespace,
+        namespace: Namespace,
+    ) -> Result<Flow, Signal> {
+        let exc = *error.clone();
+        for handler in node.handlers.clone() {
+            if let Some(type_) = &handler.type_ {
+                let matched = deref_expression(type_.clone(), env, namespace.clone())
+                    .and_then(|x| exception_matches(&exc, &x.value));
+                match matched {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(x) => {
+                        self.finally(node, Some(Signal::Raise(x)));
+                        return Ok(Flow::Next);
+                    }
+                }
+            }
+            handling.push(Handling::enter(exc.clone()));
+            self.blocks.push(Block::Try(node, Stage::Handler(exc.clone(), handler.name.clone())));
+            self.blocks.push(Block::Suite(handler.body, 0));
+            if let Some(name) = handler.name {
+                if let Some(x) = env.set_variable(namespace, name, exc) {
+                    return Err(Signal::Raise(x.into()));
+                }
+            }
+            return Ok(Flow::Next);
+        }
+        self.finally(node, Some(Signal::Raise(error)));
+        Ok(Flow::Next)
+    }
+    fn finally(&mut self, node: Box<Try>, signal: Option<Signal>) {
+        let finalbody = node.finalbody.clone();
+        self.blocks.push(Block::Try(node, Stage::Finally(signal)));
+        self.blocks.push(Block::Suite(finalbody, 0));
+    }
+    /// ## fn delegate
+    /// yield from把send和throw交给子迭代器，子迭代器结束时它的返回值是yield from表达式的值
+    fn delegate(
+        &mut self,
+        statement: Type,
+        iterator: PyObject,
+        input: Input,
+        env: &mut PyNamespace,
+        namespace: Namespace,
+    ) -> Result<Flow, Signal> {
+        let (method, arg) = match input {
+            Input::Send(x) if x.return_identity() == "NoneType" => (None, x),
+            Input::Send(x) => (Some("send"), x),
+            // the subiterator is closed before GeneratorExit is raised in the delegating generator
+            Input::Throw(exc) if is_instance_of(&exc, "GeneratorExit") => {
+                if let Some(close) = obj_lookup(&iterator, "close", namespace.clone(), env)? {
+                    call_object(&close, vec![], vec![], namespace.clone(), env)?;
+                }
+                return Err(thrown(exc));
+            }
+            Input::Throw(exc) => match obj_lookup(&iterator, "throw", namespace.clone(), env)? {
+                Some(_) => (Some("throw"), exc),
+                None => return Err(thrown(exc)),
+            },
+        };
+        let result = match method {
+            Some(name) => obj_getattr(&iterator, name, namespace.clone(), env)
+                .and_then(|method| call_object(&method, vec![arg], vec![], namespace.clone(), env))
+                .map(Ok),
+            None => next_or_return(&iterator, namespace.clone(), env),
+        };
+        match result {
+            Ok(Ok(x)) => {
+                self.resume = Resume::Delegate(statement, iterator);
+                Ok(Flow::Suspend(x))
+            }
+            Ok(Err(x)) => complete(&statement, x, env, namespace),
+            Err(x) if is_instance_of(&x, "StopIteration") => complete(&statement, stop_value(&x), env, namespace),
+            Err(x) => Err(Signal::Raise(x)),
+        }
+    }
+}
+
+/// except子句结束时删除as绑定的名字，不再处理它的异常
+fn leave_handler(name: Option<String>, handling: &mut Vec<Handling>, env: &mut PyNamespace, namespace: Namespace) {
+    if let Some(name) = name {
+        env.delete_variable(&namespace, name.as_str());
+    }
+    handling.pop();
+}
+
+/// ## fn resume
+/// 恢复执行生成器直到下一个yield，返回产出的值，生成器结束时抛出StopIteration
+pub fn resume(generator: &Shared<PyGenerator>, input: Input, env: &mut PyNamespace) -> Exec<PyObject> {
+    let mut frame = {
+        let mut current = generator.borrow_mut();
+        match std::mem::replace(&mut current.state, State::Running) {
+            State::Ready(x) => match (&x.resume, &input) {
+                (Resume::Start, Input::Send(value)) if value.return_identity() != "NoneType" => {
+                    current.state = State::Ready(x);
+                    return raise("TypeError", "can't send non-None value to a just-started generator");
+                }
+                _ => x,
+            },
+            State::Running => {
+                current.state = State::Running;
+                return raise("ValueError", "generator already executing");
+            }
+            State::Finished => {
+                current.state = State::Finished;
+                return match input {
+                    Input::Send(_) => raise_exception(obj_stop_iteration(obj_none())),
+                    Input::Throw(exc) => raise_exception(exc),
+                };
+            }
+        }
+    };
+    let (scope, module) = {
+        let current = generator.borrow();
+        (current.frame.clone(), current.module.clone())
+    };
+    let module = std::mem::replace(&mut env.module, module);
+    let result = frame.run(input, env, scope.clone());
+    env.module = module;
+    if let Ok(Ok(x)) = result {
+        generator.borrow_mut().state = State::Ready(frame);
+        return Ok(x);
+    }
+    generator.borrow_mut().state = State::Finished;
+    env.delete_local_namespace(&scope);
+    match result {
+        Ok(Ok(_)) => unreachable!(),
+        Ok(Err(x)) => raise_exception(obj_stop_iteration(x)),
+        // a StopIteration leaving the generator would be taken as its end
+        Err(x) if is_instance_of(&x, "StopIteration") => {
+            let error = obj_exception("RuntimeError", vec![obj_str("generator raised StopIteration".to_string())]);
+            if let Some(data) = exception_data(&error) {
+                let mut data = data.borrow_mut();
+                data.cause = Some(*x.clone());
+                data.context = Some(*x);
+                data.suppress_context = true;
+            }
+            Err(Box::new(error))
+        }
+        Err(x) => Err(x),
+    }
+}
+
+/// ## fn close
+/// 在暂停处抛出GeneratorExit，生成器因此结束时close正常返回
+fn close(generator: &Shared<PyGenerator>, env: &mut PyNamespace) -> Exec<PyObject> {
+    let exc = obj_exception("GeneratorExit", vec![]);
+    match resume(generator, Input::Throw(exc), env) {
+        Ok(_) => raise("RuntimeError", "generator ignored GeneratorExit"),
+        Err(x) if is_instance_of(&x, "GeneratorExit") || is_instance_of(&x, "StopIteration") => Ok(obj_none()),
+        Err(x) => Err(x),
+    }
+}
+
+/// throw(typ, val=None, tb=None)的异常，typ可以是异常类或异常实例
+fn thrown_exception(typ: PyObject, val: PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyObject> {
+    let none = val.return_identity() == "NoneType";
+    match typ.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Class(x))) if is_exception_class(&x) => {
+            let args = match val.get_value("x".to_string()) {
+                _ if none => vec![],
+                Ok(PyObjAttr::Rust(DataType::Tuple(x))) => x,
+                _ => vec![val],
+            };
+            call_object(&typ, args, vec![], namespace, env)
+        }
+        _ if exception_data(&typ).is_some() => match none {
+            true => Ok(typ),
+            false => raise("TypeError", "instance exception may not have a separate value"),
+        },
+        _ => raise(
+            "TypeError",
+            format!(
+                "exceptions must be classes or instances deriving from BaseException, not {}",
+                typ.return_identity()
+            ),
+        ),
+    }
+}
+
+pub fn obj_generator(generator: PyGenerator) -> PyObject {
+    let name = "generator".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+        build_rust_method(name.clone(), String::from("__iter__"), vec![]),
+        build_rust_method(name.clone(), String::from("__next__"), vec![]),
+        build_rust_method(name.clone(), String::from("send"), vec!["self".to_string(), "value".to_string()]),
+        build_rust_method_signature(
+            name.clone(),
+            String::from("throw"),
+            Signature {
+                args: vec!["typ".to_string(), "val".to_string(), "tb".to_string()],
+                defaults: vec![obj_none(), obj_none()],
+                ..Default::default()
+            },
+        ),
+        build_rust_method(name.clone(), String::from("close"), vec![]),
+    ];
+    build_method!(
+        name: name;
+        data:DataType::Generator(Shared::new(generator));
+        method_vec:method_vec
+    )
+}
+pub fn generator_behaviour(method: String, args: HashMapAttr, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+    let generator = match obj_parser("self".to_string(), "x".to_string(), args.clone()) {
+        Ok(DataType::Generator(x)) => x,
+        _ => panic!("Error to get generator"),
+    };
+    let arg = |name: &str| match args.get(name) {
+        Some(PyObjAttr::Interpreter(x)) => *x.clone(),
+        _ => panic!("Error to get generator"),
+    };
+    Ok(match method.as_str() {
+        "__str__" | "__repr__" => PyResult::Some(obj_str(format!(
+            "<generator object {} at {:#x}>",
+            generator.borrow().name,
+            generator.address()
+        ))),
+        "__iter__" => PyResult::Some(arg("self")),
+        "__next__" => PyResult::Some(resume(&generator, Input::Send(obj_none()), env)?),
+        "send" => PyResult::Some(resume(&generator, Input::Send(arg("value")), env)?),
+        "throw" => {
+            let exc = thrown_exception(arg("typ"), arg("val"), namespace, env)?;
+            PyResult::Some(resume(&generator, Input::Throw(exc), env)?)
+        }
+        "close" => PyResult::Some(close(&generator, env)?),
+        _ => PyResult::None,
+    })
+}
@@ -0,0 +1,93 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::ast::ast_struct::{Compare, DataType, Operator};
+use crate::ast::data_type::exception::{raise, Exec};
+use crate::ast::data_type::function::call_object;
+use crate::ast::data_type::object::{PyObjAttr, PyObject};
+use crate::ast::namespace::{Namespace, PyNamespace};
+
+/// 可变的内置类型没有哈希值，不能作为字典的键或集合的元素
+const UNHASHABLE: [&str; 3] = ["list", "dict", "set"];
+
+fn unhashable<T>(obj: &PyObject) -> Exec<T> {
+    raise("TypeError", format!("unhashable type: '{}'", obj.return_identity()))
+}
+
+fn hash_value<T: Hash>(x: T) -> i64 {
+    let mut hasher = DefaultHasher::new();
+    x.hash(&mut hasher);
+    hasher.finish() as i64
+}
+
+/// ## fn obj_hash
+/// 相当于hash(obj)，相等的数字哈希值相同，如1、1.0和True
+/// python中定义的类使用`__hash__`，只定义了`__eq__`的类和list、dict、set不能哈希
+pub fn obj_hash(obj: &PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<i64> {
+    if let Some(class) = obj.class() {
+        match class.lookup("__hash__") {
+            Some(x) if x.return_identity() == "NoneType" => return unhashable(obj),
+            Some(x) => {
+                let value = call_object(&x, vec![obj.clone()], vec![], namespace, env)?;
+                return match value.get_value("x".to_string()) {
+                    Ok(PyObjAttr::Rust(DataType::Int(x))) => Ok(x),
+                    Ok(PyObjAttr::Rust(DataType::Bool(x))) => Ok(x as i64),
+                    _ => raise("TypeError", "__hash__ method should return an integer"),
+                };
+            }
+            // a class that defines __eq__ without __hash__ is unhashable
+            None if class.lookup("__eq__").is_some() => return unhashable(obj),
+            None => {}
+        }
+    }
+    Ok(match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Int(x))) => x,
+        Ok(PyObjAttr::Rust(DataType::Bool(x))) => x as i64,
+        Ok(PyObjAttr::Rust(DataType::Float(x))) => float_hash(x),
+        Ok(PyObjAttr::Rust(DataType::Complex(real, 0.0))) => float_hash(real),
+        Ok(PyObjAttr::Rust(DataType::Complex(real, imag))) => hash_value((real.to_bits(), imag.to_bits())),
+        Ok(PyObjAttr::Rust(DataType::Str(x))) => hash_value(x),
+        Ok(PyObjAttr::Rust(DataType::Bytes(x))) => hash_value(x),
+        Ok(PyObjAttr::Rust(DataType::Tuple(x))) => {
+            let mut hashes = vec![];
+            for item in x {
+                hashes.push(obj_hash(&item, namespace.clone(), env)?);
+            }
+            hash_value(hashes)
+        }
+        Ok(PyObjAttr::Rust(DataType::Range(start, stop, step))) => hash_value((start, stop, step)),
+        _ if UNHASHABLE.contains(&obj.return_identity().as_str()) => return unhashable(obj),
+        // other objects are only equal to themselves, an instance is identified by its __dict__
+        _ => match obj.get_value("__dict__".to_string()) {
+            Ok(PyObjAttr::Rust(DataType::Dict(x))) => x.address() as i64,
+            _ => 0,
+        },
+    })
+}
+
+/// 整数值的float与对应的int哈希值相同
+fn float_hash(x: f64) -> i64 {
+    if x.fract() == 0.0 && x.abs() < 9.2e18 {
+        x as i64
+    } else {
+        hash_value(x.to_bits())
+    }
+}
+
+/// ## fn key_index
+/// 在字典的键或集合的元素中查找key的位置：哈希值相同并且==比较相等，同一个对象总是相等
+/// key不能哈希时抛出TypeError
+pub fn key_index(keys: Vec<PyObject>, key: &PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<Option<usize>> {
+    let hash = obj_hash(key, namespace.clone(), env)?;
+    for (index, x) in keys.into_iter().enumerate() {
+        if x == *key {
+            return Ok(Some(index));
+        }
+        if obj_hash(&x, namespace.clone(), env)? == hash
+            && Compare::compare(Operator::Eq, x, key.clone(), namespace.clone(), env)?
+        {
+            return Ok(Some(index));
+        }
+    }
+    Ok(None)
+}
@@ -1,6 +1,7 @@
 use crate::ast::ast_struct::DataType;
 use crate::ast::data_type::bool::obj_bool;
 use crate::ast::data_type::core_type::build_rust_method;
+use crate::ast::data_type::exception::{raise, Exec};
 use crate::ast::data_type::core_type::{custom_behaviour, obj_parser};
 use crate::ast::data_type::object::PyObjAttr;
 use crate::ast::data_type::object::PyObjBehaviors;
@@ -17,6 +18,7 @@ pub fn obj_int(x: i64) -> PyObject {
     let mut method_vec: Vec<(String, PyObjBehaviors)> =
         build_method!(name:name.clone();param:vec!["self".to_string(),"other".to_string()]);
     method_vec.append(&mut build_method!(int_and_float;name:name.clone()));
+    method_vec.append(&mut build_method!(int_and_bool;name:name.clone()));
     build_method!(
         name: name;
         data:DataType::Int(x);
@@ -24,32 +26,33 @@ pub fn obj_int(x: i64) -> PyObject {
     )
 }
 
-pub fn int_behaviour(method: String, args: HashMapAttr,namespace: Namespace,env:&mut PyNamespace) -> PyResult {
+pub fn int_behaviour(method: String, args: HashMapAttr,_namespace: Namespace,_env:&mut PyNamespace) -> Exec<PyResult> {
     let data_type_obj_x: DataType = obj_parser("self".to_string(), "x".to_string(), args.clone())
         .unwrap_or_else(|x| panic!("{}", x));
     let int_x: i64;
     match data_type_obj_x {
         DataType::Int(x) => int_x = x,
         _ => {
-            return PyResult::Err(ErrorType::ObjMethodCallError(
+            return Ok(PyResult::Err(ErrorType::ObjMethodCallError(
                 ObjMethodCallError::default()
                     .obj(ObjBasicError::default().identity("int".parse().unwrap()))
                     .method(method),
-            ))
+            )))
         }
     }
-    match custom_behaviour(data_type_obj_x.clone(), method.clone(), args) {
-        PyResult::Some(x) => {
-            return PyResult::Some(x);
-        }
-        _ => {}
+    if let PyResult::Some(x) = custom_behaviour(data_type_obj_x.clone(), method.clone(), args)? {
+        return Ok(PyResult::Some(x));
     }
     match method.as_str() {
-        "__bool__" => return PyResult::Some(obj_bool(data_type_obj_x.bool())),
-        "__neg__" => return PyResult::Some(obj_int(-int_x)),
-        "__pos__" => return PyResult::Some(obj_int(int_x)),
-        "__str__" => return PyResult::Some(obj_str(int_x.to_string())),
+        "__bool__" => return Ok(PyResult::Some(obj_bool(data_type_obj_x.bool()))),
+        "__neg__" => match int_x.checked_neg() {
+            Some(x) => return Ok(PyResult::Some(obj_int(x))),
+            None => return raise("OverflowError", "integer overflow"),
+        },
+        "__pos__" => return Ok(PyResult::Some(obj_int(int_x))),
+        "__invert__" => return Ok(PyResult::Some(obj_int(!int_x))),
+        "__str__" => return Ok(PyResult::Some(obj_str(int_x.to_string()))),
         _ => {}
     }
-    PyResult::None
+    Ok(PyResult::None)
 }
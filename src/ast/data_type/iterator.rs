@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use crate::ast::ast_struct::{ComprehensionState, DataType};
+use crate::ast::data_type::core_type::{build_rust_method, obj_parser};
+use crate::ast::data_type::exception::{is_instance_of, raise, stop_value, Exec};
+use crate::ast::data_type::int::obj_int;
+use crate::ast::data_type::none::obj_none;
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{HashMapAttr, PyObject, PyResult, Shared};
+use crate::ast::data_type::str::obj_str;
+use crate::ast::data_type::tuple::obj_tuple;
+use crate::ast::error::object_error::{ObjBasicError, StopIterationError};
+use crate::ast::error::ErrorType;
+use crate::ast::namespace::{Namespace, PyNamespace};
+
+/// ## enum IterState
+/// 内置迭代器的状态
+/// - Items：创建迭代器时取出的所有元素和下一个元素的下标
+/// - Range：下一个值、终止值和步长
+/// - Enumerate：被包装的迭代器和下一个序号
+/// - Zip：被包装的所有迭代器
+/// - Comprehension：生成器表达式执行到的位置
+#[derive(Clone, Debug)]
+pub enum IterState {
+    Items(Vec<PyObject>, usize),
+    Range(i64, i64, i64),
+    Enumerate(PyObject, i64),
+    Zip(Vec<PyObject>),
+    Comprehension(Box<ComprehensionState>),
+}
+
+/// ## fn obj_iterator
+/// 内置迭代器，identity是迭代器的类型名，如str_iterator
+pub fn obj_iterator(identity: &str, state: IterState) -> PyObject {
+    let name = "iterator".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__iter__"), vec![]),
+        build_rust_method(name.clone(), String::from("__next__"), vec![]),
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+    ];
+    let behavior: HashMap<String, PyObjBehaviors> = method_vec.into_iter().collect();
+    PyObject::default()
+        .identity(identity.to_string())
+        .attr([(
+            String::from("x"),
+            PyObjAttr::Rust(DataType::Iterator(Shared::new(state))),
+        )])
+        .extend_behavior(behavior)
+}
+pub fn iterator_behaviour(method: String, args: HashMapAttr, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+    let obj = match args.get("self") {
+        Some(PyObjAttr::Interpreter(x)) => *x.clone(),
+        _ => panic!("Error to get iterator"),
+    };
+    let state = match obj_parser("self".to_string(), "x".to_string(), args) {
+        Ok(DataType::Iterator(x)) => x,
+        _ => panic!("Error to get iterator"),
+    };
+    Ok(match method.as_str() {
+        "__iter__" => PyResult::Some(obj),
+        "__next__" => match next_item(&state, namespace, env)? {
+            Some(x) => PyResult::Some(x),
+            None => PyResult::Err(ErrorType::StopIterationError(
+                StopIterationError::default().obj(ObjBasicError::default().identity(obj.return_identity())),
+            )),
+        },
+        "__str__" | "__repr__" if matches!(*state.borrow(), IterState::Comprehension(_)) => PyResult::Some(obj_str(
+            format!("<generator object <genexpr> at {:#x}>", state.address()),
+        )),
+        "__str__" | "__repr__" => PyResult::Some(obj_str(format!(
+            "<{} object at {:#x}>",
+            obj.return_identity(),
+            state.address()
+        ))),
+        _ => PyResult::None,
+    })
+}
+fn next_item(state: &Shared<IterState>, namespace: Namespace, env: &mut PyNamespace) -> Exec<Option<PyObject>> {
+    // the wrapped iterators may run python code, so the state is not borrowed while they run
+    let current = state.borrow().clone();
+    let (item, next) = match current {
+        IterState::Items(items, index) => match items.get(index).cloned() {
+            Some(x) => (x, IterState::Items(items, index + 1)),
+            None => return Ok(None),
+        },
+        IterState::Range(value, stop, step) => {
+            if (step > 0 && value >= stop) || (step < 0 && value <= stop) {
+                return Ok(None);
+            }
+            (obj_int(value), IterState::Range(value + step, stop, step))
+        }
+        IterState::Enumerate(iterator, count) => {
+            let item = match obj_next(&iterator, namespace, env)? {
+                Some(x) => x,
+                None => return Ok(None),
+            };
+            (obj_tuple(vec![obj_int(count), item]), IterState::Enumerate(iterator, count + 1))
+        }
+        IterState::Zip(iterators) => {
+            if iterators.is_empty() {
+                return Ok(None);
+            }
+            let mut items = vec![];
+            for iterator in iterators.iter() {
+                match obj_next(iterator, namespace.clone(), env)? {
+                    Some(x) => items.push(x),
+                    None => return Ok(None),
+                }
+            }
+            (obj_tuple(items), IterState::Zip(iterators))
+        }
+        IterState::Comprehension(mut comprehension) => {
+            let item = comprehension.next(env);
+            *state.borrow_mut() = IterState::Comprehension(comprehension);
+            return Ok(item?.map(|mut x| x.remove(0)));
+        }
+    };
+    *state.borrow_mut() = next;
+    Ok(Some(item))
+}
+
+/// ## fn obj_iter
+/// 相当于iter(obj)，对象没有`__iter__`时返回None
+pub fn obj_iter(obj: &PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<Option<PyObject>> {
+    match obj.clone().call(String::from("__iter__"), HashMap::new(), namespace, env)? {
+        PyResult::Some(x) => Ok(Some(x)),
+        PyResult::Err(ErrorType::ObjMethodCallError(_)) => Ok(None),
+        PyResult::Err(x) => Err(x.into()),
+        _ => raise("TypeError", "iter() returned non-iterator of type 'NoneType'"),
+    }
+}
+
+/// ## fn obj_next
+/// 相当于next(iterator)，迭代器耗尽时返回None
+pub fn obj_next(iterator: &PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<Option<PyObject>> {
+    Ok(next_or_return(iterator, namespace, env)?.ok())
+}
+
+/// ## fn next_or_return
+/// 与obj_next相同，迭代器耗尽时返回Err，其中是StopIteration的value，如生成器的返回值
+pub fn next_or_return(
+    iterator: &PyObject,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<Result<PyObject, PyObject>> {
+    let result = match iterator.clone().call(String::from("__next__"), HashMap::new(), namespace, env) {
+        Ok(x) => x,
+        // a __next__ defined in python ends the iteration by raising StopIteration
+        Err(x) if is_instance_of(&x, "StopIteration") => return Ok(Err(stop_value(&x))),
+        Err(x) => return Err(x),
+    };
+    match result {
+        PyResult::Some(x) => Ok(Ok(x)),
+        PyResult::Err(ErrorType::StopIterationError(_)) => Ok(Err(obj_none())),
+        PyResult::Err(ErrorType::ObjMethodCallError(_)) => {
+            raise("TypeError", format!("'{}' object is not an iterator", iterator.return_identity()))
+        }
+        PyResult::Err(x) => Err(x.into()),
+        _ => panic!("Error at running"),
+    }
+}
+
+/// ## fn obj_iterate
+/// 取出可迭代对象的所有元素，用于解包
+pub fn obj_iterate(obj: &PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<Option<Vec<PyObject>>> {
+    let iterator = match obj_iter(obj, namespace.clone(), env)? {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let mut items = vec![];
+    while let Some(x) = obj_next(&iterator, namespace.clone(), env)? {
+        items.push(x);
+    }
+    Ok(Some(items))
+}
@@ -1,16 +1,167 @@
+use std::collections::HashMap;
+
 use crate::ast::ast_struct::DataType;
-use crate::ast::data_type::core_type::build_rust_method;
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::core_type::{build_rust_method, obj_parser};
+use crate::ast::data_type::exception::{raise, Exec};
+use crate::ast::data_type::int::obj_int;
+use crate::ast::data_type::iterator::{obj_iterate, obj_iterator, IterState};
+use crate::ast::data_type::none::obj_none;
 use crate::ast::data_type::object::PyObjAttr;
 use crate::ast::data_type::object::PyObjBehaviors;
-use crate::ast::data_type::object::PyObject;
+use crate::ast::data_type::object::{obj_to_repr, HashMapAttr, PyObject, PyResult, Shared};
+use crate::ast::data_type::slice::{seq_index, slice_positions, SeqIndex};
+use crate::ast::data_type::str::obj_str;
+use crate::ast::data_type::tuple::{items_eq, items_order};
+use crate::ast::namespace::{Namespace, PyNamespace};
 use crate::build_method;
-use std::collections::HashMap;
 
-#[allow(dead_code)]
+/// 列表的元素是共享的，修改对所有引用可见
 pub fn obj_list(x: Vec<PyObject>) -> PyObject {
+    let name = "list".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__len__"), vec![]),
+        build_rust_method(name.clone(), String::from("__bool__"), vec![]),
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+        build_rust_method(name.clone(), String::from("__iter__"), vec![]),
+        build_rust_method(name.clone(), String::from("__getitem__"), vec!["self".to_string(), "key".to_string()]),
+        build_rust_method(
+            name.clone(),
+            String::from("__setitem__"),
+            vec!["self".to_string(), "key".to_string(), "value".to_string()],
+        ),
+        build_rust_method(name.clone(), String::from("__delitem__"), vec!["self".to_string(), "key".to_string()]),
+        build_rust_method(name.clone(), String::from("__eq__"), vec!["self".to_string(), "other".to_string()]),
+        build_rust_method(name.clone(), String::from("__lt__"), vec!["self".to_string(), "other".to_string()]),
+        build_rust_method(name.clone(), String::from("__le__"), vec!["self".to_string(), "other".to_string()]),
+        build_rust_method(name.clone(), String::from("__gt__"), vec!["self".to_string(), "other".to_string()]),
+        build_rust_method(name.clone(), String::from("__ge__"), vec!["self".to_string(), "other".to_string()]),
+        build_rust_method(name.clone(), String::from("__add__"), vec!["self".to_string(), "other".to_string()]),
+        build_rust_method(name.clone(), String::from("__iadd__"), vec!["self".to_string(), "other".to_string()]),
+        build_rust_method(name.clone(), String::from("append"), vec!["self".to_string(), "object".to_string()]),
+    ];
     build_method!(
-        name:"bool".to_string();
-        param:vec!["self".to_string(),"other".to_string()];
-        data:DataType::List(Box::from(x))
+        name: name;
+        data:DataType::List(Shared::new(x));
+        method_vec:method_vec
     )
 }
+pub fn list_behaviour(method: String, args: HashMapAttr, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+    let list = match obj_parser("self".to_string(), "x".to_string(), args.clone()) {
+        Ok(DataType::List(x)) => x,
+        _ => panic!("Error to get list"),
+    };
+    let items = list.borrow().clone();
+    let arg = |name: &str| match args.get(name) {
+        Some(PyObjAttr::Interpreter(x)) => *x.clone(),
+        _ => panic!("Error to get list"),
+    };
+    Ok(match method.as_str() {
+        "__len__" => PyResult::Some(obj_int(items.len() as i64)),
+        "__bool__" => PyResult::Some(obj_bool(!items.is_empty())),
+        "__iter__" => PyResult::Some(obj_iterator("list_iterator", IterState::Items(items, 0))),
+        "__str__" | "__repr__" => {
+            let items: Vec<String> = items
+                .into_iter()
+                .map(|x| obj_to_repr(x, namespace.clone(), env))
+                .collect::<Exec<_>>()?;
+            PyResult::Some(obj_str(format!("[{}]", items.join(", "))))
+        }
+        "__eq__" => match arg("other").get_value("x".to_string()) {
+            Ok(PyObjAttr::Rust(DataType::List(x))) => {
+                let other = x.borrow().clone();
+                PyResult::Some(obj_bool(items_eq(items, other, namespace, env)?))
+            }
+            _ => PyResult::None,
+        },
+        "__lt__" | "__le__" | "__gt__" | "__ge__" => match arg("other").get_value("x".to_string()) {
+            Ok(PyObjAttr::Rust(DataType::List(x))) => {
+                let other = x.borrow().clone();
+                PyResult::Some(obj_bool(items_order(&method, items, other, namespace, env)?))
+            }
+            _ => PyResult::None,
+        },
+        "__add__" => match arg("other").get_value("x".to_string()) {
+            Ok(PyObjAttr::Rust(DataType::List(x))) => {
+                let mut items = items;
+                items.extend(x.borrow().clone());
+                PyResult::Some(obj_list(items))
+            }
+            _ => PyResult::None,
+        },
+        // l += x extends the list in place, so every reference sees the new items
+        "__iadd__" => {
+            let other = arg("other");
+            let values = match obj_iterate(&other, namespace, env)? {
+                Some(x) => x,
+                None => return raise("TypeError", format!("'{}' object is not iterable", other.return_identity())),
+            };
+            list.borrow_mut().extend(values);
+            PyResult::Some(arg("self"))
+        }
+        "append" => {
+            list.borrow_mut().push(arg("object"));
+            PyResult::Some(obj_none())
+        }
+        "__getitem__" => PyResult::Some(match seq_index(&arg("key"), items.len(), "list")? {
+            SeqIndex::Item(Some(x)) => items[x].clone(),
+            SeqIndex::Item(None) => return raise("IndexError", "list index out of range"),
+            SeqIndex::Slice(start, stop, step) => obj_list(
+                slice_positions(start, stop, step)
+                    .into_iter()
+                    .map(|x| items[x].clone())
+                    .collect(),
+            ),
+        }),
+        "__setitem__" => {
+            let value = arg("value");
+            match seq_index(&arg("key"), items.len(), "list")? {
+                SeqIndex::Item(Some(x)) => list.borrow_mut()[x] = value,
+                SeqIndex::Item(None) => return raise("IndexError", "list assignment index out of range"),
+                SeqIndex::Slice(start, stop, step) => {
+                    // the new items are taken out first, so x[:] = x works
+                    let values = match obj_iterate(&value, namespace, env)? {
+                        Some(x) => x,
+                        None => return raise("TypeError", "can only assign an iterable"),
+                    };
+                    if step == 1 {
+                        list.borrow_mut().splice(start as usize..stop.max(start) as usize, values);
+                    } else {
+                        let positions = slice_positions(start, stop, step);
+                        if positions.len() != values.len() {
+                            return raise(
+                                "ValueError",
+                                format!(
+                                    "attempt to assign sequence of size {} to extended slice of size {}",
+                                    values.len(),
+                                    positions.len()
+                                ),
+                            );
+                        }
+                        let mut items = list.borrow_mut();
+                        for (position, value) in positions.into_iter().zip(values) {
+                            items[position] = value;
+                        }
+                    }
+                }
+            }
+            PyResult::Some(obj_none())
+        }
+        "__delitem__" => {
+            let mut positions = match seq_index(&arg("key"), items.len(), "list")? {
+                SeqIndex::Item(Some(x)) => vec![x],
+                SeqIndex::Item(None) => return raise("IndexError", "list assignment index out of range"),
+                SeqIndex::Slice(start, stop, step) => slice_positions(start, stop, step),
+            };
+            // remove from the back so the positions stay valid
+            positions.sort_unstable();
+            let mut items = list.borrow_mut();
+            for position in positions.into_iter().rev() {
+                items.remove(position);
+            }
+            PyResult::Some(obj_none())
+        }
+        _ => PyResult::None,
+    })
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::attribute::{object_slot, OBJECT_SLOTS};
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::class::{class_of, type_of};
+use crate::ast::data_type::core_type::{build_rust_method, obj_parser};
+use crate::ast::data_type::exception::Exec;
+use crate::ast::data_type::none::obj_none;
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{obj_to_repr, HashMapAttr, PyObject, PyResult};
+use crate::ast::data_type::str::obj_str;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+
+/// ## fn obj_method
+/// 绑定方法，调用时实例作为第一个参数传给函数
+pub fn obj_method(instance: PyObject, function: PyObject) -> PyObject {
+    let name = "method".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__bool__"), vec![]),
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+    ];
+    build_method!(
+        name: name;
+        data:DataType::Method(Box::new(instance), Box::new(function));
+        method_vec:method_vec
+    )
+}
+
+/// ## fn obj_builtin_method
+/// 内置类型的绑定方法，如`"a b".split`，调用时转到对象的Rust方法
+pub fn obj_builtin_method(instance: PyObject, method: String) -> PyObject {
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method("method".to_string(), String::from("__bool__"), vec![]),
+        build_rust_method("method".to_string(), String::from("__str__"), vec![]),
+        build_rust_method("method".to_string(), String::from("__repr__"), vec![]),
+    ];
+    build_method!(
+        name: "builtin_function_or_method".to_string();
+        data:DataType::BuiltinMethod(Box::new(instance), method);
+        method_vec:method_vec
+    )
+}
+pub fn method_behaviour(method: String, args: HashMapAttr, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+    let data = obj_parser("self".to_string(), "x".to_string(), args).unwrap_or_else(|x| panic!("{}", x));
+    Ok(match (method.as_str(), data) {
+        ("__bool__", _) => PyResult::Some(obj_bool(true)),
+        ("__str__" | "__repr__", DataType::Method(instance, function)) => {
+            let name = match function.get_value("x".to_string()) {
+                Ok(PyObjAttr::Rust(DataType::Function(x))) => match x.owner {
+                    Some(owner) => format!("{}.{}", class_of(&owner).name, x.name),
+                    None => x.name,
+                },
+                _ => obj_to_repr(*function, namespace.clone(), env)?,
+            };
+            let instance = obj_to_repr(*instance, namespace, env)?;
+            PyResult::Some(obj_str(format!("<bound method {} of {}>", name, instance)))
+        }
+        ("__str__" | "__repr__", DataType::BuiltinMethod(instance, name)) => PyResult::Some(obj_str(format!(
+            "<built-in method {} of {} object>",
+            name,
+            class_of(&type_of(&instance)).name
+        ))),
+        _ => PyResult::None,
+    })
+}
+
+/// ## fn call_builtin_method
+/// 调用内置类型的绑定方法，object的方法对所有对象都有效
+pub fn call_builtin_method(
+    instance: &PyObject,
+    method: &str,
+    args: Vec<PyObject>,
+    keywords: Vec<(String, PyObject)>,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<PyObject> {
+    if OBJECT_SLOTS.contains(&method) && !instance.has_behavior(method) {
+        return object_slot(instance, method, args, keywords, namespace, env);
+    }
+    match instance.clone().call_method(method.to_string(), args, keywords, namespace, env)? {
+        PyResult::Some(x) => Ok(x),
+        PyResult::None => Ok(obj_none()),
+        PyResult::Err(x) => Err(x.into()),
+        _ => panic!("Error at running"),
+    }
+}
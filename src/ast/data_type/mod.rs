@@ -1,9 +1,30 @@
+pub mod attribute;
 pub mod bool;
+pub mod builtin;
+pub mod bytes;
+pub mod class;
+pub mod complex;
 pub mod core_type;
 pub mod data_type_calc;
+pub mod dict;
+pub mod exception;
+pub mod file;
 pub mod float;
+pub mod format;
 pub mod function;
+pub mod generator;
+pub mod hash;
 pub mod int;
+pub mod iterator;
 pub mod list;
+pub mod method;
+pub mod module;
+pub mod none;
 pub mod object;
+pub mod range;
+pub mod set;
+pub mod signature;
+pub mod slice;
 pub mod str;
+pub mod subscript;
+pub mod tuple;
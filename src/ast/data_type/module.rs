@@ -0,0 +1,369 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::ast::ast_struct::{exec_commands, parse_source, DataType};
+use crate::ast::data_type::builtin::obj_builtin_function;
+use crate::ast::data_type::core_type::{build_rust_method, obj_parser};
+use crate::ast::data_type::dict::{dict_get_str, dict_remove_str, dict_set_str, obj_dict};
+use crate::ast::data_type::exception::{raise, Exec};
+use crate::ast::data_type::int::obj_int;
+use crate::ast::data_type::iterator::obj_iterate;
+use crate::ast::data_type::list::obj_list;
+use crate::ast::data_type::none::obj_none;
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{obj_to_repr, HashMapAttr, PyObject, PyResult, Shared};
+use crate::ast::data_type::str::obj_str;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+use crate::tools::tokenize::TOKEN_TYPES;
+
+/// ## struct PyModule
+/// 模块对象，模块的属性就是它的全局变量，保存在PyNamespace中以name为键的全局命名空间里
+/// - initializing：模块的代码还没有执行完，循环导入时得到的是这样的模块
+#[derive(Debug)]
+pub struct PyModule {
+    pub name: String,
+    pub initializing: bool,
+}
+
+pub fn obj_module(name: &str) -> PyObject {
+    let module = PyModule {
+        name: name.to_string(),
+        initializing: false,
+    };
+    let name = "module".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+    ];
+    build_method!(
+        name: name;
+        data:DataType::Module(Shared::new(module));
+        method_vec:method_vec
+    )
+}
+pub fn module_behaviour(method: String, args: HashMapAttr, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+    let module = match obj_parser("self".to_string(), "x".to_string(), args) {
+        Ok(DataType::Module(x)) => x.borrow().name.clone(),
+        _ => panic!("Error to get module"),
+    };
+    Ok(match method.as_str() {
+        "__str__" | "__repr__" => {
+            let file = env.get_module_variable(&module, "__file__").and_then(|x| str_value(&x));
+            let origin = match (file, env.get_module_variable(&module, "__path__")) {
+                (Some(x), _) => format!("from '{}'", x),
+                (None, Some(x)) => format!("(namespace) from {}", obj_to_repr(x, namespace, env)?),
+                (None, None) => "(built-in)".to_string(),
+            };
+            PyResult::Some(obj_str(format!("<module '{}' {}>", module, origin)))
+        }
+        _ => PyResult::None,
+    })
+}
+
+/// str对象的内容，不是str时返回None
+pub fn str_value(obj: &PyObject) -> Option<String> {
+    match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Str(x))) => Some(x),
+        _ => None,
+    }
+}
+
+/// 模块对象的名字，不是模块时返回None
+pub fn module_name(obj: &PyObject) -> Option<String> {
+    match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Module(x))) => Some(x.borrow().name.clone()),
+        _ => None,
+    }
+}
+
+/// 模块还没有执行完时为true
+pub fn module_initializing(obj: &PyObject) -> bool {
+    match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Module(x))) => x.borrow().initializing,
+        _ => false,
+    }
+}
+
+fn set_initializing(obj: &PyObject, initializing: bool) {
+    if let Ok(PyObjAttr::Rust(DataType::Module(x))) = obj.get_value("x".to_string()) {
+        x.borrow_mut().initializing = initializing;
+    }
+}
+
+/// ## fn init_modules
+/// 创建sys和`__main__`模块
+/// sys.path的第一项是当前目录，运行脚本时是脚本所在的目录
+pub fn init_modules(env: &mut PyNamespace) {
+    let sys = obj_module("sys");
+    let main = obj_module("__main__");
+    let path = vec![obj_str(String::new())];
+    let modules = obj_dict(vec![
+        (obj_str("sys".to_string()), sys),
+        (obj_str("__main__".to_string()), main),
+    ]);
+    for (id, value) in [
+        ("__name__", obj_str("sys".to_string())),
+        ("modules", modules),
+        ("path", obj_list(path)),
+        ("getrecursionlimit", obj_builtin_function("sys.getrecursionlimit".to_string())),
+        ("setrecursionlimit", obj_builtin_function("sys.setrecursionlimit".to_string())),
+    ] {
+        env.set_module_variable("sys", id.to_string(), value);
+    }
+    env.set_module_variable("__main__", "__name__".to_string(), obj_str("__main__".to_string()));
+}
+
+/// ## fn builtin_functions
+/// 用Rust实现的模块中的函数，这些模块在第一次导入时创建，不在sys.path中查找
+fn builtin_functions(name: &str) -> Option<Vec<&'static str>> {
+    match name {
+        "tokenize" => Some(vec!["generate_tokens"]),
+        _ => None,
+    }
+}
+
+/// 用Rust实现的模块中的常量，如tokenize.NAME和tokenize.tok_name
+fn builtin_constants(name: &str) -> Vec<(&'static str, PyObject)> {
+    match name {
+        "tokenize" => {
+            let names = TOKEN_TYPES.iter().map(|(name, x)| (obj_int(*x), obj_str(name.to_string()))).collect();
+            let mut constants: Vec<(&'static str, PyObject)> =
+                TOKEN_TYPES.iter().map(|(name, x)| (*name, obj_int(*x))).collect();
+            constants.push(("tok_name", obj_dict(names)));
+            constants
+        }
+        _ => vec![],
+    }
+}
+
+/// sys.modules，被替换成其他对象时抛出RuntimeError
+fn sys_modules(env: &mut PyNamespace) -> Exec<Shared<Vec<(PyObject, PyObject)>>> {
+    match env.get_module_variable("sys", "modules").map(|x| x.get_value("x".to_string())) {
+        Some(Ok(PyObjAttr::Rust(DataType::Dict(x)))) => Ok(x),
+        _ => raise("RuntimeError", "lost sys.modules"),
+    }
+}
+
+/// ## fn search_path
+/// sys.path中的目录，不是字符串的项被忽略
+pub fn search_path(env: &mut PyNamespace) -> Exec<Shared<Vec<PyObject>>> {
+    match env.get_module_variable("sys", "path").map(|x| x.get_value("x".to_string())) {
+        Some(Ok(PyObjAttr::Rust(DataType::List(x)))) => Ok(x),
+        _ => raise("RuntimeError", "lost sys.path"),
+    }
+}
+
+/// 找到的模块：源文件和包的目录，命名空间包没有源文件
+struct ModuleSpec {
+    file: Option<PathBuf>,
+    path: Option<PathBuf>,
+}
+
+/// ## fn find_module
+/// 在目录中依次查找包`name/__init__.py`和模块`name.py`，都没有时使用第一个同名目录作为命名空间包
+fn find_module(name: &str, dirs: Vec<String>) -> Option<ModuleSpec> {
+    let mut namespace = None;
+    for dir in dirs {
+        let dir = match dir.as_str() {
+            "" => Path::new(".").to_path_buf(),
+            _ => PathBuf::from(dir),
+        };
+        let package = dir.join(name);
+        if package.join("__init__.py").is_file() {
+            return Some(ModuleSpec {
+                file: Some(package.join("__init__.py")),
+                path: Some(package),
+            });
+        }
+        let module = dir.join(format!("{}.py", name));
+        if module.is_file() {
+            return Some(ModuleSpec {
+                file: Some(module),
+                path: None,
+            });
+        }
+        if package.is_dir() && namespace.is_none() {
+            namespace = Some(package);
+        }
+    }
+    namespace.map(|x| ModuleSpec {
+        file: None,
+        path: Some(x),
+    })
+}
+
+/// ## fn import_module
+/// 导入完整的模块名，如a.b.c会依次导入a、a.b和a.b.c，返回sys.modules中的模块
+pub fn import_module(name: &str, env: &mut PyNamespace) -> Exec<PyObject> {
+    match load_module(name, env)? {
+        Some(x) => Ok(x),
+        None => raise("ModuleNotFoundError", format!("No module named '{}'", name)),
+    }
+}
+
+/// 导入模块，找不到时返回None
+pub fn load_module(name: &str, env: &mut PyNamespace) -> Exec<Option<PyObject>> {
+    let modules = sys_modules(env)?;
+    if let Some(x) = dict_get_str(&modules, name) {
+        return Ok(Some(x));
+    }
+    if let Some(functions) = builtin_functions(name) {
+        let module = obj_module(name);
+        env.set_module_variable(name, "__name__".to_string(), obj_str(name.to_string()));
+        for function in functions {
+            let value = obj_builtin_function(format!("{}.{}", name, function));
+            env.set_module_variable(name, function.to_string(), value);
+        }
+        for (id, value) in builtin_constants(name) {
+            env.set_module_variable(name, id.to_string(), value);
+        }
+        dict_set_str(&modules, name, module.clone());
+        return Ok(Some(module));
+    }
+    let (parent, child) = match name.rsplit_once('.') {
+        Some((parent, child)) => (Some(parent), child),
+        None => (None, name),
+    };
+    // submodules are searched in the __path__ of their package
+    let dirs = match parent {
+        Some(parent) => {
+            import_module(parent, env)?;
+            match env.get_module_variable(parent, "__path__") {
+                Some(x) => list_strings(&x),
+                None => {
+                    return raise(
+                        "ModuleNotFoundError",
+                        format!("No module named '{}'; '{}' is not a package", name, parent),
+                    )
+                }
+            }
+        }
+        None => list_strings(&obj_list(search_path(env)?.borrow().clone())),
+    };
+    let spec = match find_module(child, dirs) {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let module = obj_module(name);
+    env.remove_module(name);
+    let package = match (&spec.path, parent) {
+        (Some(_), _) => name.to_string(),
+        (None, Some(parent)) => parent.to_string(),
+        (None, None) => String::new(),
+    };
+    let file = spec.file.as_ref().map(|x| x.to_string_lossy().to_string());
+    env.set_module_variable(name, "__name__".to_string(), obj_str(name.to_string()));
+    env.set_module_variable(name, "__file__".to_string(), file.clone().map_or_else(obj_none, obj_str));
+    env.set_module_variable(name, "__package__".to_string(), obj_str(package));
+    if let Some(x) = &spec.path {
+        let path = obj_list(vec![obj_str(x.to_string_lossy().to_string())]);
+        env.set_module_variable(name, "__path__".to_string(), path);
+    }
+    // the module is in sys.modules before its code runs, so a circular import gets the partial module
+    dict_set_str(&modules, name, module.clone());
+    if let Some(file) = file {
+        let source = match std::fs::read_to_string(&file) {
+            Ok(x) => x,
+            Err(x) => return raise("ImportError", format!("{}: '{}'", x, file)),
+        };
+        set_initializing(&module, true);
+        let previous = std::mem::replace(&mut env.module, name.to_string());
+        let result = parse_source(source, file.as_str()).and_then(|x| exec_commands(&x, env, Namespace::Global));
+        env.module = previous;
+        set_initializing(&module, false);
+        if let Err(x) = result {
+            dict_remove_str(&modules, name);
+            return Err(x);
+        }
+    }
+    if let Some(parent) = parent {
+        env.set_module_variable(parent, child.to_string(), module.clone());
+    }
+    Ok(Some(dict_get_str(&modules, name).unwrap_or(module)))
+}
+
+fn list_strings(obj: &PyObject) -> Vec<String> {
+    match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::List(x))) => x.borrow().iter().filter_map(str_value).collect(),
+        _ => vec![],
+    }
+}
+
+/// ## fn resolve_name
+/// 相对导入的模块名，如在包a.b中`from ..c import x`导入的是a.c
+pub fn resolve_name(module: Option<&str>, level: usize, env: &mut PyNamespace) -> Exec<String> {
+    if level == 0 {
+        return Ok(module.unwrap_or_default().to_string());
+    }
+    let package = match env.get_global("__package__".to_string()).ok().and_then(|x| str_value(&x)) {
+        Some(x) if !x.is_empty() => x,
+        _ => return raise("ImportError", "attempted relative import with no known parent package"),
+    };
+    let bits: Vec<&str> = package.rsplitn(level, '.').collect();
+    if bits.len() < level {
+        return raise("ImportError", "attempted relative import beyond top-level package");
+    }
+    Ok(match module {
+        Some(x) => format!("{}.{}", bits[level - 1], x),
+        None => bits[level - 1].to_string(),
+    })
+}
+
+/// ## fn import_from
+/// `from module import name`中的name：先找模块的属性，包中没有这个属性时导入同名的子模块
+pub fn import_from(module: &PyObject, name: &str, env: &mut PyNamespace) -> Exec<PyObject> {
+    let module_name = module_name(module).unwrap_or_default();
+    if let Some(x) = env.get_module_variable(&module_name, name) {
+        return Ok(x);
+    }
+    if env.get_module_variable(&module_name, "__path__").is_some() {
+        if let Some(x) = load_module(format!("{}.{}", module_name, name).as_str(), env)? {
+            return Ok(x);
+        }
+    }
+    let file = env.get_module_variable(&module_name, "__file__").and_then(|x| str_value(&x));
+    let location = file.unwrap_or_else(|| "unknown location".to_string());
+    if module_initializing(module) {
+        return raise(
+            "ImportError",
+            format!(
+                "cannot import name '{}' from partially initialized module '{}' (most likely due to a circular import) ({})",
+                name, module_name, location
+            ),
+        );
+    }
+    raise("ImportError", format!("cannot import name '{}' from '{}' ({})", name, module_name, location))
+}
+
+/// ## fn public_names
+/// `from module import *`导入的名字：有`__all__`时是其中的名字，否则是所有不以下划线开头的全局变量
+pub fn public_names(module: &PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<Vec<String>> {
+    let module_name = module_name(module).unwrap_or_default();
+    let all = match env.get_module_variable(&module_name, "__all__") {
+        Some(x) => x,
+        None => {
+            return Ok(env
+                .module_variables(&module_name)
+                .into_iter()
+                .map(|(x, _)| x)
+                .filter(|x| !x.starts_with('_'))
+                .collect())
+        }
+    };
+    let items = match obj_iterate(&all, namespace, env)? {
+        Some(x) => x,
+        None => return raise("TypeError", format!("'{}' object is not iterable", all.return_identity())),
+    };
+    items
+        .iter()
+        .map(|x| match str_value(x) {
+            Some(x) => Ok(x),
+            None => raise(
+                "TypeError",
+                format!("Item in {}.__all__ must be str, not {}", module_name, x.return_identity()),
+            ),
+        })
+        .collect()
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::core_type::build_rust_method;
+use crate::ast::data_type::exception::Exec;
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{HashMapAttr, PyObject, PyResult};
+use crate::ast::data_type::str::obj_str;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+
+pub fn obj_none() -> PyObject {
+    let name = "NoneType".to_string();
+    let param = vec!["self".to_string(), "other".to_string()];
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__bool__"), vec![]),
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+        build_rust_method(name.clone(), String::from("__eq__"), param.clone()),
+        build_rust_method(name.clone(), String::from("__ne__"), param),
+    ];
+    build_method!(
+        name: name;
+        data:DataType::None;
+        method_vec:method_vec
+    )
+}
+pub fn none_behaviour(method: String, args: HashMapAttr, _namespace: Namespace, _env: &mut PyNamespace) -> Exec<PyResult> {
+    // None只和None相等
+    let is_none = |args: &HashMapAttr| match args.get("other") {
+        Some(PyObjAttr::Interpreter(x)) => x.return_identity() == "NoneType",
+        _ => false,
+    };
+    Ok(match method.as_str() {
+        "__bool__" => PyResult::Some(obj_bool(false)),
+        "__str__" | "__repr__" => PyResult::Some(obj_str("None".to_string())),
+        "__eq__" => PyResult::Some(obj_bool(is_none(&args))),
+        "__ne__" => PyResult::Some(obj_bool(!is_none(&args))),
+        _ => PyResult::None,
+    })
+}
+
+/// ## fn obj_not_implemented
+/// NotImplemented：运算方法返回它表示不支持另一个操作数，此时改用反射方法
+pub fn obj_not_implemented() -> PyObject {
+    let name = "NotImplementedType".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__bool__"), vec![]),
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+    ];
+    build_method!(
+        name: name;
+        data:DataType::None;
+        method_vec:method_vec
+    )
+}
+pub fn not_implemented_behaviour(method: String, _args: HashMapAttr, _namespace: Namespace, _env: &mut PyNamespace) -> Exec<PyResult> {
+    Ok(match method.as_str() {
+        "__bool__" => PyResult::Some(obj_bool(true)),
+        "__str__" | "__repr__" => PyResult::Some(obj_str("NotImplemented".to_string())),
+        _ => PyResult::None,
+    })
+}
+pub fn is_not_implemented(obj: &PyObject) -> bool {
+    obj.return_identity() == "NotImplementedType"
+}
@@ -1,13 +1,34 @@
 use crate::ast::ast_struct::DataType;
 use crate::ast::data_type::bool::{bool_behaviour, obj_bool};
+use crate::ast::data_type::builtin::{builtin_function_behaviour, super_behaviour};
+use crate::ast::data_type::bytes::bytes_behaviour;
+use crate::ast::data_type::class::{class_behaviour, object_behaviour, PyClass};
+use crate::ast::data_type::complex::complex_behaviour;
+use crate::ast::data_type::dict::dict_behaviour;
+use crate::ast::data_type::exception::{exception_behaviour, raise, Exec};
+use crate::ast::data_type::file::file_behaviour;
 use crate::ast::data_type::float::float_behaviour;
+use crate::ast::data_type::function::{call_object, function_behaviour};
 use crate::ast::data_type::int::int_behaviour;
+use crate::ast::data_type::iterator::iterator_behaviour;
+use crate::ast::data_type::list::list_behaviour;
+use crate::ast::data_type::method::method_behaviour;
+use crate::ast::data_type::module::module_behaviour;
+use crate::ast::data_type::generator::generator_behaviour;
+use crate::ast::data_type::none::{none_behaviour, not_implemented_behaviour};
+use crate::ast::data_type::range::range_behaviour;
+use crate::ast::data_type::set::set_behaviour;
+use crate::ast::data_type::slice::slice_behaviour;
+use crate::ast::data_type::signature::Signature;
 use crate::ast::data_type::str::str_behaviour;
+use crate::ast::data_type::tuple::tuple_behaviour;
 use crate::ast::error::object_error::{ObjBasicError, ObjMethodCallError};
 use crate::ast::error::ErrorType;
+use std::cell::{Ref, RefCell, RefMut};
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
+use std::rc::Rc;
 use crate::ast::namespace::{Namespace, PyNamespace};
 
 /// ## struct RustObjBehavior
@@ -17,17 +38,38 @@ use crate::ast::namespace::{Namespace, PyNamespace};
 pub struct RustObjBehavior {
     pub name: String,
     pub method: String,
-    pub args: Vec<String>,
+    pub args: Signature,
 }
 impl RustObjBehavior {
     /// 调用相关代码
-    fn exec(&self, x: HashMap<String, PyObjAttr>,namespace: Namespace,env:&mut PyNamespace) -> PyResult {
+    fn exec(&self, x: HashMap<String, PyObjAttr>,namespace: Namespace,env:&mut PyNamespace) -> Exec<PyResult> {
         match self.name.as_str() {
             "int" => int_behaviour(self.method.clone(), x,namespace,env),
             "float" => float_behaviour(self.method.clone(), x,namespace,env),
+            "complex" => complex_behaviour(self.method.clone(), x,namespace,env),
             "bool" => bool_behaviour(self.method.clone(), x,namespace,env),
             "str" => str_behaviour(self.method.clone(), x,namespace,env),
-            _ => todo!(),
+            "bytes" => bytes_behaviour(self.method.clone(), x,namespace,env),
+            "function" => function_behaviour(self.method.clone(), x,namespace,env),
+            "NoneType" => none_behaviour(self.method.clone(), x,namespace,env),
+            "NotImplementedType" => not_implemented_behaviour(self.method.clone(), x,namespace,env),
+            "tuple" => tuple_behaviour(self.method.clone(), x,namespace,env),
+            "dict" => dict_behaviour(self.method.clone(), x,namespace,env),
+            "type" => class_behaviour(self.method.clone(), x,namespace,env),
+            "object" => object_behaviour(self.method.clone(), x,namespace,env),
+            "builtin_function_or_method" => builtin_function_behaviour(self.method.clone(), x,namespace,env),
+            "super" => super_behaviour(self.method.clone(), x,namespace,env),
+            "iterator" => iterator_behaviour(self.method.clone(), x,namespace,env),
+            "range" => range_behaviour(self.method.clone(), x,namespace,env),
+            "list" => list_behaviour(self.method.clone(), x,namespace,env),
+            "method" => method_behaviour(self.method.clone(), x,namespace,env),
+            "slice" => slice_behaviour(self.method.clone(), x,namespace,env),
+            "set" => set_behaviour(self.method.clone(), x,namespace,env),
+            "BaseException" => exception_behaviour(self.method.clone(), x,namespace,env),
+            "TextIOWrapper" => file_behaviour(self.method.clone(), x,namespace,env),
+            "module" => module_behaviour(self.method.clone(), x,namespace,env),
+            "generator" => generator_behaviour(self.method.clone(), x,namespace,env),
+            x => panic!("No rust behaviour for type {}", x),
         }
     }
 }
@@ -36,15 +78,51 @@ impl RustObjBehavior {
 /// **注：解释器还未完工，此枚举属于临时解决办法**
 /// - None:没有实现方法
 /// - Rust：调用rust的函数
-/// - Interpreter：todo!()
+/// - Interpreter：调用python中定义的函数，第一个参数是self
 #[allow(dead_code)]
 #[derive(Clone, Debug, PartialEq)]
-pub(crate) enum PyObjBehaviors {
-    Interpreter,
+pub enum PyObjBehaviors {
+    Interpreter(Box<PyObject>),
     Rust(Box<RustObjBehavior>),
     None,
 }
 
+/// ## struct Shared
+/// 可变对象的内容，clone之后仍然指向同一份数据
+/// 两个Shared只有指向同一份数据时才相等
+pub struct Shared<T>(Rc<RefCell<T>>);
+impl<T> Shared<T> {
+    pub fn new(x: T) -> Self {
+        Shared(Rc::new(RefCell::new(x)))
+    }
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.0.borrow()
+    }
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
+        self.0.borrow_mut()
+    }
+    /// 数据的地址，用于id()和默认的repr
+    pub fn address(&self) -> usize {
+        Rc::as_ptr(&self.0) as *const u8 as usize
+    }
+}
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Shared(self.0.clone())
+    }
+}
+impl<T> PartialEq for Shared<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+/// 可变对象可能引用自身，所以只输出地址
+impl<T> Debug for Shared<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<shared at {:#x}>", self.address())
+    }
+}
+
 /// ## enum PyObjAttr
 /// 此枚举主要用来确定值的类型为Rust的DataType枚举还是解释器的对象
 /// **注：解释器还未完工，此枚举属于临时解决办法**
@@ -73,7 +151,7 @@ impl Default for PyObject {
             (String::from("__init__"), PyObjBehaviors::None),
             (String::from("__add__"), PyObjBehaviors::None),
             (String::from("__sub__"), PyObjBehaviors::None),
-            (String::from("__div__"), PyObjBehaviors::None),
+            (String::from("__truediv__"), PyObjBehaviors::None),
             (String::from("__lt__"), PyObjBehaviors::None),
             (String::from("__eq__"), PyObjBehaviors::None),
             (String::from("__gt__"), PyObjBehaviors::None),
@@ -116,7 +194,7 @@ impl PyObject {
     where
         T: IntoIterator<Item = (String, PyObjBehaviors)>,
     {
-        self.behaviors.extend(x.into_iter());
+        self.behaviors.extend(x);
         self.clone()
     }
     pub fn set_behavior(&mut self, name: String, obj_behaviors: PyObjBehaviors) -> Self {
@@ -166,27 +244,72 @@ impl PyObject {
             .obj(ObjBasicError::default().identity(self.identity.clone()))
             .method(method)
     }
+    /// 对象的类，只有python中定义的类的实例才有
+    pub(crate) fn class(&self) -> Option<PyClass> {
+        match self.attr.get("__class__") {
+            Some(PyObjAttr::Interpreter(x)) => match x.get_value("x".to_string()) {
+                Ok(PyObjAttr::Rust(DataType::Class(x))) => Some(*x),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+    /// 按类的mro查找方法，遇到内置类型时使用对象自身的behaviors
+    fn behavior(&self, name: &str) -> Option<PyObjBehaviors> {
+        for class in self.class().map_or(vec![], |x| x.mro_classes()) {
+            if class.is_builtin() {
+                match self.behaviors.get(name) {
+                    Some(PyObjBehaviors::None) | None => continue,
+                    Some(x) => return Some(x.clone()),
+                }
+            }
+            if let Some(x) = class.get(name) {
+                if let Ok(PyObjAttr::Rust(DataType::Function(_))) = x.get_value("x".to_string()) {
+                    return Some(PyObjBehaviors::Interpreter(Box::new(x)));
+                }
+            }
+        }
+        self.behaviors.get(name).cloned()
+    }
+    /// 对象自身是否有这个Rust方法
+    pub(crate) fn has_behavior(&self, name: &str) -> bool {
+        !matches!(self.behaviors.get(name), Some(PyObjBehaviors::None) | None)
+    }
+    /// 按mro查找，对象是否有这个方法，包括python中定义的方法
+    pub(crate) fn has_method(&self, name: &str) -> bool {
+        !matches!(self.behavior(name), Some(PyObjBehaviors::None) | None)
+    }
+    /// 用python函数实现的方法：self作为第一个位置参数，其余参数按名字传入
+    fn call_interpreter(&self, function: &PyObject, args: HashMapAttr, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyObject> {
+        let keywords = args
+            .into_iter()
+            .filter(|(key, _)| key != "self")
+            .map(|(key, value)| match value {
+                PyObjAttr::Interpreter(x) => (key, *x),
+                x => (key, PyObject::default().attr([(String::from("x"), x)])),
+            })
+            .collect();
+        call_object(function, vec![self.clone()], keywords, namespace, env)
+    }
     fn inner_call(
         &self,
         behavior: String,
         other: HashMapAttr,
         namespace: Namespace,env:&mut PyNamespace
-    ) -> Result<PyResult, ObjMethodCallError> {
-        match self.behaviors.get(&behavior) {
-            None => Err(self.create_obj_call_error(behavior)),
+    ) -> Exec<PyResult> {
+        match self.behavior(&behavior) {
+            None => Ok(PyResult::Err(ErrorType::ObjMethodCallError(self.create_obj_call_error(behavior)))),
             Some(x) => {
                 let attr_vec: Vec<(String, PyObjAttr)> = vec![(
                     String::from("self"),
-                    PyObjAttr::Interpreter(Box::from(PyObject::default().attr(self.attr.clone()))),
+                    PyObjAttr::Interpreter(Box::from(self.clone())),
                 )];
                 let mut attr: HashMap<String, PyObjAttr> = attr_vec.into_iter().collect();
                 attr.extend(other);
-                match x.clone() {
-                    PyObjBehaviors::Interpreter => {
-                        todo!()
-                    }
-                    PyObjBehaviors::Rust(x) => Ok(x.exec(attr,namespace,env)),
-                    PyObjBehaviors::None => Err(self.create_obj_call_error(behavior)),
+                match x {
+                    PyObjBehaviors::Interpreter(x) => Ok(PyResult::Some(self.call_interpreter(&x, attr, namespace, env)?)),
+                    PyObjBehaviors::Rust(x) => x.exec(attr,namespace,env),
+                    PyObjBehaviors::None => Ok(PyResult::Err(ErrorType::ObjMethodCallError(self.create_obj_call_error(behavior)))),
                 }
             }
         }
@@ -204,132 +327,169 @@ impl PyObject {
         &self,
         method: String,
         value: Vec<PyObjAttr>,
-    ) -> HashMap<String, PyObjAttr> {
-        let mut key: Vec<String> = vec![];
-        match self.behaviors.get(&method.clone()) {
-            None => {}
-            Some(x) => match x.clone() {
-                PyObjBehaviors::Interpreter => {
-                    todo!()
-                }
-                PyObjBehaviors::Rust(x) => {
-                    key = x.args.clone();
-                }
+    ) -> Exec<HashMap<String, PyObjAttr>> {
+        let signature = match self.behavior(&method) {
+            None => Signature::default(),
+            Some(x) => match x {
+                PyObjBehaviors::Interpreter(x) => match x.get_value("x".to_string()) {
+                    Ok(PyObjAttr::Rust(DataType::Function(x))) => x.signature.method(),
+                    _ => Signature::default(),
+                },
+                PyObjBehaviors::Rust(x) => x.args,
                 PyObjBehaviors::None => {
                     panic!("Not a method")
                 }
             },
+        };
+        let value: Vec<PyObject> = value
+            .into_iter()
+            .map(|x| match x {
+                PyObjAttr::Interpreter(x) => *x,
+                PyObjAttr::Rust(x) => PyObject::default().attr([(String::from("x"), PyObjAttr::Rust(x))]),
+                PyObjAttr::None => panic!("Error to convert"),
+            })
+            .collect();
+        match signature.bind(method.as_str(), value, vec![]) {
+            Ok(x) => Ok(x
+                .into_iter()
+                .map(|(key, value)| (key, PyObjAttr::Interpreter(Box::new(value))))
+                .collect()),
+            Err(x) => raise("TypeError", x),
         }
-        if key.len() - 1 == value.len() {
-            let mut vec: Vec<(String, PyObjAttr)> = vec![];
-            for (index, item) in key.into_iter().enumerate() {
-                if index == 0 {
-                    continue;
-                }
-                vec.push((item, value[index - 1].clone()));
-            }
-            let hashmap: HashMap<String, PyObjAttr> = vec.into_iter().collect();
-            return hashmap;
-        }
-        panic!("Error to convert")
     }
-    pub fn call(&mut self, behavior: String, other: HashMap<String, PyObjAttr>, namespace: Namespace, env: &mut PyNamespace) -> PyResult {
-        match self.inner_call(behavior, other,namespace,env) {
-            Ok(x) => match x {
-                PyResult::None => PyResult::None,
-                PyResult::Some(x) => PyResult::Some(x),
-                PyResult::ChangeAttr(x, y) => {
-                    self.attr
-                        .extend(x.into_iter().map(|(k, v)| (k.clone(), v.clone())));
-                    PyObject::deref_py_result(*y)
-                }
-                PyResult::ChangeBehavior(x, y) => {
-                    self.behaviors
-                        .extend(x.into_iter().map(|(k, v)| (k.clone(), v.clone())));
-                    PyObject::deref_py_result(*y)
-                }
-                PyResult::Err(x) => PyResult::Err(x),
-            },
-            Err(x) => PyResult::Err(ErrorType::ObjMethodCallError(x)),
+    /// 按方法的形参列表绑定位置参数和关键字参数后调用，用于绑定方法
+    /// 调用的是对象自身的Rust方法，子类中同名的方法通过super()调用到这里
+    pub(crate) fn call_method(
+        &mut self,
+        method: String,
+        args: Vec<PyObject>,
+        keywords: Vec<(String, PyObject)>,
+        namespace: Namespace,
+        env: &mut PyNamespace,
+    ) -> Exec<PyResult> {
+        let behavior = match self.behaviors.get(&method) {
+            Some(PyObjBehaviors::Rust(x)) => x.clone(),
+            _ => return Ok(PyResult::Err(ErrorType::ObjMethodCallError(self.create_obj_call_error(method)))),
+        };
+        let bound = match behavior.args.bind(method.as_str(), args, keywords) {
+            Ok(x) => x,
+            Err(x) => return raise("TypeError", x),
+        };
+        let mut attr: HashMap<String, PyObjAttr> =
+            HashMap::from([(String::from("self"), PyObjAttr::Interpreter(Box::from(self.clone())))]);
+        attr.extend(
+            bound
+                .into_iter()
+                .map(|(key, value)| (key, PyObjAttr::Interpreter(Box::new(value)))),
+        );
+        let result = behavior.exec(attr, namespace, env)?;
+        Ok(self.apply_result(result))
+    }
+    pub fn call(&mut self, behavior: String, other: HashMap<String, PyObjAttr>, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+        let result = self.inner_call(behavior, other, namespace, env)?;
+        Ok(self.apply_result(result))
+    }
+    /// 方法修改了对象的属性或方法时更新对象
+    fn apply_result(&mut self, x: PyResult) -> PyResult {
+        match x {
+            PyResult::None => PyResult::None,
+            PyResult::Some(x) => PyResult::Some(x),
+            PyResult::ChangeAttr(x, y) => {
+                self.attr
+                    .extend(x.into_iter().map(|(k, v)| (k.clone(), v.clone())));
+                PyObject::deref_py_result(*y)
+            }
+            PyResult::ChangeBehavior(x, y) => {
+                self.behaviors
+                    .extend(x.into_iter().map(|(k, v)| (k.clone(), v.clone())));
+                PyObject::deref_py_result(*y)
+            }
+            PyResult::Err(x) => PyResult::Err(x),
         }
     }
-    fn return_identity(&self) -> String {
-        return self.identity.clone();
+    pub(crate) fn return_identity(&self) -> String {
+        self.identity.clone()
     }
-    fn init(&self, args: HashMap<String, PyObjAttr>,namespace: Namespace,env:&mut PyNamespace) {
-        match self.behaviors.get("__init__") {
+    fn init(&self, args: HashMap<String, PyObjAttr>,namespace: Namespace,env:&mut PyNamespace) -> Exec<()> {
+        match self.behavior("__init__") {
             None => {
                 panic!("Cannot Support Calc")
             }
-            Some(x) => match x.clone() {
-                PyObjBehaviors::Interpreter => {
-                    todo!()
+            Some(x) => match x {
+                PyObjBehaviors::Interpreter(x) => {
+                    self.call_interpreter(&x, args, namespace, env)?;
                 }
                 PyObjBehaviors::Rust(x) => {
-                    x.exec(args,namespace,env);
+                    x.exec(args,namespace,env)?;
                 }
                 PyObjBehaviors::None => {
                     panic!("Cannot Init {}", self.identity)
                 }
             },
         }
+        Ok(())
     }
-    pub fn py_call(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> PyResult{
+    pub fn py_call(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult>{
         self.call(String::from("__call__"), other,namespace,env)
     }
-    pub fn add(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
-        self.call(String::from("__add__"), other,namespace,env)
-    }
-    pub fn sub(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
-        self.call(String::from("__sub__"), other,namespace,env)
+    pub fn getitem(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+        self.call(String::from("__getitem__"), other,namespace,env)
     }
-    pub fn mul(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
-        self.call(String::from("__mult__"), other,namespace,env)
+    pub fn setitem(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+        self.call(String::from("__setitem__"), other,namespace,env)
     }
-    pub fn div(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
-        self.call(String::from("__div__"), other,namespace,env)
+    pub fn delitem(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+        self.call(String::from("__delitem__"), other,namespace,env)
     }
-    pub fn lt(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
+    pub fn lt(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
         self.call(String::from("__lt__"), other,namespace,env)
     }
-    pub fn gt(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
+    pub fn gt(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
         self.call(String::from("__gt__"), other,namespace,env)
     }
-    pub fn py_eq(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
+    pub fn py_eq(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
         self.call(String::from("__eq__"), other,namespace,env)
     }
-    pub fn py_ne(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
+    pub fn py_ne(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
         self.call(String::from("__ne__"), other,namespace,env)
     }
-    pub fn ge(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
+    pub fn ge(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
         self.call(String::from("__ge__"), other,namespace,env)
     }
-    pub fn le(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
+    pub fn le(&mut self, other: HashMap<String, PyObjAttr>,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
         self.call(String::from("__le__"), other,namespace,env)
     }
-    pub fn neg(&mut self,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
+    pub fn neg(&mut self,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
         let other: HashMap<String, PyObjAttr> = HashMap::new();
         self.call(String::from("__neg__"), other,namespace,env)
     }
-    pub fn not(&mut self,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
-        PyResult::Some(obj_bool(!obj_to_bool(self.clone(),namespace,env)))
+    pub fn not(&mut self,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+        Ok(PyResult::Some(obj_bool(!obj_to_bool(self.clone(),namespace,env)?)))
     }
-    pub fn pos(&mut self,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
+    pub fn pos(&mut self,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
         let other: HashMap<String, PyObjAttr> = HashMap::new();
         self.call(String::from("__pos__"), other,namespace,env)
     }
-    pub fn bool(&mut self,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
+    pub fn invert(&mut self,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+        let other: HashMap<String, PyObjAttr> = HashMap::new();
+        self.call(String::from("__invert__"), other,namespace,env)
+    }
+    pub fn bool(&mut self,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
         let other: HashMap<String, PyObjAttr> = HashMap::new();
         self.call(String::from("__bool__"), other,namespace,env)
     }
-    pub fn len(&mut self,namespace: Namespace, env: &mut PyNamespace) -> PyResult {
+    pub fn len(&mut self,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
         let other: HashMap<String, PyObjAttr> = HashMap::new();
         self.call(String::from("__len__"), other,namespace,env)
     }
-    pub fn str(&mut self,namespace: Namespace, env: &mut PyNamespace) -> PyResult{
+    pub fn str(&mut self,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult>{
         let other: HashMap<String, PyObjAttr> = HashMap::new();
         self.call(String::from("__str__"), other,namespace,env)
     }
+    pub fn repr(&mut self,namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult>{
+        let other: HashMap<String, PyObjAttr> = HashMap::new();
+        self.call(String::from("__repr__"), other,namespace,env)
+    }
 }
 
 impl Display for PyObject {
@@ -342,11 +502,11 @@ impl Debug for PyObject {
         write!(f, "{} {:?}", self.identity, self.attr)
     }
 }
-pub fn obj_to_bool(mut obj: PyObject,namespace: Namespace, env: &mut PyNamespace) -> bool {
+pub fn obj_to_bool(mut obj: PyObject,namespace: Namespace, env: &mut PyNamespace) -> Exec<bool> {
     if obj.identity == "bool" {
         match obj.get_value("x".to_string()) {
             Ok(x) => match x {
-                PyObjAttr::Rust(x) => return x.bool(),
+                PyObjAttr::Rust(x) => return Ok(x.bool()),
                 _ => {
                     panic!("Cannot identify boolean values")
                 }
@@ -356,29 +516,29 @@ pub fn obj_to_bool(mut obj: PyObject,namespace: Namespace, env: &mut PyNamespace
             }
         };
     };
-    match obj.bool(namespace.clone(),env) {
+    match obj.bool(namespace.clone(),env)? {
+        PyResult::Some(x) if x.identity != "bool" => {
+            return raise("TypeError", format!("__bool__ should return bool, returned {}", x.identity))
+        }
         PyResult::Some(x) => return obj_to_bool(x, namespace, env),
-        PyResult::Err(x) => match x {
-            ErrorType::ObjMethodCallError(_) => {
-                match obj.len(namespace.clone(),env) {
-                    PyResult::Some(y) => {
-                        return obj_to_bool(y,namespace,env);
-                    }
-                    _ => {}
-                };
+        PyResult::Err(ErrorType::ObjMethodCallError(_)) => match obj.len(namespace.clone(),env)? {
+            PyResult::Some(y) => {
+                return obj_to_bool(y,namespace,env);
             }
+            // objects without __bool__ and __len__ are true
+            PyResult::Err(ErrorType::ObjMethodCallError(_)) => return Ok(true),
             _ => {}
         },
         _ => {}
     }
-    panic!("Error to convert to bool:{}", obj.identity)
+    raise("TypeError", format!("'{}' object cannot be converted to bool", obj.identity))
 }
-pub fn obj_to_str(mut obj: PyObject,namespace: Namespace, env: &mut PyNamespace) -> String{
+pub fn obj_to_str(mut obj: PyObject,namespace: Namespace, env: &mut PyNamespace) -> Exec<String> {
     if obj.identity == "str" {
         match obj.get_value("x".to_string()) {
             Ok(x) => match x {
                 PyObjAttr::Rust(x) => match x {
-                    DataType::Str(x) => {return x}
+                    DataType::Str(x) => {return Ok(x)}
                     _ => panic!("Cannot identify str values")
                 },
                 _ => {
@@ -390,23 +550,45 @@ pub fn obj_to_str(mut obj: PyObject,namespace: Namespace, env: &mut PyNamespace)
             }
         };
     };
-    match obj.str(namespace.clone(),env) {
+    match obj.str(namespace.clone(),env)? {
         PyResult::Some(x) => {
-            return obj_to_str(x, namespace, env)
+            return str_result(x, "__str__")
         }
         PyResult::Err(_) => {
-            return format!("{:#?}",obj)
+            return Ok(format!("{:#?}",obj))
         }
         _ => {}
     }
     panic!("Error to convert to str:{}", obj.identity)
 }
-
-#[macro_export]
-macro_rules! define_obj_method {
-    (method $method:expr;identity $identity:expr;content $content:stmt) => {{
-        if $method == String::from($identity) {
-            $content
+/// `__str__`和`__repr__`必须返回str，否则抛出TypeError
+fn str_result(x: PyObject, method: &str) -> Exec<String> {
+    match x.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Str(s))) => Ok(s),
+        _ => raise("TypeError", format!("{} returned non-string (type {})", method, x.identity)),
+    }
+}
+/// 没有__repr__的对象使用__str__
+pub fn obj_to_repr(mut obj: PyObject,namespace: Namespace, env: &mut PyNamespace) -> Exec<String> {
+    match obj.repr(namespace.clone(),env)? {
+        PyResult::Some(x) => str_result(x, "__repr__"),
+        _ => obj_to_str(obj, namespace, env),
+    }
+}
+/// ascii()，在repr的基础上转义非ASCII字符
+pub fn obj_to_ascii(obj: PyObject,namespace: Namespace, env: &mut PyNamespace) -> Exec<String> {
+    let mut s = String::new();
+    for char in obj_to_repr(obj, namespace, env)?.chars() {
+        let code = char as u32;
+        if char.is_ascii() {
+            s.push(char)
+        } else if code <= 0xff {
+            s += format!("\\x{:02x}", code).as_str()
+        } else if code <= 0xffff {
+            s += format!("\\u{:04x}", code).as_str()
+        } else {
+            s += format!("\\U{:08x}", code).as_str()
         }
-    }};
+    }
+    Ok(s)
 }
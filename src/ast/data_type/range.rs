@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::core_type::{build_rust_method, obj_parser};
+use crate::ast::data_type::exception::{raise, Exec};
+use crate::ast::data_type::int::obj_int;
+use crate::ast::data_type::iterator::{obj_iterator, IterState};
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{HashMapAttr, PyObject, PyResult};
+use crate::ast::data_type::slice::{seq_index, SeqIndex};
+use crate::ast::data_type::str::obj_str;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+
+/// range(start, stop, step)，step不为0，由range()检查
+pub fn obj_range(start: i64, stop: i64, step: i64) -> PyObject {
+    let name = "range".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__iter__"), vec![]),
+        build_rust_method(name.clone(), String::from("__len__"), vec![]),
+        build_rust_method(name.clone(), String::from("__bool__"), vec![]),
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+        build_rust_method(name.clone(), String::from("__getitem__"), vec!["self".to_string(), "key".to_string()]),
+    ];
+    build_method!(
+        name: name;
+        data:DataType::Range(start, stop, step);
+        method_vec:method_vec
+    )
+}
+/// range中元素的个数
+fn range_len(start: i64, stop: i64, step: i64) -> i64 {
+    if step > 0 && start < stop {
+        (stop - start - 1) / step + 1
+    } else if step < 0 && start > stop {
+        (start - stop - 1) / -step + 1
+    } else {
+        0
+    }
+}
+pub fn range_behaviour(method: String, args: HashMapAttr, _namespace: Namespace, _env: &mut PyNamespace) -> Exec<PyResult> {
+    let (start, stop, step) = match obj_parser("self".to_string(), "x".to_string(), args.clone()) {
+        Ok(DataType::Range(x, y, z)) => (x, y, z),
+        _ => panic!("Error to get range"),
+    };
+    Ok(match method.as_str() {
+        "__iter__" => PyResult::Some(obj_iterator("range_iterator", IterState::Range(start, stop, step))),
+        "__len__" => PyResult::Some(obj_int(range_len(start, stop, step))),
+        "__bool__" => PyResult::Some(obj_bool(range_len(start, stop, step) != 0)),
+        "__getitem__" => {
+            let key = match args.get("key") {
+                Some(PyObjAttr::Interpreter(x)) => *x.clone(),
+                _ => panic!("Error to get range"),
+            };
+            PyResult::Some(match seq_index(&key, range_len(start, stop, step) as usize, "range")? {
+                SeqIndex::Item(Some(x)) => obj_int(start + x as i64 * step),
+                SeqIndex::Item(None) => return raise("IndexError", "range object index out of range"),
+                // 切片后仍然是range
+                SeqIndex::Slice(x, y, z) => obj_range(start + x * step, start + y * step, step * z),
+            })
+        }
+        "__str__" | "__repr__" if step == 1 => PyResult::Some(obj_str(format!("range({}, {})", start, stop))),
+        "__str__" | "__repr__" => PyResult::Some(obj_str(format!("range({}, {}, {})", start, stop, step))),
+        _ => PyResult::None,
+    })
+}
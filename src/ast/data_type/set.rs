@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::core_type::{build_rust_method, obj_parser};
+use crate::ast::data_type::exception::Exec;
+use crate::ast::data_type::hash::key_index;
+use crate::ast::data_type::int::obj_int;
+use crate::ast::data_type::iterator::{obj_iterator, IterState};
+use crate::ast::data_type::none::obj_none;
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{obj_to_repr, HashMapAttr, PyObject, PyResult, Shared};
+use crate::ast::data_type::str::obj_str;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+
+/// ## fn build_set
+/// 集合的元素按插入顺序保存，相等的元素只保留第一个，元素不能哈希时抛出TypeError
+pub fn build_set(x: Vec<PyObject>, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyObject> {
+    let items = Shared::new(vec![]);
+    for item in x {
+        set_add(&items, item, namespace.clone(), env)?;
+    }
+    Ok(obj_set(items))
+}
+/// 与已经去重的元素共享数据
+pub fn obj_set(items: Shared<Vec<PyObject>>) -> PyObject {
+    let name = "set".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__len__"), vec![]),
+        build_rust_method(name.clone(), String::from("__bool__"), vec![]),
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+        build_rust_method(name.clone(), String::from("__iter__"), vec![]),
+        build_rust_method(name.clone(), String::from("add"), vec!["self".to_string(), "element".to_string()]),
+        build_rust_method(name.clone(), String::from("__contains__"), vec!["self".to_string(), "element".to_string()]),
+    ];
+    build_method!(
+        name: name;
+        data:DataType::Set(items);
+        method_vec:method_vec
+    )
+}
+pub fn set_behaviour(method: String, args: HashMapAttr, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+    let set = match obj_parser("self".to_string(), "x".to_string(), args.clone()) {
+        Ok(DataType::Set(x)) => x,
+        _ => panic!("Error to get set"),
+    };
+    let items = set.borrow().clone();
+    Ok(match method.as_str() {
+        "__len__" => PyResult::Some(obj_int(items.len() as i64)),
+        "__bool__" => PyResult::Some(obj_bool(!items.is_empty())),
+        "__iter__" => PyResult::Some(obj_iterator("set_iterator", IterState::Items(items, 0))),
+        // {} is an empty dict, so an empty set is written as set()
+        "__str__" | "__repr__" if items.is_empty() => PyResult::Some(obj_str("set()".to_string())),
+        "__str__" | "__repr__" => {
+            let items: Vec<String> = items
+                .into_iter()
+                .map(|x| obj_to_repr(x, namespace.clone(), env))
+                .collect::<Exec<_>>()?;
+            PyResult::Some(obj_str(format!("{{{}}}", items.join(", "))))
+        }
+        "add" => match args.get("element") {
+            Some(PyObjAttr::Interpreter(x)) => {
+                set_add(&set, *x.clone(), namespace, env)?;
+                PyResult::Some(obj_none())
+            }
+            _ => panic!("Error to get set"),
+        },
+        "__contains__" => match args.get("element") {
+            Some(PyObjAttr::Interpreter(x)) => PyResult::Some(obj_bool(key_index(items, x, namespace, env)?.is_some())),
+            _ => panic!("Error to get set"),
+        },
+        _ => PyResult::None,
+    })
+}
+
+/// 元素与字典的键一样按哈希值和==比较，已存在时不添加
+pub(crate) fn set_add(set: &Shared<Vec<PyObject>>, item: PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<()> {
+    let items = set.borrow().clone();
+    if key_index(items, &item, namespace, env)?.is_none() {
+        set.borrow_mut().push(item);
+    }
+    Ok(())
+}
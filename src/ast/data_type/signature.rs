@@ -0,0 +1,167 @@
+use crate::ast::data_type::dict::obj_dict;
+use crate::ast::data_type::str::obj_str;
+use crate::ast::data_type::tuple::obj_tuple;
+use crate::ast::data_type::object::PyObject;
+
+/// ## struct Signature
+/// 函数的形参列表，Rust实现的方法和def定义的函数共用同一套参数绑定
+/// - posonlyargs：`/`之前的仅位置参数
+/// - args：普通参数
+/// - vararg：`*args`
+/// - kwonlyargs：`*`或`*args`之后的仅关键字参数
+/// - kwarg：`**kwargs`
+/// - defaults：最后len(defaults)个位置参数的默认值，定义函数时已经计算好
+/// - kw_defaults：与kwonlyargs一一对应，None表示没有默认值
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Signature {
+    pub posonlyargs: Vec<String>,
+    pub args: Vec<String>,
+    pub vararg: Option<String>,
+    pub kwonlyargs: Vec<String>,
+    pub kwarg: Option<String>,
+    pub defaults: Vec<PyObject>,
+    pub kw_defaults: Vec<Option<PyObject>>,
+}
+
+impl Signature {
+    /// 只有普通参数的形参列表，方法的self不参与绑定
+    pub fn positional(args: Vec<String>) -> Self {
+        Signature {
+            args: args.into_iter().filter(|x| x != "self").collect(),
+            ..Default::default()
+        }
+    }
+    /// 作为方法调用时的形参列表，去掉第一个位置参数self
+    pub fn method(&self) -> Self {
+        let mut signature = self.clone();
+        if !signature.posonlyargs.is_empty() {
+            signature.posonlyargs.remove(0);
+        } else if !signature.args.is_empty() {
+            signature.args.remove(0);
+        }
+        signature
+    }
+    pub fn positional_names(&self) -> Vec<String> {
+        let mut names = self.posonlyargs.clone();
+        names.extend(self.args.clone());
+        names
+    }
+    /// ## fn bind
+    /// 把实参绑定到形参上，返回(形参名, 值)，出错时返回与CPython相同的TypeError信息
+    pub fn bind(
+        &self,
+        name: &str,
+        args: Vec<PyObject>,
+        keywords: Vec<(String, PyObject)>,
+    ) -> Result<Vec<(String, PyObject)>, String> {
+        let positional = self.positional_names();
+        if args.len() > positional.len() && self.vararg.is_none() {
+            let required = positional.len() - self.defaults.len();
+            return Err(format!(
+                "{}() takes {} positional argument{} but {} {} given",
+                name,
+                if required == positional.len() {
+                    positional.len().to_string()
+                } else {
+                    format!("from {} to {}", required, positional.len())
+                },
+                if positional.len() == 1 { "" } else { "s" },
+                args.len(),
+                if args.len() == 1 { "was" } else { "were" }
+            ));
+        }
+        let mut values: Vec<Option<PyObject>> = vec![None; positional.len()];
+        let mut extra = vec![];
+        for (index, value) in args.into_iter().enumerate() {
+            match values.get_mut(index) {
+                Some(x) => *x = Some(value),
+                None => extra.push(value),
+            }
+        }
+        let mut kwonly_values: Vec<Option<PyObject>> = vec![None; self.kwonlyargs.len()];
+        let mut kwargs = vec![];
+        let mut posonly_passed = vec![];
+        for (key, value) in keywords {
+            let slot = if self.posonlyargs.contains(&key) {
+                None
+            } else if let Some(index) = positional.iter().position(|x| *x == key) {
+                Some(&mut values[index])
+            } else {
+                self.kwonlyargs.iter().position(|x| *x == key).map(|index| &mut kwonly_values[index])
+            };
+            match slot {
+                Some(Some(_)) => {
+                    return Err(format!("{}() got multiple values for argument '{}'", name, key))
+                }
+                Some(x) => *x = Some(value),
+                None if self.kwarg.is_some() => kwargs.push((obj_str(key), value)),
+                None if self.posonlyargs.contains(&key) => posonly_passed.push(key),
+                None => {
+                    return Err(format!("{}() got an unexpected keyword argument '{}'", name, key))
+                }
+            }
+        }
+        if !posonly_passed.is_empty() {
+            return Err(format!(
+                "{}() got some positional-only arguments passed as keyword arguments: '{}'",
+                name,
+                posonly_passed.join(", ")
+            ));
+        }
+        // the defaults belong to the last positional parameters
+        let first_default = positional.len() - self.defaults.len();
+        for (index, value) in values.iter_mut().enumerate() {
+            if value.is_none() && index >= first_default {
+                *value = Some(self.defaults[index - first_default].clone());
+            }
+        }
+        for (index, value) in kwonly_values.iter_mut().enumerate() {
+            if value.is_none() {
+                *value = self.kw_defaults[index].clone();
+            }
+        }
+        missing_error(name, "positional", &positional, &values)?;
+        missing_error(name, "keyword-only", &self.kwonlyargs, &kwonly_values)?;
+        let mut bound: Vec<(String, PyObject)> =
+            positional.into_iter().zip(values.into_iter().flatten()).collect();
+        if let Some(x) = &self.vararg {
+            bound.push((x.clone(), obj_tuple(extra)));
+        }
+        bound.extend(self.kwonlyargs.iter().cloned().zip(kwonly_values.into_iter().flatten()));
+        if let Some(x) = &self.kwarg {
+            bound.push((x.clone(), obj_dict(kwargs)));
+        }
+        Ok(bound)
+    }
+}
+
+/// 缺少参数时的报错，如"f() missing 2 required positional arguments: 'a' and 'b'"
+fn missing_error(name: &str, kind: &str, names: &[String], values: &[Option<PyObject>]) -> Result<(), String> {
+    let missing: Vec<String> = names
+        .iter()
+        .zip(values.iter())
+        .filter(|(_, value)| value.is_none())
+        .map(|(arg, _)| format!("'{}'", arg))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "{}() missing {} required {} argument{}: {}",
+        name,
+        missing.len(),
+        kind,
+        if missing.len() == 1 { "" } else { "s" },
+        join_names(missing)
+    ))
+}
+
+/// 'a'、'a' and 'b'、'a', 'b', and 'c'
+fn join_names(mut names: Vec<String>) -> String {
+    let last = names.pop().unwrap_or_default();
+    match names.len() {
+        0 => last,
+        1 => format!("{} and {}", names[0], last),
+        _ => format!("{}, and {}", names.join(", "), last),
+    }
+}
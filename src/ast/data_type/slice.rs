@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use crate::ast::ast_struct::DataType;
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::core_type::{build_rust_method, obj_parser};
+use crate::ast::data_type::exception::{raise, Exec};
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{obj_to_repr, HashMapAttr, PyObject, PyResult};
+use crate::ast::data_type::str::obj_str;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+
+/// ## fn obj_slice
+/// 切片对象`start:stop:step`，省略的部分是None
+pub fn obj_slice(start: PyObject, stop: PyObject, step: PyObject) -> PyObject {
+    let name = "slice".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__bool__"), vec![]),
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+    ];
+    build_method!(
+        name: name;
+        data:DataType::Slice(Box::new(start), Box::new(stop), Box::new(step));
+        method_vec:method_vec
+    )
+}
+pub fn slice_behaviour(method: String, args: HashMapAttr, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+    let (start, stop, step) = match obj_parser("self".to_string(), "x".to_string(), args) {
+        Ok(DataType::Slice(x, y, z)) => (*x, *y, *z),
+        _ => panic!("Error to get slice"),
+    };
+    Ok(match method.as_str() {
+        "__bool__" => PyResult::Some(obj_bool(true)),
+        "__str__" | "__repr__" => {
+            let start = obj_to_repr(start, namespace.clone(), env)?;
+            let stop = obj_to_repr(stop, namespace.clone(), env)?;
+            let step = obj_to_repr(step, namespace, env)?;
+            PyResult::Some(obj_str(format!("slice({}, {}, {})", start, stop, step)))
+        }
+        _ => PyResult::None,
+    })
+}
+
+/// ## enum SeqIndex
+/// 序列的下标
+/// - Item：负数已经加上长度，越界时是None
+/// - Slice：按长度调整后的start、stop和step
+pub enum SeqIndex {
+    Item(Option<usize>),
+    Slice(i64, i64, i64),
+}
+
+/// 切片的边界只能是整数或None
+fn slice_bound(obj: &PyObject) -> Exec<Option<i64>> {
+    match obj.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::None)) => Ok(None),
+        Ok(PyObjAttr::Rust(DataType::Int(x))) => Ok(Some(x)),
+        Ok(PyObjAttr::Rust(DataType::Bool(x))) => Ok(Some(x as i64)),
+        _ => raise("TypeError", "slice indices must be integers or None or have an __index__ method"),
+    }
+}
+
+/// ## fn slice_indices
+/// 与CPython的PySlice_AdjustIndices相同：负数加上长度，再截断到序列的范围内
+pub fn slice_indices(start: &PyObject, stop: &PyObject, step: &PyObject, len: usize) -> Exec<(i64, i64, i64)> {
+    let len = len as i64;
+    let step = slice_bound(step)?.unwrap_or(1);
+    if step == 0 {
+        return raise("ValueError", "slice step cannot be zero");
+    }
+    let (lower, upper) = if step < 0 { (-1, len - 1) } else { (0, len) };
+    let adjust = |x: Option<i64>, default: i64| match x {
+        None => default,
+        Some(x) if x < 0 => (x + len).max(lower),
+        Some(x) => x.min(upper),
+    };
+    let (start_default, stop_default) = if step < 0 { (upper, lower) } else { (lower, upper) };
+    Ok((adjust(slice_bound(start)?, start_default), adjust(slice_bound(stop)?, stop_default), step))
+}
+
+/// 切片选中的所有下标
+pub fn slice_positions(start: i64, stop: i64, step: i64) -> Vec<usize> {
+    let mut positions = vec![];
+    let mut index = start;
+    while (step > 0 && index < stop) || (step < 0 && index > stop) {
+        positions.push(index as usize);
+        index += step;
+    }
+    positions
+}
+
+/// ## fn seq_index
+/// 把下标转换为序列中的位置，type_name用于报错，如list、tuple
+pub fn seq_index(key: &PyObject, len: usize, type_name: &str) -> Exec<SeqIndex> {
+    let index = match key.get_value("x".to_string()) {
+        Ok(PyObjAttr::Rust(DataType::Int(x))) => x,
+        Ok(PyObjAttr::Rust(DataType::Bool(x))) => x as i64,
+        Ok(PyObjAttr::Rust(DataType::Slice(start, stop, step))) => {
+            let (start, stop, step) = slice_indices(&start, &stop, &step, len)?;
+            return Ok(SeqIndex::Slice(start, stop, step));
+        }
+        _ => {
+            return raise(
+                "TypeError",
+                format!("{} indices must be integers or slices, not {}", type_name, key.return_identity()),
+            )
+        }
+    };
+    let index = if index < 0 { index + len as i64 } else { index };
+    Ok(SeqIndex::Item(if index >= 0 && index < len as i64 { Some(index as usize) } else { None }))
+}
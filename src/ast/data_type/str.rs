@@ -1,7 +1,14 @@
 use crate::ast::ast_struct::DataType;
-use crate::ast::data_type::core_type::build_rust_method;
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::core_type::{build_rust_method, build_rust_method_signature};
 use crate::ast::data_type::core_type::{custom_behaviour, obj_parser};
+use crate::ast::data_type::exception::{raise, Exec};
 use crate::ast::data_type::int::obj_int;
+use crate::ast::data_type::iterator::{obj_iterate, obj_iterator, IterState};
+use crate::ast::data_type::list::obj_list;
+use crate::ast::data_type::none::obj_none;
+use crate::ast::data_type::signature::Signature;
+use crate::ast::data_type::slice::{seq_index, slice_positions, SeqIndex};
 use crate::ast::data_type::object::PyObjAttr;
 use crate::ast::data_type::object::PyObjBehaviors;
 use crate::ast::data_type::object::{HashMapAttr, PyObject, PyResult};
@@ -13,32 +20,215 @@ pub fn obj_str(x: String) -> PyObject {
     let name = "str".to_string();
     let mut method_vec: Vec<(String, PyObjBehaviors)> =
         build_method!(name:name.clone();param:vec!["self".to_string(),"other".to_string()]);
-    method_vec.append(&mut vec![build_rust_method(
-        name.clone(),
-        String::from("__len__"),
-        vec![],
-    )]);
+    method_vec.append(&mut vec![
+        build_rust_method(name.clone(), String::from("__len__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+        build_rust_method(name.clone(), String::from("__iter__"), vec![]),
+        build_rust_method(name.clone(), String::from("__contains__"), vec!["self".to_string(), "key".to_string()]),
+        build_rust_method(name.clone(), String::from("__getitem__"), vec!["self".to_string(), "key".to_string()]),
+        build_rust_method(name.clone(), String::from("upper"), vec![]),
+        build_rust_method(name.clone(), String::from("lower"), vec![]),
+        build_rust_method(name.clone(), String::from("join"), vec!["self".to_string(), "iterable".to_string()]),
+        build_rust_method(name.clone(), String::from("startswith"), vec!["self".to_string(), "prefix".to_string()]),
+        build_rust_method(name.clone(), String::from("endswith"), vec!["self".to_string(), "suffix".to_string()]),
+        build_rust_method_signature(
+            name.clone(),
+            String::from("split"),
+            Signature {
+                args: vec!["sep".to_string(), "maxsplit".to_string()],
+                defaults: vec![obj_none(), obj_int(-1)],
+                ..Default::default()
+            },
+        ),
+        build_rust_method_signature(
+            name.clone(),
+            String::from("strip"),
+            Signature {
+                args: vec!["chars".to_string()],
+                defaults: vec![obj_none()],
+                ..Default::default()
+            },
+        ),
+        build_rust_method_signature(
+            name.clone(),
+            String::from("replace"),
+            Signature {
+                args: vec!["old".to_string(), "new".to_string(), "count".to_string()],
+                defaults: vec![obj_int(-1)],
+                ..Default::default()
+            },
+        ),
+    ]);
     build_method!(
         name: name;
         data:DataType::Str(x);
         method_vec:method_vec
     )
 }
-pub fn str_behaviour(method: String, args: HashMapAttr,namespace: Namespace,env:&mut PyNamespace) -> PyResult {
+pub fn str_behaviour(method: String, args: HashMapAttr,namespace: Namespace,env:&mut PyNamespace) -> Exec<PyResult> {
     let obj_x: DataType = obj_parser("self".to_string(), "x".to_string(), args.clone())
         .unwrap_or_else(|x| panic!("{}", x));
-    match custom_behaviour(obj_x.clone(), method.clone(), args) {
-        PyResult::Some(x) => {
-            return PyResult::Some(x);
+    if let PyResult::Some(x) = custom_behaviour(obj_x.clone(), method.clone(), args.clone())? {
+        return Ok(PyResult::Some(x));
+    }
+    match method.clone().as_str() {
+        "__len__" => {
+            if let DataType::Str(x) = obj_x {
+                return Ok(PyResult::Some(obj_int(x.chars().count() as i64)));
+            }
+        }
+        "__repr__" => {
+            if let DataType::Str(x) = obj_x {
+                return Ok(PyResult::Some(obj_str(str_repr(&x))));
+            }
+        }
+        "__iter__" => {
+            if let DataType::Str(x) = obj_x {
+                let items = x.chars().map(|x| obj_str(x.to_string())).collect();
+                return Ok(PyResult::Some(obj_iterator("str_iterator", IterState::Items(items, 0))));
+            }
         }
         _ => {}
     }
-    match method.clone().as_str() {
-        "__len__" => match obj_x {
-            DataType::Str(x) => return PyResult::Some(obj_int(x.len() as i64)),
-            _ => {}
+    let x = match obj_x {
+        DataType::Str(x) => x,
+        _ => return Ok(PyResult::None),
+    };
+    let arg = |name: &str| match args.get(name) {
+        Some(PyObjAttr::Interpreter(x)) => match x.get_value("x".to_string()) {
+            Ok(PyObjAttr::Rust(x)) => x,
+            _ => DataType::None,
         },
-        _ => {}
+        _ => panic!("Error to get str"),
+    };
+    let str_arg = |name: &str, method: &str| match arg(name) {
+        DataType::Str(x) => Ok(x),
+        _ => raise("TypeError", format!("{}() argument must be str", method)),
+    };
+    Ok(match method.as_str() {
+        "__getitem__" => {
+            let key = match args.get("key") {
+                Some(PyObjAttr::Interpreter(x)) => *x.clone(),
+                _ => panic!("Error to get str"),
+            };
+            let chars: Vec<char> = x.chars().collect();
+            PyResult::Some(obj_str(match seq_index(&key, chars.len(), "string")? {
+                SeqIndex::Item(Some(x)) => chars[x].to_string(),
+                SeqIndex::Item(None) => return raise("IndexError", "string index out of range"),
+                SeqIndex::Slice(start, stop, step) => {
+                    slice_positions(start, stop, step).into_iter().map(|x| chars[x]).collect()
+                }
+            }))
+        }
+        "__contains__" => match arg("key") {
+            DataType::Str(key) => PyResult::Some(obj_bool(x.contains(key.as_str()))),
+            _ => {
+                let key = match args.get("key") {
+                    Some(PyObjAttr::Interpreter(x)) => x.return_identity(),
+                    _ => panic!("Error to get str"),
+                };
+                return raise("TypeError", format!("'in <string>' requires string as left operand, not {}", key));
+            }
+        },
+        "upper" => PyResult::Some(obj_str(x.to_uppercase())),
+        "lower" => PyResult::Some(obj_str(x.to_lowercase())),
+        "split" => {
+            let maxsplit = match arg("maxsplit") {
+                DataType::Int(x) => x,
+                _ => return raise("TypeError", "'maxsplit' must be an integer"),
+            };
+            let items = match arg("sep") {
+                DataType::None => split_whitespace(&x, maxsplit),
+                DataType::Str(sep) if sep.is_empty() => return raise("ValueError", "empty separator"),
+                DataType::Str(sep) if maxsplit < 0 => x.split(sep.as_str()).map(|x| x.to_string()).collect(),
+                DataType::Str(sep) => x
+                    .splitn(maxsplit as usize + 1, sep.as_str())
+                    .map(|x| x.to_string())
+                    .collect(),
+                _ => return raise("TypeError", "must be str or None"),
+            };
+            PyResult::Some(obj_list(items.into_iter().map(obj_str).collect()))
+        }
+        "strip" => PyResult::Some(obj_str(match arg("chars") {
+            DataType::None => x.trim().to_string(),
+            DataType::Str(chars) => x.trim_matches(|c| chars.contains(c)).to_string(),
+            _ => return raise("TypeError", "strip arg must be None or str"),
+        })),
+        "replace" => {
+            let (old, new) = (str_arg("old", "replace")?, str_arg("new", "replace")?);
+            PyResult::Some(obj_str(match arg("count") {
+                DataType::Int(count) if count < 0 => x.replace(old.as_str(), new.as_str()),
+                DataType::Int(count) => x.replacen(old.as_str(), new.as_str(), count as usize),
+                _ => return raise("TypeError", "'count' must be an integer"),
+            }))
+        }
+        "startswith" => PyResult::Some(obj_bool(x.starts_with(str_arg("prefix", "startswith")?.as_str()))),
+        "endswith" => PyResult::Some(obj_bool(x.ends_with(str_arg("suffix", "endswith")?.as_str()))),
+        "join" => {
+            let iterable = match args.get("iterable") {
+                Some(PyObjAttr::Interpreter(x)) => *x.clone(),
+                _ => panic!("Error to get str"),
+            };
+            let items = match obj_iterate(&iterable, namespace, env)? {
+                Some(x) => x,
+                None => return raise("TypeError", "can only join an iterable"),
+            };
+            let mut strings = vec![];
+            for (index, item) in items.into_iter().enumerate() {
+                match item.get_value("x".to_string()) {
+                    Ok(PyObjAttr::Rust(DataType::Str(x))) => strings.push(x),
+                    _ => {
+                        let message = format!("sequence item {}: expected str instance, {} found", index, item.return_identity());
+                        return raise("TypeError", message);
+                    }
+                }
+            }
+            PyResult::Some(obj_str(strings.join(x.as_str())))
+        }
+        _ => PyResult::None,
+    })
+}
+/// 没有分隔符时按连续的空白分割，分割maxsplit次后剩下的部分保持原样
+fn split_whitespace(x: &str, maxsplit: i64) -> Vec<String> {
+    let mut items = vec![];
+    let mut rest = x.trim_start();
+    while !rest.is_empty() {
+        if maxsplit >= 0 && items.len() as i64 == maxsplit {
+            items.push(rest.to_string());
+            break;
+        }
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        items.push(rest[..end].to_string());
+        rest = rest[end..].trim_start();
+    }
+    items
+}
+/// 与python的repr(str)相同，优先使用单引号
+pub fn str_repr(x: &str) -> String {
+    let quote = if x.contains('\'') && !x.contains('"') { '"' } else { '\'' };
+    let mut s = quote.to_string();
+    for char in x.chars() {
+        match char {
+            '\\' => s += "\\\\",
+            '\n' => s += "\\n",
+            '\r' => s += "\\r",
+            '\t' => s += "\\t",
+            x if x == quote => {
+                s.push('\\');
+                s.push(x)
+            }
+            x if x.is_control() => {
+                let code = x as u32;
+                s += if code <= 0xff {
+                    format!("\\x{:02x}", code)
+                } else {
+                    format!("\\u{:04x}", code)
+                }
+                .as_str()
+            }
+            x => s.push(x),
+        }
     }
-    PyResult::None
+    s.push(quote);
+    s
 }
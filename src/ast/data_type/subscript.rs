@@ -0,0 +1,65 @@
+use crate::ast::data_type::exception::{raise, Exec};
+use crate::ast::data_type::none::obj_none;
+use crate::ast::data_type::object::{PyObjAttr, PyObject, PyResult};
+use crate::ast::namespace::{Namespace, PyNamespace};
+
+/// 调用下标相关的方法，对象没有这个方法时用message报TypeError
+fn call_item_method(
+    obj: &PyObject,
+    method: &str,
+    args: Vec<PyObject>,
+    message: &str,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<PyObject> {
+    if !obj.has_method(method) {
+        return raise("TypeError", format!("'{}' object {}", obj.return_identity(), message));
+    }
+    let mut obj = obj.clone();
+    let args = args.into_iter().map(|x| PyObjAttr::Interpreter(Box::new(x))).collect();
+    let hashmap = obj.convert_vec_to_hashmap(method.to_string(), args)?;
+    let result = match method {
+        "__getitem__" => obj.getitem(hashmap, namespace, env),
+        "__setitem__" => obj.setitem(hashmap, namespace, env),
+        _ => obj.delitem(hashmap, namespace, env),
+    }?;
+    match result {
+        PyResult::Some(x) => Ok(x),
+        PyResult::None => Ok(obj_none()),
+        PyResult::Err(x) => Err(x.into()),
+        _ => panic!("Error at running"),
+    }
+}
+
+/// ## fn obj_getitem
+/// 相当于obj[key]
+pub fn obj_getitem(obj: &PyObject, key: PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyObject> {
+    call_item_method(obj, "__getitem__", vec![key], "is not subscriptable", namespace, env)
+}
+
+/// ## fn obj_setitem
+/// 相当于obj[key] = value
+pub fn obj_setitem(
+    obj: &PyObject,
+    key: PyObject,
+    value: PyObject,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<()> {
+    call_item_method(
+        obj,
+        "__setitem__",
+        vec![key, value],
+        "does not support item assignment",
+        namespace,
+        env,
+    )?;
+    Ok(())
+}
+
+/// ## fn obj_delitem
+/// 相当于del obj[key]
+pub fn obj_delitem(obj: &PyObject, key: PyObject, namespace: Namespace, env: &mut PyNamespace) -> Exec<()> {
+    call_item_method(obj, "__delitem__", vec![key], "doesn't support item deletion", namespace, env)?;
+    Ok(())
+}
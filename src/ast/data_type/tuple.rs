@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use crate::ast::ast_struct::{Compare, DataType, Operator};
+use crate::ast::data_type::bool::obj_bool;
+use crate::ast::data_type::builtin::token_info_repr;
+use crate::ast::data_type::core_type::{build_rust_method, obj_parser};
+use crate::ast::data_type::exception::{raise, Exec};
+use crate::ast::data_type::int::obj_int;
+use crate::ast::data_type::iterator::{obj_iterator, IterState};
+use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::object::PyObjBehaviors;
+use crate::ast::data_type::object::{obj_to_repr, HashMapAttr, PyObject, PyResult};
+use crate::ast::data_type::slice::{seq_index, slice_positions, SeqIndex};
+use crate::ast::data_type::str::obj_str;
+use crate::ast::namespace::{Namespace, PyNamespace};
+use crate::build_method;
+
+pub fn obj_tuple(x: Vec<PyObject>) -> PyObject {
+    let name = "tuple".to_string();
+    let method_vec: Vec<(String, PyObjBehaviors)> = vec![
+        build_rust_method(name.clone(), String::from("__len__"), vec![]),
+        build_rust_method(name.clone(), String::from("__bool__"), vec![]),
+        build_rust_method(name.clone(), String::from("__str__"), vec![]),
+        build_rust_method(name.clone(), String::from("__repr__"), vec![]),
+        build_rust_method(name.clone(), String::from("__iter__"), vec![]),
+        build_rust_method(name.clone(), String::from("__getitem__"), vec!["self".to_string(), "key".to_string()]),
+        build_rust_method(name.clone(), String::from("__eq__"), vec!["self".to_string(), "other".to_string()]),
+        build_rust_method(name.clone(), String::from("__lt__"), vec!["self".to_string(), "other".to_string()]),
+        build_rust_method(name.clone(), String::from("__le__"), vec!["self".to_string(), "other".to_string()]),
+        build_rust_method(name.clone(), String::from("__gt__"), vec!["self".to_string(), "other".to_string()]),
+        build_rust_method(name.clone(), String::from("__ge__"), vec!["self".to_string(), "other".to_string()]),
+    ];
+    build_method!(
+        name: name;
+        data:DataType::Tuple(x);
+        method_vec:method_vec
+    )
+}
+pub fn tuple_behaviour(method: String, args: HashMapAttr, namespace: Namespace, env: &mut PyNamespace) -> Exec<PyResult> {
+    let items = match obj_parser("self".to_string(), "x".to_string(), args.clone()) {
+        Ok(DataType::Tuple(x)) => x,
+        _ => panic!("Error to get tuple"),
+    };
+    Ok(match method.as_str() {
+        "__len__" => PyResult::Some(obj_int(items.len() as i64)),
+        "__bool__" => PyResult::Some(obj_bool(!items.is_empty())),
+        "__iter__" => PyResult::Some(obj_iterator("tuple_iterator", IterState::Items(items, 0))),
+        "__getitem__" => {
+            let key = match args.get("key") {
+                Some(PyObjAttr::Interpreter(x)) => *x.clone(),
+                _ => panic!("Error to get tuple"),
+            };
+            PyResult::Some(match seq_index(&key, items.len(), "tuple")? {
+                SeqIndex::Item(Some(x)) => items[x].clone(),
+                SeqIndex::Item(None) => return raise("IndexError", "tuple index out of range"),
+                SeqIndex::Slice(start, stop, step) => obj_tuple(
+                    slice_positions(start, stop, step)
+                        .into_iter()
+                        .map(|x| items[x].clone())
+                        .collect(),
+                ),
+            })
+        }
+        "__eq__" => {
+            let other = match args.get("other") {
+                Some(PyObjAttr::Interpreter(x)) => x.get_value("x".to_string()),
+                _ => panic!("Error to get tuple"),
+            };
+            match other {
+                Ok(PyObjAttr::Rust(DataType::Tuple(x))) => PyResult::Some(obj_bool(items_eq(items, x, namespace, env)?)),
+                _ => PyResult::None,
+            }
+        }
+        "__lt__" | "__le__" | "__gt__" | "__ge__" => {
+            let other = match args.get("other") {
+                Some(PyObjAttr::Interpreter(x)) => x.get_value("x".to_string()),
+                _ => panic!("Error to get tuple"),
+            };
+            match other {
+                Ok(PyObjAttr::Rust(DataType::Tuple(x))) => {
+                    PyResult::Some(obj_bool(items_order(&method, items, x, namespace, env)?))
+                }
+                _ => PyResult::None,
+            }
+        }
+        "__str__" | "__repr__" if is_token_info(&args) => PyResult::Some(obj_str(token_info_repr(items, namespace, env)?)),
+        "__str__" | "__repr__" => {
+            let items: Vec<String> = items
+                .into_iter()
+                .map(|x| obj_to_repr(x, namespace.clone(), env))
+                .collect::<Exec<_>>()?;
+            // a tuple with one item needs a trailing comma
+            let comma = if items.len() == 1 { "," } else { "" };
+            PyResult::Some(obj_str(format!("({}{})", items.join(", "), comma)))
+        }
+        _ => PyResult::None,
+    })
+}
+
+/// tokenize.TokenInfo也是tuple，只有repr不同
+fn is_token_info(args: &HashMapAttr) -> bool {
+    matches!(args.get("self"), Some(PyObjAttr::Interpreter(x)) if x.return_identity() == "TokenInfo")
+}
+
+/// ## fn items_eq
+/// 序列相等：长度相同并且对应的元素相等，同一个对象总是相等
+pub fn items_eq(x: Vec<PyObject>, y: Vec<PyObject>, namespace: Namespace, env: &mut PyNamespace) -> Exec<bool> {
+    if x.len() != y.len() {
+        return Ok(false);
+    }
+    for (x, y) in x.into_iter().zip(y) {
+        if x != y && !Compare::compare(Operator::Eq, x, y, namespace.clone(), env)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// ## fn items_order
+/// 序列按字典序比较：第一对不相等的元素决定结果，前面都相等时比较长度
+pub fn items_order(
+    method: &str,
+    x: Vec<PyObject>,
+    y: Vec<PyObject>,
+    namespace: Namespace,
+    env: &mut PyNamespace,
+) -> Exec<bool> {
+    let operator = match method {
+        "__lt__" => Operator::Lt,
+        "__le__" => Operator::LtE,
+        "__gt__" => Operator::Gt,
+        _ => Operator::GtE,
+    };
+    let (x_len, y_len) = (x.len(), y.len());
+    for (x, y) in x.into_iter().zip(y) {
+        if x != y && !Compare::compare(Operator::Eq, x.clone(), y.clone(), namespace.clone(), env)? {
+            return Compare::compare(operator, x, y, namespace, env);
+        }
+    }
+    Ok(match operator {
+        Operator::Lt => x_len < y_len,
+        Operator::LtE => x_len <= y_len,
+        Operator::Gt => x_len > y_len,
+        _ => x_len >= y_len,
+    })
+}
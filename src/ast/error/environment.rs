@@ -17,12 +17,20 @@ impl Display for GetVariableError {
     }
 }
 impl GetVariableError {
-    pub fn new(basic_error: BasicError, id: String, namespace: String) -> ErrorType {
-        return ErrorType::GetVariableError(GetVariableError {
+    pub fn new(basic_error: BasicError, id: String, namespace: String) -> Self {
+        GetVariableError {
             basic_error,
             id,
             namespace,
-        });
+        }
+    }
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+}
+impl From<GetVariableError> for ErrorType {
+    fn from(value: GetVariableError) -> Self {
+        ErrorType::GetVariableError(value)
     }
 }
 
@@ -42,12 +50,17 @@ impl Display for SetVariableError {
     }
 }
 impl SetVariableError {
-    pub fn new(basic_error: BasicError, id: String, namespace: String) -> ErrorType {
-        return ErrorType::SetVariableError(SetVariableError {
+    pub fn new(basic_error: BasicError, id: String, namespace: String) -> Self {
+        SetVariableError {
             basic_error,
             id,
             namespace,
-        });
+        }
+    }
+}
+impl From<SetVariableError> for ErrorType {
+    fn from(value: SetVariableError) -> Self {
+        ErrorType::SetVariableError(value)
     }
 }
 
@@ -66,11 +79,16 @@ impl Display for NamespaceNotFound {
     }
 }
 impl NamespaceNotFound {
-    pub fn new(basic_error: BasicError, namespace: String) -> ErrorType {
-        return ErrorType::NamespaceNotFound(NamespaceNotFound {
+    pub fn new(basic_error: BasicError, namespace: String) -> Self {
+        NamespaceNotFound {
             basic_error,
             namespace,
-        });
+        }
+    }
+}
+impl From<NamespaceNotFound> for ErrorType {
+    fn from(value: NamespaceNotFound) -> Self {
+        ErrorType::NamespaceNotFound(value)
     }
 }
 #[derive(Clone, Debug)]
@@ -89,11 +107,65 @@ impl Display for GetNonlocalVariableError {
     }
 }
 impl GetNonlocalVariableError {
-    pub fn new(basic_error: BasicError, id: String, namespace: String) -> ErrorType {
-        return ErrorType::GetNonlocalVariableError(GetNonlocalVariableError {
+    pub fn new(basic_error: BasicError, id: String, namespace: String) -> Self {
+        GetNonlocalVariableError {
             basic_error,
             id,
             namespace,
-        });
+        }
+    }
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+}
+impl From<GetNonlocalVariableError> for ErrorType {
+    fn from(value: GetNonlocalVariableError) -> Self {
+        ErrorType::GetNonlocalVariableError(value)
+    }
+}
+
+/// 函数中赋值过的名字在绑定之前被读取，enclosing为true时名字属于外层函数
+#[derive(Clone, Debug)]
+pub struct UnboundLocalError {
+    basic_error: BasicError,
+    id: String,
+    enclosing: bool,
+}
+impl Display for UnboundLocalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{},\nError at getting variable:{} is not bound",
+            self.basic_error, self.id
+        )
+    }
+}
+impl UnboundLocalError {
+    pub fn new(basic_error: BasicError, id: String, enclosing: bool) -> Self {
+        UnboundLocalError {
+            basic_error,
+            id,
+            enclosing,
+        }
+    }
+    pub fn exception(&self) -> (&'static str, String) {
+        match self.enclosing {
+            true => (
+                "NameError",
+                format!(
+                    "cannot access free variable '{}' where it is not associated with a value in enclosing scope",
+                    self.id
+                ),
+            ),
+            false => (
+                "UnboundLocalError",
+                format!("cannot access local variable '{}' where it is not associated with a value", self.id),
+            ),
+        }
+    }
+}
+impl From<UnboundLocalError> for ErrorType {
+    fn from(value: UnboundLocalError) -> Self {
+        ErrorType::UnboundLocalError(value)
     }
 }
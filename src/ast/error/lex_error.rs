@@ -0,0 +1,49 @@
+use std::fmt::{Display, Formatter};
+
+use crate::ast::error::{BasicError, ErrorType};
+
+/// ## struct LexError
+/// 词法分析错误，扫描器遇到错误后会继续扫描，一次报告所有错误
+/// - kind：对应的异常类，SyntaxError或它的子类IndentationError、TabError
+/// - message：错误的内容，不包括异常类，显示时在前面加上kind
+#[derive(Clone, Debug)]
+pub struct LexError {
+    basic_error: BasicError,
+    kind: &'static str,
+    message: String,
+}
+
+impl Display for LexError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},\n{}: {}", self.basic_error, self.kind, self.message)
+    }
+}
+impl LexError {
+    pub fn new(basic_error: BasicError, message: &str) -> LexError {
+        LexError {
+            basic_error,
+            kind: "SyntaxError",
+            message: message.to_string(),
+        }
+    }
+    //Builder
+    pub fn kind(mut self, kind: &'static str) -> Self {
+        self.kind = kind;
+        self
+    }
+    pub fn kind_name(&self) -> &'static str {
+        self.kind
+    }
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+    /// (lineno, col_offset)，从0开始
+    pub fn position(&self) -> (u64, u64) {
+        (self.basic_error.lineno, self.basic_error.col_offset)
+    }
+}
+impl From<LexError> for ErrorType {
+    fn from(value: LexError) -> Self {
+        ErrorType::LexError(value)
+    }
+}
@@ -1,12 +1,14 @@
 use crate::ast::error::environment::{
-    GetNonlocalVariableError, GetVariableError, NamespaceNotFound, SetVariableError,
+    GetNonlocalVariableError, GetVariableError, NamespaceNotFound, SetVariableError, UnboundLocalError,
 };
 use std::fmt::{Display, Formatter};
 
-use crate::ast::error::object_error::{ObjBasicError, ObjMethodCallError};
+use crate::ast::error::lex_error::LexError;
+use crate::ast::error::object_error::{ObjBasicError, ObjMethodCallError, StopIterationError};
 use crate::ast::error::parser_error::ParserError;
 
 pub mod environment;
+pub mod lex_error;
 pub mod object_error;
 pub mod parser_error;
 
@@ -53,11 +55,14 @@ pub enum ErrorType {
     BasicError(BasicError),
     ObjBasicError(ObjBasicError),
     ObjMethodCallError(ObjMethodCallError),
+    StopIterationError(StopIterationError),
+    LexError(LexError),
     ParserError(ParserError),
     GetVariableError(GetVariableError),
     SetVariableError(SetVariableError),
     GetNonlocalVariableError(GetNonlocalVariableError),
     NamespaceNotFound(NamespaceNotFound),
+    UnboundLocalError(UnboundLocalError),
 }
 
 impl Display for ErrorType {
@@ -72,6 +77,12 @@ impl Display for ErrorType {
             ErrorType::ObjMethodCallError(x) => {
                 write!(f, "{}", x)
             }
+            ErrorType::StopIterationError(x) => {
+                write!(f, "{}", x)
+            }
+            ErrorType::LexError(x) => {
+                write!(f, "{}", x)
+            }
             ErrorType::ParserError(x) => {
                 write!(f, "{}", x)
             }
@@ -87,6 +98,28 @@ impl Display for ErrorType {
             ErrorType::NamespaceNotFound(x) => {
                 write!(f, "{}", x)
             }
+            ErrorType::UnboundLocalError(x) => {
+                write!(f, "{}", x)
+            }
+        }
+    }
+}
+
+impl ErrorType {
+    /// ## fn exception
+    /// 对应的内置异常的名字和信息，没有对应的异常时是SystemError
+    pub fn exception(&self) -> (&'static str, String) {
+        match self {
+            ErrorType::GetVariableError(x) => ("NameError", format!("name '{}' is not defined", x.id())),
+            ErrorType::GetNonlocalVariableError(x) => {
+                ("SyntaxError", format!("no binding for nonlocal '{}' found", x.id()))
+            }
+            ErrorType::UnboundLocalError(x) => x.exception(),
+            ErrorType::ObjMethodCallError(x) => x.exception(),
+            ErrorType::StopIterationError(_) => ("StopIteration", String::new()),
+            ErrorType::LexError(x) => (x.kind_name(), x.message().to_string()),
+            ErrorType::ParserError(x) => ("SyntaxError", x.message().to_string()),
+            x => ("SystemError", x.to_string()),
         }
     }
 }
@@ -60,4 +60,36 @@ impl ObjMethodCallError {
         self.method = method;
         self.clone()
     }
+    /// 对应的异常：运算查找的特殊方法不存在时是TypeError，其余是AttributeError
+    pub fn exception(&self) -> (&'static str, String) {
+        if self.method.starts_with("__") && self.method.ends_with("__") {
+            (
+                "TypeError",
+                format!("'{}' object does not support '{}'", self.obj.identity, self.method),
+            )
+        } else {
+            (
+                "AttributeError",
+                format!("'{}' object has no attribute '{}'", self.obj.identity, self.method),
+            )
+        }
+    }
+}
+/// ## struct StopIterationError
+/// 迭代器耗尽时由`__next__`返回
+#[derive(Clone, Debug, Default)]
+pub struct StopIterationError {
+    obj: ObjBasicError,
+}
+impl Display for StopIterationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},\nStopIteration", self.obj)
+    }
+}
+impl StopIterationError {
+    //builder
+    pub fn obj(&mut self, obj_basic_error: ObjBasicError) -> Self {
+        self.obj = obj_basic_error;
+        self.clone()
+    }
 }
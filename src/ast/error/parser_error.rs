@@ -2,18 +2,67 @@ use std::fmt::{Display, Formatter};
 
 use crate::ast::error::{BasicError, ErrorType};
 
+/// ## struct ParserError
+/// 语法分析错误
+/// - kind：对应的异常类，SyntaxError或它的子类IndentationError
+/// - message：python的报错信息，为空时是"invalid syntax"
 #[derive(Clone, Debug)]
 pub struct ParserError {
     basic_error: BasicError,
+    kind: &'static str,
+    message: String,
 }
 
 impl Display for ParserError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{},\nError at parser", self.basic_error)
+        if self.message.is_empty() {
+            write!(f, "{},\nError at parser", self.basic_error)
+        } else {
+            write!(f, "{},\n{}: {}", self.basic_error, self.kind, self.message)
+        }
     }
 }
 impl ParserError {
-    pub fn new(basic_error: BasicError) -> ErrorType {
-        return ErrorType::ParserError(ParserError { basic_error });
+    pub fn new(basic_error: BasicError) -> Self {
+        ParserError {
+            basic_error,
+            kind: "SyntaxError",
+            message: String::new(),
+        }
+    }
+    /// 带有python报错信息的语法错误，如"'return' outside function"
+    pub fn syntax_error(basic_error: BasicError, message: &str) -> ErrorType {
+        ErrorType::ParserError(ParserError {
+            basic_error,
+            kind: "SyntaxError",
+            message: message.to_string(),
+        })
+    }
+    /// 缩进错误，如"unexpected indent"
+    pub fn indentation_error(basic_error: BasicError, message: &str) -> ErrorType {
+        ErrorType::ParserError(ParserError {
+            basic_error,
+            kind: "IndentationError",
+            message: message.to_string(),
+        })
+    }
+    pub fn kind_name(&self) -> &'static str {
+        self.kind
+    }
+    /// 对应的SyntaxError的信息
+    pub fn message(&self) -> &str {
+        match self.message.as_str() {
+            "" => "invalid syntax",
+            x => x,
+        }
+    }
+    /// (lineno, col_offset)，从0开始
+    pub fn position(&self) -> (u64, u64) {
+        (self.basic_error.lineno, self.basic_error.col_offset)
+    }
+}
+impl From<ParserError> for ErrorType {
+    fn from(value: ParserError) -> Self {
+        ErrorType::ParserError(value)
     }
 }
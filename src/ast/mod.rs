@@ -1,6 +1,6 @@
 pub mod analyze;
 pub mod ast_struct;
 pub mod data_type;
-mod error;
+pub mod error;
 pub mod namespace;
 pub mod scanner;
@@ -4,7 +4,7 @@ use uuid::Uuid;
 
 use crate::ast::data_type::object::PyObject;
 use crate::ast::error::{BasicError, ErrorType};
-use crate::ast::error::environment::{GetVariableError, NamespaceNotFound, SetVariableError};
+use crate::ast::error::environment::{GetVariableError, NamespaceNotFound, SetVariableError, UnboundLocalError};
 
 type PyEnvId = HashMap<String, Uuid>;
 
@@ -13,20 +13,12 @@ type PyEnvId = HashMap<String, Uuid>;
 /// 变量池
 /// 为什么不用Hashmap之类？为了实现双向键值对
 /// 为什么不用Bimap？因为PyObject没有实现Eq特征（哭）
+#[derive(Default)]
 pub struct VariablePool {
     id: Vec<Uuid>,
     value: Vec<PyObject>,
     count: Vec<u64>,
 }
-impl Default for VariablePool {
-    fn default() -> Self {
-        VariablePool {
-            id: vec![],
-            value: vec![],
-            count: vec![],
-        }
-    }
-}
 impl VariablePool {
     pub fn insert(&mut self, key: Uuid, value: PyObject) {
         self.id.push(key);
@@ -97,8 +89,15 @@ impl VariablePool {
 pub struct PyNamespace {
     pub variable_pool: VariablePool,
     pub(crate) builtin_namespace: PyEnvId,
-    pub(crate) global_namespace: PyEnvId,
+    /// 每个模块的全局命名空间，键是模块名
+    pub(crate) global_namespace: HashMap<String, PyEnvId>,
     pub(crate) enclosing_namespace: HashMap<String, InterNamespace>,
+    /// 正在执行的代码所在的模块，Namespace::Global指的是它的全局命名空间
+    pub(crate) module: String,
+    /// 正在执行的Python函数的层数
+    pub(crate) depth: usize,
+    /// 函数调用层数的上限，超过时抛出RecursionError，即sys.getrecursionlimit()
+    pub(crate) recursion_limit: usize,
 }
 impl Default for PyNamespace {
     fn default() -> Self {
@@ -107,21 +106,20 @@ impl Default for PyNamespace {
             builtin_namespace: Default::default(),
             global_namespace: Default::default(),
             enclosing_namespace: Default::default(),
+            module: "__main__".to_string(),
+            depth: 0,
+            recursion_limit: 1000,
         }
     }
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct InterNamespace {
     pub namespace: PyEnvId,
     pub sub: HashMap<String, InterNamespace>,
-}
-impl Default for InterNamespace {
-    fn default() -> Self {
-        InterNamespace {
-            namespace: Default::default(),
-            sub: Default::default(),
-        }
-    }
+    /// 有函数在此命名空间中定义时为true，函数返回后不能删除
+    pub captured: bool,
+    /// 函数体中赋值过的名字，它们在整个函数中都是局部变量
+    pub locals: Vec<String>,
 }
 impl PyNamespace {
     fn get_from_env(&mut self, py_env_id: PyEnvId, id: &String) -> Option<PyObject> {
@@ -140,7 +138,7 @@ impl PyNamespace {
                 BasicError::default(),
                 id,
                 "Builtin".to_string(),
-            )),
+            ).into()),
             Some(x) => Ok(x),
         }
     }
@@ -155,7 +153,7 @@ impl PyNamespace {
                     BasicError::default(),
                     id,
                     "Builtin".to_string(),
-                ))
+                ).into())
             }
             Some(x) => {
                 self.variable_pool.update_value(*x, value);
@@ -164,27 +162,28 @@ impl PyNamespace {
         None
     }
     pub fn get_global(&mut self, id: String) -> Result<PyObject, ErrorType> {
-        match self.get_from_env(self.global_namespace.clone(), &id) {
+        let module = self.module.clone();
+        match self.get_module_variable(&module, &id) {
             None => Err(GetVariableError::new(
                 BasicError::default(),
                 id,
                 "Global".to_string(),
-            )),
+            ).into()),
             Some(x) => Ok(x),
         }
     }
     pub fn set_global(&mut self, id: String, value: PyObject) {
-        let uuid = self.variable_pool.store_new_value(value);
-        self.global_namespace.insert(id.clone(), uuid);
+        let module = self.module.clone();
+        self.set_module_variable(&module, id, value);
     }
     pub fn update_global(&mut self, id: String, value: PyObject) -> Option<ErrorType> {
-        match self.global_namespace.get(&id) {
+        match self.global_namespace.get(&self.module).and_then(|x| x.get(&id)) {
             None => {
                 return Some(SetVariableError::new(
                     BasicError::default(),
                     id,
                     "Builtin".to_string(),
-                ))
+                ).into())
             }
             Some(x) => {
                 self.variable_pool.update_value(*x, value);
@@ -192,6 +191,49 @@ impl PyNamespace {
         };
         None
     }
+    /// 模块的全局变量，如`import m`后的`m.x`
+    pub fn get_module_variable(&mut self, module: &str, id: &str) -> Option<PyObject> {
+        let uuid = *self.global_namespace.get(module)?.get(id)?;
+        self.variable_pool.get_value(uuid)
+    }
+    pub fn set_module_variable(&mut self, module: &str, id: String, value: PyObject) {
+        let uuid = self.variable_pool.store_new_value(value);
+        // release the value the name was bound to, like delete_module_variable
+        if let Some(old) = self.global_namespace.entry(module.to_string()).or_default().insert(id, uuid) {
+            self.variable_pool.del_variable(old);
+        }
+    }
+    /// 变量不存在时返回false
+    pub fn delete_module_variable(&mut self, module: &str, id: &str) -> bool {
+        match self.global_namespace.get_mut(module).and_then(|x| x.remove(id)) {
+            Some(x) => {
+                self.variable_pool.del_variable(x);
+                true
+            }
+            None => false,
+        }
+    }
+    /// ## fn module_variables
+    /// 模块中绑定的所有全局变量，按名字排序
+    pub fn module_variables(&mut self, module: &str) -> Vec<(String, PyObject)> {
+        let ids: Vec<(String, Uuid)> = match self.global_namespace.get(module) {
+            None => return vec![],
+            Some(x) => x.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+        };
+        let mut variables: Vec<(String, PyObject)> = ids
+            .into_iter()
+            .filter_map(|(id, uuid)| Some((id, self.variable_pool.get_value(uuid)?)))
+            .collect();
+        variables.sort_by(|x, y| x.0.cmp(&y.0));
+        variables
+    }
+    /// ## fn remove_module
+    /// 删除模块的全局命名空间，重新导入时从空的命名空间开始执行
+    pub fn remove_module(&mut self, module: &str) {
+        for uuid in self.global_namespace.remove(module).unwrap_or_default().into_values() {
+            self.variable_pool.del_variable(uuid);
+        }
+    }
     pub fn get_enclosing(
         &mut self,
         namespace_id: String,
@@ -211,7 +253,7 @@ impl PyNamespace {
             BasicError::default(),
             id,
             namespace_id,
-        ))
+        ).into())
     }
     pub fn create_enclosing_namespace(&mut self, namespace_id: String) {
         self.enclosing_namespace
@@ -251,7 +293,7 @@ impl PyNamespace {
         match local_id.get(index) {
             None => Ok(inter_namespace),
             Some(x) => match inter_namespace.sub.get_mut(x) {
-                None => Err(NamespaceNotFound::new(BasicError::default(), x.clone())),
+                None => Err(NamespaceNotFound::new(BasicError::default(), x.clone()).into()),
                 Some(x) => Self::deref_local_namespace(x, local_id, index + 1),
             },
         }
@@ -280,7 +322,7 @@ impl PyNamespace {
                     BasicError::default(),
                     id,
                     "".to_string(),
-                ))
+                ).into())
             }
             Some(x) => {
                 match Self::deref_local_namespace(x, local_id, 0) {
@@ -309,7 +351,7 @@ impl PyNamespace {
                         let inter = x;
                         match inter.namespace.get(&id) {
                             None => {}
-                            Some(x) => match self.variable_pool.get_value(x.clone()) {
+                            Some(x) => match self.variable_pool.get_value(*x) {
                                 None => {}
                                 Some(x) => return Ok(x),
                             },
@@ -323,14 +365,131 @@ impl PyNamespace {
             BasicError::default(),
             id,
             "".to_string(),
-        ))
+        ).into())
     }
-    pub fn get_nonlocal(
-        &mut self,
-        namespace_id: String,
-        local_id: Vec<String>,
-    ) -> Result<(PyObject, Vec<String>), ErrorType> {
-        todo!()
+    /// ## fn declare_locals
+    /// 记录函数帧中的局部变量名，调用函数时设置
+    pub fn declare_locals(&mut self, namespace: &Namespace, locals: Vec<String>) {
+        if let Some(x) = self.deref_namespace(namespace) {
+            x.locals = locals;
+        }
+    }
+    fn is_local(&mut self, namespace_id: String, local_id: Vec<String>, id: &str) -> bool {
+        self.enclosing_namespace
+            .get_mut(&namespace_id)
+            .and_then(|x| Self::deref_local_namespace(x, local_id, 0).ok())
+            .is_some_and(|x| x.locals.iter().any(|x| x == id))
+    }
+    /// ## fn get_variable
+    /// 按照LEGB的顺序查找变量：从当前函数帧逐层向外，然后是global和builtin
+    /// 函数帧的局部变量还没有绑定时抛出UnboundLocalError
+    pub fn get_variable(&mut self, namespace: Namespace, id: String) -> Result<PyObject, ErrorType> {
+        let path = namespace.path();
+        for index in (1..=path.len()).rev() {
+            if let Ok(x) = self.get_local(path[0].clone(), path[1..index].to_vec(), id.clone()) {
+                return Ok(x);
+            }
+            // a local name that is not bound yet does not fall back to the outer scopes
+            if self.is_local(path[0].clone(), path[1..index].to_vec(), id.as_str()) {
+                return Err(UnboundLocalError::new(BasicError::default(), id, index < path.len()).into());
+            }
+        }
+        if let Namespace::Builtin = namespace {
+            return self.get_builtin(id);
+        }
+        match self.get_global(id.clone()) {
+            Ok(x) => Ok(x),
+            Err(_) => self.get_builtin(id),
+        }
+    }
+    /// ## fn set_variable
+    /// 在当前命名空间中绑定变量
+    pub fn set_variable(&mut self, namespace: Namespace, id: String, value: PyObject) -> Option<ErrorType> {
+        match namespace {
+            Namespace::Builtin => panic!("You cannot set built variable in code"),
+            Namespace::Global => self.set_global(id, value),
+            Namespace::Enclosing(x) => self.set_enclosing(x, id, value),
+            Namespace::Local(x) => return self.set_local(x[0].clone(), x[1..].to_vec(), id, value),
+        }
+        None
+    }
+    /// ## fn delete_variable
+    /// 在当前命名空间中解除变量的绑定，变量不存在时返回false
+    pub fn delete_variable(&mut self, namespace: &Namespace, id: &str) -> bool {
+        let uuid = match namespace {
+            Namespace::Builtin => panic!("You cannot delete built variable in code"),
+            Namespace::Global => {
+                let module = self.module.clone();
+                return self.delete_module_variable(&module, id);
+            }
+            _ => self.deref_namespace(namespace).and_then(|x| x.namespace.remove(id)),
+        };
+        match uuid {
+            Some(x) => {
+                self.variable_pool.del_variable(x);
+                true
+            }
+            None => false,
+        }
+    }
+    fn deref_namespace(&mut self, namespace: &Namespace) -> Option<&mut InterNamespace> {
+        let path = namespace.path();
+        match self.enclosing_namespace.get_mut(path.first()?) {
+            None => None,
+            Some(x) => Self::deref_local_namespace(x, path[1..].to_vec(), 0).ok(),
+        }
+    }
+    /// ## fn capture_namespace
+    /// 标记函数帧被闭包引用
+    pub fn capture_namespace(&mut self, namespace: &Namespace) {
+        if let Some(x) = self.deref_namespace(namespace) {
+            x.captured = true;
+        }
+    }
+    fn release_namespace(&mut self, inter_namespace: InterNamespace) {
+        for uuid in inter_namespace.namespace.values() {
+            self.variable_pool.del_variable(*uuid);
+        }
+        for sub in inter_namespace.sub.into_values() {
+            self.release_namespace(sub);
+        }
+    }
+    /// ## fn local_variables
+    /// 命名空间中绑定的所有变量，按名字排序
+    pub fn local_variables(&mut self, namespace: &Namespace) -> Vec<(String, PyObject)> {
+        let ids: Vec<(String, Uuid)> = match self.deref_namespace(namespace) {
+            None => return vec![],
+            Some(x) => x.namespace.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+        };
+        let mut variables: Vec<(String, PyObject)> = ids
+            .into_iter()
+            .filter_map(|(id, uuid)| Some((id, self.variable_pool.get_value(uuid)?)))
+            .collect();
+        variables.sort_by(|x, y| x.0.cmp(&y.0));
+        variables
+    }
+    /// ## fn delete_local_namespace
+    /// 函数返回后删除它的帧，被闭包引用的帧会保留
+    pub fn delete_local_namespace(&mut self, namespace: &Namespace) {
+        if self.deref_namespace(namespace).is_none_or(|x| x.captured) {
+            return;
+        }
+        self.remove_namespace(namespace);
+    }
+    /// ## fn remove_namespace
+    /// 不管是否被引用都删除命名空间，用于类体
+    pub fn remove_namespace(&mut self, namespace: &Namespace) {
+        let mut path = namespace.path();
+        let id = path.pop().unwrap();
+        let removed = if path.is_empty() {
+            self.enclosing_namespace.remove(&id)
+        } else {
+            let parent = Namespace::Local(path);
+            self.deref_namespace(&parent).and_then(|x| x.sub.remove(&id))
+        };
+        if let Some(x) = removed {
+            self.release_namespace(x);
+        }
     }
 }
 #[allow(dead_code)]
@@ -347,3 +506,23 @@ pub enum Namespace {
     Enclosing(String),
     Local(Vec<String>),
 }
+impl Namespace {
+    /// 函数帧的路径，第一个是enclosing的id，Builtin和Global为空
+    pub fn path(&self) -> Vec<String> {
+        match self {
+            Namespace::Builtin | Namespace::Global => vec![],
+            Namespace::Enclosing(x) => vec![x.clone()],
+            Namespace::Local(x) => x.clone(),
+        }
+    }
+    /// 在此命名空间中嵌套一层新的函数帧
+    pub fn child(&self, id: String) -> Namespace {
+        let mut path = self.path();
+        path.push(id);
+        if path.len() == 1 {
+            Namespace::Enclosing(path.remove(0))
+        } else {
+            Namespace::Local(path)
+        }
+    }
+}
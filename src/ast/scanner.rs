@@ -1,8 +1,13 @@
 use std::collections::HashMap;
 
-use crate::ast::scanner::TokenType::{BangEqual, Comma, Dot, EqualEqual, ExactDivision, GreaterEqual, In, Is, LeftBrace, LeftParen, LessEqual, LineBreak, Minus, Mod, Plus, Pow, RightBrace, RightParen, Semicolon, Slash, Star, AND, BANG, CLASS, COLON, DEF, ELSE, EQUAL, FALSE, FOR, GREATER, IDENTIFIER, IF, LAMBDA, LESS, NOT, NUMBER, OR, PRINT, RETURN, SPACE, STRING, TAB, TRUE, WHILE, ELIF, Break, Continue};
-use crate::{count_char_occurrences, strip_quotes};
-#[allow(dead_code)]
+use crate::ast::error::lex_error::LexError;
+use crate::ast::error::BasicError;
+use unicode_ident::{is_xid_continue, is_xid_start};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::ast::scanner::TokenType::{BangEqual, Comma, Dot, EqualEqual, ExactDivision, GreaterEqual, In, Is, LeftBrace, LeftBracket, LeftParen, LessEqual, NEWLINE, Minus, Mod, Plus, Pow, RightBrace, RightBracket, RightParen, Semicolon, Slash, Star, Tilde, Ampersand, Pipe, Caret, LeftShift, RightShift, At, Arrow, Ellipsis, ColonEqual, PlusEqual, MinusEqual, StarEqual, SlashEqual, ExactDivisionEqual, ModEqual, PowEqual, AtEqual, AmpersandEqual, PipeEqual, CaretEqual, LeftShiftEqual, RightShiftEqual, AND, BANG, CLASS, COLON, DEL, DEF, ELSE, EQUAL, FALSE, FOR, GREATER, IDENTIFIER, IF, LAMBDA, LESS, NOT, NUMBER, OR, PRINT, RETURN, STRING, TRUE, WHILE, ELIF, Break, Continue, TRY, EXCEPT, FINALLY, RAISE, AS, FROM, WITH, IMPORT, YIELD, INDENT, DEDENT};
+/// 关键字的token和关键字一样全部大写，如IF、CLASS
+#[allow(dead_code, clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TokenType {
     // Single-character tokens.
@@ -10,6 +15,8 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
     Dot,
     Minus,
@@ -21,6 +28,31 @@ pub enum TokenType {
     Star,
     COLON,
     Pow,
+    Tilde,
+    Ampersand,
+    Pipe,
+    Caret,
+    LeftShift,
+    RightShift,
+    At,
+    Arrow,
+    Ellipsis,
+    ColonEqual,
+
+    // Augmented assignment.
+    PlusEqual,
+    MinusEqual,
+    StarEqual,
+    SlashEqual,
+    ExactDivisionEqual,
+    ModEqual,
+    PowEqual,
+    AtEqual,
+    AmpersandEqual,
+    PipeEqual,
+    CaretEqual,
+    LeftShiftEqual,
+    RightShiftEqual,
 
     BANG,
     BangEqual,
@@ -41,6 +73,7 @@ pub enum TokenType {
     // Keywords.
     AND,
     CLASS,
+    DEL,
     Break,
     Continue,
     ELSE,
@@ -56,20 +89,34 @@ pub enum TokenType {
     DEF,
     LAMBDA,
     NOT,
+    TRY,
+    EXCEPT,
+    FINALLY,
+    RAISE,
+    AS,
+    FROM,
+    WITH,
+    IMPORT,
+    YIELD,
     PRINT,
 
-    SPACE,
-    TAB,
+    INDENT,
+    DEDENT,
+    NEWLINE,
     EOF,
     None,
-    LineBreak,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub enum Literal {
     Str(String),
+    Bytes(Vec<u8>),
+    FormatStr(String),
     Float(f64),
     Int(i64),
+    /// 超出i64范围的整数，计算时抛出OverflowError
+    LargeInt,
+    Imaginary(f64),
     Identifier(String),
     #[default]
     None,
@@ -80,25 +127,41 @@ pub struct Token {
     pub(crate) token_type: TokenType,
     pub lineno: usize,
     pub col_offset: usize,
+    pub end_lineno: usize,
+    pub end_col_offset: usize,
     pub(crate) literal: Literal,
     pub(crate) lexeme: String,
+    /// token之前的空白、注释和续行符，只在保留trivia的模式下记录
+    pub trivia: String,
 }
 #[derive(Debug)]
 pub struct Scanner {
     source: String,
     pub(crate) lineno: usize,
     pub(crate) col_offset: usize,
+    /// 当前token开始的行，多行字符串的开始行和结束行不同
+    start_lineno: usize,
     pub(crate) end_lineno: usize,
     pub(crate) end_col_offset: usize,
     current_char: String,
     pub(crate) token: Vec<Token>,
     lexeme: String,
     checker: Checker,
+    /// 缩进栈，(col, altcol)，col按制表符宽度8计算，altcol按制表符宽度1计算
+    indent_stack: Vec<(usize, usize)>,
+    /// 未闭合的括号，(括号, 行, 列)，括号内的换行不会产生NEWLINE
+    brackets: Vec<(char, usize, usize)>,
+    /// 上一行以反斜杠结尾，与下一行连接为同一逻辑行
+    line_continuation: bool,
+    pub(crate) errors: Vec<LexError>,
+    /// 保留trivia，token的lexeme是源码中的原文
+    keep_trivia: bool,
+    /// 紧跟在数字后面的标识符，(行, 列, 错误信息)，它只能是`1if x else 2`中的关键字
+    number_end: Option<(usize, usize, &'static str)>,
 }
 #[derive(Debug)]
 enum CheckMethod {
     InLine,
-    Next,
     All,
 }
 #[derive(Debug)]
@@ -117,13 +180,14 @@ pub struct Checker {
     check_for: CheckFor,
 }
 pub fn build_scanner(source: String) -> Scanner {
-    let end_lineno = source.lines().count() - 1;
-    let end = source.lines().last().unwrap().len();
+    let end_lineno = source.lines().count().saturating_sub(1);
+    let end = source.lines().last().unwrap_or("").len();
     let end_col_offset = if end >= 1 { end - 1 } else { end };
     Scanner {
         source,
         lineno: 0,
         col_offset: 0,
+        start_lineno: 0,
         end_lineno,
         end_col_offset,
         current_char: "".parse().unwrap(),
@@ -135,6 +199,19 @@ pub fn build_scanner(source: String) -> Scanner {
             is_checked: true,
             check_for: CheckFor::Normal,
         },
+        indent_stack: vec![(0, 0)],
+        brackets: vec![],
+        line_continuation: false,
+        errors: vec![],
+        keep_trivia: false,
+        number_end: None,
+    }
+}
+impl Scanner {
+    // Builder
+    pub fn trivia(mut self, keep_trivia: bool) -> Self {
+        self.keep_trivia = keep_trivia;
+        self
     }
 }
 impl Scanner {
@@ -149,71 +226,215 @@ impl Scanner {
                     self.add_for_number();
                     self.checker.is_checked = true
                 }
-                _ => throw_error(self.lineno, self.col_offset + 1, "Unexpected token"),
+                CheckFor::String => {
+                    let quote = self.checker.current_check_char.clone();
+                    if self.quoted() == quote.repeat(2) {
+                        // two quotes at the end of line are an empty string rather than a multi lines string
+                        self.add_for_string();
+                    } else if count_trailing_backslash(&self.lexeme) % 2 == 1 {
+                        // a backslash at the end of line continues the string on the next line
+                        self.lexeme += "\n";
+                    } else {
+                        self.throw_error(self.start_lineno, self.col_offset, "unterminated string literal");
+                        self.checker.is_checked = true
+                    }
+                }
+                CheckFor::Normal => {
+                    self.checker.is_checked = true;
+                    self.recognize_token()
+                }
             },
             CheckMethod::All => self.lexeme += "\n",
-            _ => {}
         }
     }
     fn add_for_number(&mut self) {
         self.checker.is_checked = true;
-        if self.lexeme.contains(".") {
-            let float: f64 = format!("0{}", self.lexeme).parse::<f64>().unwrap();
-            self.add_token_with_literal(NUMBER, Literal::Float(float))
-        } else {
-            let int: i64 = format!("0{}", self.lexeme).parse::<i64>().unwrap();
-            self.add_token_with_literal(NUMBER, Literal::Int(int))
+        if self.lexeme == "." {
+            self.recognize_token();
+            return;
+        }
+        match parse_number(&self.lexeme) {
+            Ok(literal) => self.add_token_with_literal(NUMBER, literal),
+            Err(message) => self.throw_error(self.lineno, self.col_offset, message),
         }
     }
     fn check_for_number(&mut self, char: &char, string_char: String) -> bool {
-        if self.checker.current_check_char == "." && !('0'..'9').contains(&char) {
-            self.checker.is_checked = true;
-            self.recognize_token();
+        if self.checker.current_check_char == "." && !char.is_ascii_digit() {
+            // not a number like .5, so it is an operator like . or ...
+            self.checker.check_for = CheckFor::Normal;
+            return self.check_for_others(string_char);
         } else {
             self.checker.current_check_char = String::from("");
-            if ('0'..'9').contains(&char)
-                || (string_char == "." && count_char_occurrences!(self.lexeme, '.') < 1)
-            {
+            // Take everything which may belong to the literal, parse_number decides whether it is valid
+            if self.extends_number(*char) {
                 self.lexeme += string_char.as_str();
                 return true;
-            } else {
-                self.checker.is_checked = true;
-                self.add_for_number();
+            }
+            if is_identifier_continue(*char) && self.is_exponent_sign() {
+                // the e of 1else is the beginning of the keyword rather than an exponent
+                let exponent = self.lexeme.pop().unwrap_or('e');
+                let col_offset = self.col_offset + self.lexeme.chars().count();
+                self.end_number();
+                self.lexeme = format!("{}{}", exponent, string_char);
+                self.col_offset = col_offset;
+                self.build_checker(String::from(""), CheckMethod::InLine, CheckFor::Identifier);
+                return true;
+            }
+            self.end_number();
+            if !is_identifier_start(*char) {
+                self.number_end = None;
             }
         }
         false
     }
-    fn check_for_string(&mut self) -> bool {
-        //we all know that python has two string definition token, " " and """ """(multi lines)
-        // """ situation
-        if self.lexeme.starts_with("\"\"") && self.lexeme.len() == 2 {
-            return true;
-        } else if self.lexeme.starts_with("\"\"\"") && self.lexeme.len() == 3 {
-            self.checker.check_method = CheckMethod::All;
+    /// 字符是否可能属于正在扫描的数字，数字后面的其他字母开始一个新的token
+    fn extends_number(&self, char: char) -> bool {
+        let lexeme = self.lexeme.to_ascii_lowercase();
+        let char = char.to_ascii_lowercase();
+        match char {
+            '0'..='9' | '_' => true,
+            '.' => lexeme.chars().all(|x| x.is_ascii_digit() || x == '_'),
+            '+' | '-' => self.is_exponent_sign(),
+            'x' | 'o' | 'b' if lexeme == "0" => true,
+            _ if lexeme.starts_with("0x") => char.is_ascii_hexdigit(),
+            _ if lexeme.starts_with("0o") || lexeme.starts_with("0b") || lexeme.ends_with('j') => false,
+            'e' => !lexeme.contains('e'),
+            'j' => true,
+            _ => false,
+        }
+    }
+    fn is_exponent_sign(&self) -> bool {
+        let lexeme = self.lexeme.to_ascii_lowercase();
+        lexeme.ends_with('e') && !lexeme.starts_with("0x")
+    }
+    /// 添加数字，并记录数字的位置，后面紧跟的标识符不是关键字时报错
+    fn end_number(&mut self) {
+        let errors = self.errors.len();
+        self.add_for_number();
+        let lexeme = self.lexeme.to_ascii_lowercase();
+        let message = if lexeme.starts_with("0x") {
+            "invalid hexadecimal literal"
+        } else if lexeme.starts_with("0o") {
+            "invalid octal literal"
+        } else if lexeme.starts_with("0b") {
+            "invalid binary literal"
+        } else if lexeme.ends_with('j') {
+            "invalid imaginary literal"
+        } else {
+            "invalid decimal literal"
+        };
+        // an invalid number is reported only once
+        self.number_end = (self.errors.len() == errors).then_some((self.lineno, self.col_offset, message));
+    }
+    /// 去掉前缀后的字符串，包括引号
+    fn quoted(&self) -> &str {
+        let quote = self.checker.current_check_char.chars().next().unwrap_or('"');
+        match self.lexeme.find(quote) {
+            None => "",
+            Some(index) => &self.lexeme[index..],
+        }
+    }
+    fn check_for_string(&mut self, string_char: String) -> bool {
+        // python has four string delimiters, ' " and the multi lines version of them
+        let quote = self.checker.current_check_char.clone();
+        let quoted = self.quoted().to_string();
+        if quoted == quote {
+            self.lexeme += string_char.as_str();
             return true;
         }
-
         match self.checker.check_method {
             CheckMethod::All => {
-                if !self.lexeme.ends_with("\"\"\"") {
-                    return true;
+                self.lexeme += string_char.as_str();
+                let quoted = self.quoted();
+                let closing = quote.repeat(3);
+                if quoted.len() >= 6
+                    && quoted.ends_with(closing.as_str())
+                    && count_trailing_backslash(&quoted[..quoted.len() - 3]).is_multiple_of(2)
+                {
+                    self.add_for_string();
                 }
+                true
+            }
+            _ => {
+                if quoted == quote.repeat(2) {
+                    if string_char == quote {
+                        self.lexeme += string_char.as_str();
+                        self.checker.check_method = CheckMethod::All;
+                        return true;
+                    }
+                    // Only an empty string, the current char belongs to the next token
+                    self.add_for_string();
+                    return false;
+                }
+                let escaped = count_trailing_backslash(&self.lexeme) % 2 == 1;
+                self.lexeme += string_char.as_str();
+                if string_char == quote && !escaped {
+                    self.add_for_string();
+                }
+                true
             }
-            _ => {}
         }
-        // " situation
-        self.add_for_string();
-        false
     }
     fn add_for_string(&mut self) {
-        self.add_token_with_literal(
-            STRING,
-            Literal::Str(String::from(strip_quotes!(self.lexeme))),
-        );
         self.checker.is_checked = true;
+        let quote = self.checker.current_check_char.clone();
+        let prefix = self.lexeme[..self.lexeme.len() - self.quoted().len()].to_ascii_lowercase();
+        let quoted = self.quoted();
+        let body = if quoted.len() >= 6 && quoted.starts_with(quote.repeat(3).as_str()) {
+            &quoted[3..quoted.len() - 3]
+        } else {
+            &quoted[1..quoted.len() - 1]
+        };
+        let raw = prefix.contains('r');
+        let units = if raw {
+            Ok(body.chars().map(|x| x as u32).collect())
+        } else {
+            decode_escapes(body, prefix.contains('b'))
+        };
+        let literal = match units {
+            Err(message) => {
+                self.throw_error(self.start_lineno, self.col_offset, message.as_str());
+                return;
+            }
+            Ok(units) => {
+                if prefix.contains('b') {
+                    if !body.is_ascii() {
+                        self.throw_error(
+                            self.start_lineno,
+                            self.col_offset,
+                            "bytes can only contain ASCII literal characters",
+                        );
+                        return;
+                    }
+                    Literal::Bytes(units.into_iter().map(|x| x as u8).collect())
+                } else {
+                    let string: String = units
+                        .into_iter()
+                        .map(|x| char::from_u32(x).unwrap_or(char::REPLACEMENT_CHARACTER))
+                        .collect();
+                    if prefix.contains('f') {
+                        Literal::FormatStr(string)
+                    } else {
+                        Literal::Str(string)
+                    }
+                }
+            }
+        };
+        self.add_token_with_literal(STRING, literal);
     }
     fn check_for_identifier(&mut self, char: &char, string_char: String) -> bool {
-        if ('a'..'z').contains(&char) || ('A'..'Z').contains(&char) || char.clone() == '_' {
+        if (*char == '"' || *char == '\'')
+            && STRING_PREFIXES.contains(&self.lexeme.to_ascii_lowercase().as_str())
+        {
+            // the identifier is a string prefix like r or rb
+            if let Some((lineno, col_offset, message)) = self.number_end.take() {
+                self.throw_error(lineno, col_offset, message);
+            }
+            self.build_checker(string_char.clone(), CheckMethod::InLine, CheckFor::String);
+            self.lexeme += string_char.as_str();
+            return true;
+        }
+        if is_identifier_continue(*char) {
             self.lexeme += string_char.as_str();
             return true;
         } else {
@@ -222,70 +443,65 @@ impl Scanner {
         false
     }
     fn add_for_identifier(&mut self) {
+        if let Some((lineno, col_offset, message)) = self.number_end.take() {
+            // like CPython, 1if x else 2 is accepted while 1abc is not
+            if !NUMBER_SUFFIX_KEYWORDS.contains(&self.lexeme.as_str()) {
+                self.throw_error(lineno, col_offset, message);
+            }
+        }
         if !self.recognize_keywords() {
-            self.add_token_with_literal(IDENTIFIER, Literal::Identifier(self.lexeme.clone()));
+            // identifiers are compared in NFKC form, so ﬁ and fi are the same name (PEP 3131)
+            let name: String = self.lexeme.nfkc().collect();
+            if !name.starts_with(is_identifier_start) || !name.chars().all(is_identifier_continue) {
+                self.throw_error(self.lineno, self.col_offset, "invalid character in identifier");
+            }
+            self.add_token_with_literal(IDENTIFIER, Literal::Identifier(name));
         }
         self.checker.is_checked = true
     }
     fn check_for_others(&mut self, string_char: String) -> bool {
-        if self.checker.current_check_char == string_char {
-            self.lexeme += string_char.as_str();
-            match self.checker.check_for {
-                CheckFor::String => {
-                    if self.check_for_string() {
-                        return true;
-                    }
-                }
-                CheckFor::Normal => {
-                    self.recognize_token();
-                    self.checker.is_checked = true;
-                }
-                _ => {}
-            }
+        // longest match, take the char while the lexeme is still the beginning of an operator
+        let lexeme = self.lexeme.clone() + string_char.as_str();
+        if OPERATORS.iter().any(|(x, _)| x.starts_with(lexeme.as_str())) {
+            self.lexeme = lexeme;
             return true;
-        } else {
-            match self.checker.check_method {
-                CheckMethod::Next => {
-                    self.checker.is_checked = true;
-                    self.recognize_token();
-                }
-                _ => {
-                    self.lexeme += string_char.as_str();
-                    return true;
-                }
-            }
         }
+        self.checker.is_checked = true;
+        self.recognize_token();
         false
     }
     fn build_checker_for_others(&mut self, char: &char) -> bool {
-        if ('0'..='9').contains(&char) {
+        if char.is_ascii_digit() {
             self.build_checker(String::from(""), CheckMethod::InLine, CheckFor::Number);
             return true;
-        } else if ('a'..='z').contains(char) || ('A'..='Z').contains(char) || char.clone() == '_' {
+        } else if is_identifier_start(*char) {
             self.build_checker(String::from(""), CheckMethod::InLine, CheckFor::Identifier);
             return true;
         }
         false
     }
-    fn check_for_all(&mut self, char: &char, string_char: &String) -> bool {
+    fn check_for_all(&mut self, char: &char, string_char: &str) -> bool {
         match self.checker.check_for {
             CheckFor::Number => {
-                if self.check_for_number(&char, string_char.clone()) {
+                if self.check_for_number(char, string_char.to_string()) {
                     return true;
                 }
             }
             CheckFor::Identifier => {
-                if self.check_for_identifier(&char, string_char.clone()) {
+                if self.check_for_identifier(char, string_char.to_string()) {
+                    return true;
+                }
+            }
+            CheckFor::String => {
+                if self.check_for_string(string_char.to_string()) {
                     return true;
                 }
             }
             _ => {
                 /*
-                if not number, we also have two patterns,string and normal.
                 Normal is designed for checking operator(like <=).
-                String is designed for string literal
                 */
-                if self.check_for_others(string_char.clone()) {
+                if self.check_for_others(string_char.to_string()) {
                     return true;
                 }
             }
@@ -295,78 +511,127 @@ impl Scanner {
     fn build_checker_for_normal(&mut self, char: &char) -> (bool, bool) {
         // first bool is to continue char, second bool is to continue line
         match self.current_char.as_str() {
-            "<" => {
-                self.build_checker(String::from("="), CheckMethod::Next, CheckFor::Normal);
-                return (true, false);
-            }
-            "=" => {
-                self.build_checker(String::from("="), CheckMethod::Next, CheckFor::Normal);
-                return (true, false);
-            }
-            "!" => {
-                self.build_checker(String::from("="), CheckMethod::Next, CheckFor::Normal);
-                return (true, false);
-            }
-            ">" => {
-                self.build_checker(String::from("="), CheckMethod::Next, CheckFor::Normal);
-                return (true, false);
-            }
-            "/" => {
-                self.build_checker(String::from("/"), CheckMethod::Next, CheckFor::Normal);
-                return (true, false);
-            }
             "#" => return (true, true),
             "\r" => return (true, false),
-            "\"" => {
-                self.build_checker(String::from("\""), CheckMethod::InLine, CheckFor::String);
+            "\"" | "'" => {
+                self.build_checker(self.current_char.clone(), CheckMethod::InLine, CheckFor::String);
                 return (true, false);
             }
             "." => {
                 self.build_checker(String::from("."), CheckMethod::InLine, CheckFor::Number);
                 return (true, false);
             }
-            "*" => {
-                self.build_checker(String::from("*"), CheckMethod::Next, CheckFor::Normal);
-                return (true, false);
-            }
             _ => {
-                if self.build_checker_for_others(&char) {
+                if self.build_checker_for_others(char) {
+                    return (true, false);
+                }
+                // the char may be the beginning of a longer operator like <<=
+                if OPERATORS.iter().any(|(x, _)| x.len() > 1 && x.starts_with(*char)) {
+                    self.build_checker(String::from(""), CheckMethod::InLine, CheckFor::Normal);
                     return (true, false);
                 }
             }
         }
-        return (false, false);
+        (false, false)
     }
 
-    fn check_indent(&mut self, mut indent: bool, string_char: String) -> (bool, bool) {
-        // first bool is bool indent's value, second is whether the loop continue
-        if string_char != " " && string_char != "\t" {
-            indent = false
-        }
-        if indent {
-            if string_char == " " {
-                self.add_token(SPACE);
-            } else if string_char == "\t" {
-                self.add_token(TAB);
-            }
-            return (indent, true);
+    fn check_indent(&mut self, line: &str) {
+        // Like CPython, a tab moves col to the next multiple of 8 while altcol treats it as 1,
+        // so the two only disagree when tabs and spaces are mixed inconsistently
+        let mut col = 0;
+        let mut altcol = 0;
+        for char in line.chars() {
+            match char {
+                ' ' => {
+                    col += 1;
+                    altcol += 1;
+                }
+                '\t' => {
+                    col = (col / 8 + 1) * 8;
+                    altcol += 1;
+                }
+                '\x0c' => {
+                    col = 0;
+                    altcol = 0;
+                }
+                _ => break,
+            }
+        }
+        let whitespace: String = line
+            .chars()
+            .take_while(|x| *x == ' ' || *x == '\t' || *x == '\x0c')
+            .collect();
+        let (top_col, top_altcol) = *self.indent_stack.last().unwrap();
+        if col == top_col {
+            if altcol != top_altcol {
+                self.throw_tab_error(whitespace.len());
+            }
+        } else if col > top_col {
+            if altcol <= top_altcol {
+                self.throw_tab_error(whitespace.len());
+            }
+            self.indent_stack.push((col, altcol));
+            self.col_offset = 0;
+            self.lexeme = whitespace.clone();
+            self.add_token(INDENT);
         } else {
-            if string_char == " " || string_char == "\t" {
-                return (indent, true);
+            while self.indent_stack.len() > 1 && self.indent_stack.last().unwrap().0 > col {
+                self.indent_stack.pop();
+                self.col_offset = whitespace.len();
+                self.lexeme = "".to_string();
+                self.add_token(DEDENT);
+            }
+            let (top_col, top_altcol) = *self.indent_stack.last().unwrap();
+            if col != top_col {
+                self.throw_indentation_error(
+                    whitespace.len(),
+                    "IndentationError",
+                    "unindent does not match any outer indentation level",
+                );
+            } else if altcol != top_altcol {
+                self.throw_tab_error(whitespace.len());
             }
         }
-        (indent, false)
+    }
+    fn throw_tab_error(&mut self, col_offset: usize) {
+        self.throw_indentation_error(
+            col_offset,
+            "TabError",
+            "inconsistent use of tabs and spaces in indentation",
+        )
+    }
+    fn throw_indentation_error(&mut self, col_offset: usize, kind: &'static str, message: &str) {
+        let error = self.lex_error(self.lineno, col_offset, message).kind(kind);
+        self.errors.push(error)
     }
 }
 impl Scanner {
-    pub fn scan(&mut self) {
+    /// ## fn scan
+    /// 扫描全部源码，有词法错误时返回所有错误
+    pub fn scan(&mut self) -> Result<Vec<Token>, Vec<LexError>> {
         let binding = self.source.clone();
         let lines: Vec<&str> = binding.lines().collect();
-        let mut indent: bool;
         'line: for (lineno, line) in lines.iter().enumerate() {
             self.lineno = lineno;
-            indent = true;
-            self.col_offset = 0;
+            // a multi lines string keeps the position where it starts
+            if self.checker.is_checked {
+                self.start_lineno = lineno;
+                self.col_offset = 0;
+            }
+            // a line inside brackets or after a backslash continues the logical line
+            let joined = !self.brackets.is_empty() || self.line_continuation;
+            self.line_continuation = false;
+            // Only the start of a logical line can change the indentation level,
+            // blank lines and comment-only lines are ignored just like CPython
+            if self.checker.is_checked {
+                let content = line.trim_start_matches([' ', '\t', '\x0c']);
+                if content.is_empty() || content.starts_with('#') {
+                    continue 'line;
+                }
+                if !joined {
+                    self.check_indent(line);
+                }
+            }
             'char: for (col_offset, char) in line.chars().enumerate() {
                 let string_char = char.to_string();
                 // handling multi chars
@@ -377,19 +642,26 @@ impl Scanner {
                         continue;
                     }
                 }
-                // Handling indentation in string
-                let tmp_indent = self.check_indent(indent, string_char.clone());
-                indent = tmp_indent.0;
-                if tmp_indent.1 {
+                // whitespace only separates tokens
+                if string_char == " " || string_char == "\t" || string_char == "\x0c" {
                     continue;
                 }
+                if char == '\\' {
+                    if col_offset + 1 == line.trim_end_matches('\r').chars().count() {
+                        self.line_continuation = true;
+                    } else {
+                        self.throw_error(lineno, col_offset, "unexpected character after line continuation character");
+                    }
+                    break 'char;
+                }
                 self.lexeme = string_char;
                 self.col_offset = col_offset;
+                self.start_lineno = lineno;
                 self.current_char = char.to_string();
                 let continued = self.build_checker_for_normal(&char);
                 if continued.0 {
                     if continued.1 {
-                        continue 'line;
+                        break 'char;
                     }
                     continue 'char;
                 }
@@ -398,15 +670,18 @@ impl Scanner {
             if !self.checker.is_checked {
                 self.line_checker()
             }
-            if self.checker.is_checked {
+            if self.checker.is_checked && self.brackets.is_empty() && !self.line_continuation {
+                self.start_lineno = lineno;
+                self.col_offset = line.chars().count();
                 self.lexeme = "\n".to_string();
-                self.add_token(LineBreak)
+                self.add_token(NEWLINE)
             }
         }
         if !self.checker.is_checked {
             match self.checker.check_for {
                 CheckFor::String => {
-                    self.add_for_string();
+                    self.checker.is_checked = true;
+                    self.throw_error(self.start_lineno, self.col_offset, "unterminated triple-quoted string literal");
                 }
                 CheckFor::Number => self.add_for_number(),
                 CheckFor::Identifier => {
@@ -417,54 +692,102 @@ impl Scanner {
                     self.recognize_token()
                 }
             }
+            self.start_lineno = self.lineno;
+            self.col_offset = self.source.lines().last().unwrap_or("").chars().count();
+            self.lexeme = "".to_string();
+            self.add_token(NEWLINE)
+        }
+        if let Some((bracket, lineno, col_offset)) = self.brackets.last().copied() {
+            self.throw_error(lineno, col_offset, format!("'{}' was never closed", bracket).as_str());
+        } else if self.line_continuation {
+            self.throw_error(self.lineno, self.col_offset, "unexpected EOF while parsing");
+        }
+        // close every block which is still open at the end of file,
+        // an empty source has no line so the end is on the first line
+        self.lineno = match self.source.is_empty() {
+            true => 0,
+            false => self.end_lineno + 1,
+        };
+        self.start_lineno = self.lineno;
+        self.col_offset = 0;
+        self.lexeme = "".to_string();
+        while self.indent_stack.len() > 1 {
+            self.indent_stack.pop();
+            self.add_token(DEDENT);
+        }
+        self.add_token(TokenType::EOF);
+        if self.keep_trivia {
+            self.attach_trivia();
+        }
+        if self.errors.is_empty() {
+            Ok(self.token.clone())
+        } else {
+            Err(self.errors.clone())
         }
-        self.token.push(Token {
-            token_type: TokenType::EOF,
-            lineno: self.end_lineno,
-            col_offset: self.col_offset + 1,
-            literal: Literal::None,
-            lexeme: "".to_string(),
-        })
     }
     fn recognize_token(&mut self) {
-        let token_lists = vec![
-            ("(".to_string(), LeftParen),
-            (")".to_string(), RightParen),
-            ("{".to_string(), LeftBrace),
-            ("}".to_string(), RightBrace),
-            (",".to_string(), Comma),
-            ("+".to_string(), Plus),
-            ("-".to_string(), Minus),
-            ("*".to_string(), Star),
-            ("%".to_string(), Mod),
-            (";".to_string(), Semicolon),
-            (".".to_string(), Dot),
-            (":".to_string(), COLON),
-            ("<".to_string(), LESS),
-            ("!".to_string(), BANG),
-            ("/".to_string(), Slash),
-            ("<=".to_string(), LessEqual),
-            ("!=".to_string(), BangEqual),
-            ("=".to_string(), EQUAL),
-            ("==".to_string(), EqualEqual),
-            (">".to_string(), GREATER),
-            (">=".to_string(), GreaterEqual),
-            ("//".to_string(), ExactDivision),
-            ("**".to_string(), Pow),
-        ];
-        let token_map: HashMap<String, TokenType> = token_lists.into_iter().collect();
-        match token_map.get(&self.lexeme.clone()) {
-            None => throw_error(self.lineno, self.col_offset, "Unexpected Character"),
-            Some(token) => {
-                self.add_token(token.clone());
+        // the lexeme is split with longest match, ".." is not an operator but two dots
+        let lexeme = self.lexeme.clone();
+        let col_offset = self.col_offset;
+        let mut rest = lexeme.as_str();
+        while !rest.is_empty() {
+            let operator = OPERATORS
+                .iter()
+                .filter(|(x, _)| rest.starts_with(x))
+                .max_by_key(|(x, _)| x.len());
+            match operator {
+                None => {
+                    self.throw_error(self.lineno, self.col_offset, "Unexpected Character");
+                    break;
+                }
+                Some((operator, token)) => {
+                    self.lexeme = operator.to_string();
+                    self.add_token(*token);
+                    self.match_bracket(*token);
+                    self.col_offset += operator.chars().count();
+                    rest = &rest[operator.len()..];
+                }
             }
+        }
+        self.lexeme = lexeme;
+        self.col_offset = col_offset;
+    }
+    fn match_bracket(&mut self, token_type: TokenType) {
+        let (opening, closing) = match token_type {
+            LeftParen | LeftBracket | LeftBrace => {
+                let bracket = self.lexeme.chars().next().unwrap();
+                self.brackets.push((bracket, self.lineno, self.col_offset));
+                return;
+            }
+            RightParen => ('(', ')'),
+            RightBracket => ('[', ']'),
+            RightBrace => ('{', '}'),
+            _ => return,
         };
+        match self.brackets.pop() {
+            None => self.throw_error(
+                self.lineno,
+                self.col_offset,
+                format!("unmatched '{}'", closing).as_str(),
+            ),
+            Some((bracket, _, _)) if bracket != opening => self.throw_error(
+                self.lineno,
+                self.col_offset,
+                format!(
+                    "closing parenthesis '{}' does not match opening parenthesis '{}'",
+                    closing, bracket
+                )
+                .as_str(),
+            ),
+            _ => {}
+        }
     }
 
     fn recognize_keywords(&mut self) -> bool {
         let keyword_list = vec![
             ("and".to_string(), AND),
             ("class".to_string(), CLASS),
+            ("del".to_string(), DEL),
             ("else".to_string(), ELSE),
             ("False".to_string(), FALSE),
             ("for".to_string(), FOR),
@@ -474,6 +797,7 @@ impl Scanner {
             ("or".to_string(), OR),
             ("return".to_string(), RETURN),
             ("True".to_string(), TRUE),
+            ("None".to_string(), TokenType::None),
             ("while".to_string(), WHILE),
             ("not".to_string(), NOT),
             ("is".to_string(), Is),
@@ -483,17 +807,26 @@ impl Scanner {
             ("elif".to_string(), ELIF),
             ("break".to_string(), Break),
             ("continue".to_string(), Continue),
+            ("try".to_string(), TRY),
+            ("except".to_string(), EXCEPT),
+            ("finally".to_string(), FINALLY),
+            ("raise".to_string(), RAISE),
+            ("as".to_string(), AS),
+            ("from".to_string(), FROM),
+            ("with".to_string(), WITH),
+            ("import".to_string(), IMPORT),
+            ("yield".to_string(), YIELD),
             ("elif".to_string(), ELIF),
             ("print".to_string(), PRINT), // Tmp
         ];
         let keywords_map: HashMap<String, TokenType> = keyword_list.into_iter().collect();
-        return match keywords_map.get(&self.lexeme.clone()) {
+        match keywords_map.get(&self.lexeme.clone()) {
             None => false,
             Some(token) => {
-                self.add_token(token.clone());
+                self.add_token(*token);
                 true
             }
-        };
+        }
     }
     fn build_checker(&mut self, check_str: String, check_method: CheckMethod, check_for: CheckFor) {
         self.checker = Checker {
@@ -504,25 +837,295 @@ impl Scanner {
         }
     }
 
+    /// 记录错误后继续扫描，出错的字符或token会被跳过
+    fn throw_error(&mut self, line: usize, col_offset: usize, message: &str) {
+        let error = self.lex_error(line, col_offset, message);
+        self.errors.push(error)
+    }
+    fn lex_error(&self, line: usize, col_offset: usize, message: &str) -> LexError {
+        LexError::new(
+            BasicError::default()
+                .lineno(line as u64)
+                .col_offset(col_offset as u64)
+                .lexeme(self.lexeme.clone()),
+            message,
+        )
+    }
     fn add_token(&mut self, token_type: TokenType) {
-        self.token.push(Token {
-            token_type,
-            lineno: self.lineno,
-            col_offset: self.col_offset,
-            literal: Literal::None,
-            lexeme: self.lexeme.clone(),
-        })
+        self.add_token_with_literal(token_type, Literal::None)
     }
     fn add_token_with_literal(&mut self, token_type: TokenType, literal: Literal) {
+        // a multi lines token ends at the line of its last line break
+        let (end_lineno, end_col_offset) = match self.lexeme.rfind('\n') {
+            None => (self.start_lineno, self.col_offset + self.lexeme.chars().count()),
+            Some(index) => (
+                self.start_lineno + self.lexeme.matches('\n').count(),
+                self.lexeme[index + 1..].chars().count(),
+            ),
+        };
         self.token.push(Token {
             token_type,
-            lineno: self.lineno,
+            lineno: self.start_lineno,
             col_offset: self.col_offset,
+            end_lineno,
+            end_col_offset,
             literal,
             lexeme: self.lexeme.clone(),
+            trivia: "".to_string(),
         })
     }
+    /// 用token的位置从源码中取出lexeme和之前的trivia
+    fn attach_trivia(&mut self) {
+        let mut line_starts = vec![0];
+        line_starts.extend(self.source.match_indices('\n').map(|(index, _)| index + 1));
+        let source = self.source.as_str();
+        let offset = |lineno: usize, col_offset: usize| match line_starts.get(lineno) {
+            None => source.len(),
+            Some(start) => source[*start..]
+                .char_indices()
+                .nth(col_offset)
+                .map_or(source.len(), |(index, _)| start + index),
+        };
+        let mut end = 0;
+        for token in self.token.iter_mut() {
+            let token_start = offset(token.lineno, token.col_offset).max(end);
+            let token_end = offset(token.end_lineno, token.end_col_offset).max(token_start);
+            token.trivia = source[end..token_start].to_string();
+            token.lexeme = source[token_start..token_end].to_string();
+            end = token_end;
+        }
+    }
+}
+/// 标识符的第一个字符，XID_Start或下划线，兼容字符按NFKC规范化后判断
+fn is_identifier_start(char: char) -> bool {
+    char == '_' || is_xid_start(char) || char.to_string().nfkc().next().is_some_and(is_xid_start)
+}
+/// 标识符的后续字符，XID_Continue，兼容字符按NFKC规范化后判断
+fn is_identifier_continue(char: char) -> bool {
+    is_xid_continue(char) || char.to_string().nfkc().all(is_xid_continue)
+}
+/// 运算符和分隔符
+const OPERATORS: [(&str, TokenType); 48] = [
+    ("(", LeftParen),
+    (")", RightParen),
+    ("{", LeftBrace),
+    ("}", RightBrace),
+    ("[", LeftBracket),
+    ("]", RightBracket),
+    (",", Comma),
+    (";", Semicolon),
+    (".", Dot),
+    ("...", Ellipsis),
+    (":", COLON),
+    (":=", ColonEqual),
+    ("->", Arrow),
+    ("+", Plus),
+    ("-", Minus),
+    ("*", Star),
+    ("**", Pow),
+    ("/", Slash),
+    ("//", ExactDivision),
+    ("%", Mod),
+    ("@", At),
+    ("~", Tilde),
+    ("&", Ampersand),
+    ("|", Pipe),
+    ("^", Caret),
+    ("<<", LeftShift),
+    (">>", RightShift),
+    ("<", LESS),
+    (">", GREATER),
+    ("<=", LessEqual),
+    (">=", GreaterEqual),
+    ("==", EqualEqual),
+    ("!=", BangEqual),
+    ("!", BANG),
+    ("=", EQUAL),
+    ("+=", PlusEqual),
+    ("-=", MinusEqual),
+    ("*=", StarEqual),
+    ("/=", SlashEqual),
+    ("//=", ExactDivisionEqual),
+    ("%=", ModEqual),
+    ("**=", PowEqual),
+    ("@=", AtEqual),
+    ("&=", AmpersandEqual),
+    ("|=", PipeEqual),
+    ("^=", CaretEqual),
+    ("<<=", LeftShiftEqual),
+    (">>=", RightShiftEqual),
+];
+/// 可以紧跟在数字后面的关键字，与CPython的verify_end_of_number相同
+const NUMBER_SUFFIX_KEYWORDS: [&str; 8] = ["and", "else", "for", "if", "in", "is", "not", "or"];
+const STRING_PREFIXES: [&str; 8] = ["r", "u", "b", "f", "br", "rb", "fr", "rf"];
+fn count_trailing_backslash(s: &str) -> usize {
+    s.chars().rev().take_while(|x| *x == '\\').count()
+}
+/// 解析转义字符，返回码位，bytes不支持\u,\U和\N{...}
+fn decode_escapes(body: &str, bytes: bool) -> Result<Vec<u32>, String> {
+    let mut units: Vec<u32> = vec![];
+    let mut chars = body.chars().peekable();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            units.push(char as u32);
+            continue;
+        }
+        let escape = match chars.next() {
+            None => {
+                units.push('\\' as u32);
+                break;
+            }
+            Some(x) => x,
+        };
+        match escape {
+            '\n' => {}
+            '\\' | '\'' | '"' => units.push(escape as u32),
+            'a' => units.push(0x07),
+            'b' => units.push(0x08),
+            'f' => units.push(0x0c),
+            'n' => units.push(0x0a),
+            'r' => units.push(0x0d),
+            't' => units.push(0x09),
+            'v' => units.push(0x0b),
+            '0'..='7' => {
+                let mut value = escape.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match chars.peek().and_then(|x| x.to_digit(8)) {
+                        None => break,
+                        Some(digit) => {
+                            value = value * 8 + digit;
+                            chars.next();
+                        }
+                    }
+                }
+                units.push(if bytes { value & 0xff } else { value });
+            }
+            'x' => units.push(take_hex(&mut chars, 2, "x")?),
+            'u' if !bytes => units.push(take_hex(&mut chars, 4, "u")?),
+            'U' if !bytes => {
+                let value = take_hex(&mut chars, 8, "U")?;
+                if char::from_u32(value).is_none() {
+                    return Err("illegal Unicode character".to_string());
+                }
+                units.push(value)
+            }
+            'N' if !bytes => {
+                if chars.next() != Some('{') {
+                    return Err("malformed \\N character escape".to_string());
+                }
+                let name: String = chars.by_ref().take_while(|x| *x != '}').collect();
+                match unicode_names2::character(name.as_str()) {
+                    None => return Err("unknown Unicode character name".to_string()),
+                    Some(x) => units.push(x as u32),
+                }
+            }
+            // unknown escapes are kept as they are
+            _ => {
+                units.push('\\' as u32);
+                units.push(escape as u32);
+            }
+        }
+    }
+    Ok(units)
 }
-pub fn throw_error(line: usize, col_offset: usize, message: &str) {
-    println!("[{}:{}]Error:{}", line + 1, col_offset + 1, message)
+fn take_hex(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+    len: usize,
+    escape: &str,
+) -> Result<u32, String> {
+    let mut value = 0;
+    for _ in 0..len {
+        match chars.peek().and_then(|x| x.to_digit(16)) {
+            None => return Err(format!("truncated \\{}{} escape", escape, "X".repeat(len))),
+            Some(digit) => {
+                value = value * 16 + digit;
+                chars.next();
+            }
+        }
+    }
+    Ok(value)
+}
+/// 检查数字中的下划线，下划线只能单独出现在两个数字之间
+fn check_digits(digits: &str, radix: u32) -> bool {
+    if digits.is_empty() || digits.starts_with('_') || digits.ends_with('_') || digits.contains("__") {
+        return false;
+    }
+    digits.chars().all(|x| x == '_' || x.is_digit(radix))
+}
+fn parse_decimal(lexeme: &str, imaginary: bool) -> Result<Literal, &'static str> {
+    let (mantissa, exponent) = match lexeme.find('e') {
+        None => (lexeme, None),
+        Some(index) => (&lexeme[..index], Some(&lexeme[index + 1..])),
+    };
+    if let Some(exponent) = exponent {
+        let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        if !check_digits(exponent, 10) {
+            return Err("invalid decimal literal");
+        }
+    }
+    let mut parts = mantissa.split('.');
+    let integer = parts.next().unwrap_or("");
+    let fraction = parts.next();
+    if parts.next().is_some() {
+        return Err("invalid decimal literal");
+    }
+    match fraction {
+        None => {
+            if !check_digits(integer, 10) {
+                return Err("invalid decimal literal");
+            }
+        }
+        Some(fraction) => {
+            // "1." and ".5" are both floats, but a dot alone is not a number
+            if (integer.is_empty() && fraction.is_empty())
+                || (!integer.is_empty() && !check_digits(integer, 10))
+                || (!fraction.is_empty() && !check_digits(fraction, 10))
+            {
+                return Err("invalid decimal literal");
+            }
+        }
+    }
+    let digits = lexeme.replace('_', "");
+    if fraction.is_none() && exponent.is_none() && !imaginary {
+        if digits.starts_with('0') && digits.chars().any(|x| x != '0') {
+            return Err("leading zeros in decimal integer literals are not permitted; use an 0o prefix for octal integers");
+        }
+        return Ok(match digits.parse::<i64>() {
+            Ok(int) => Literal::Int(int),
+            Err(_) => Literal::LargeInt,
+        });
+    }
+    match digits.parse::<f64>() {
+        Ok(float) => Ok(Literal::Float(float)),
+        Err(_) => Err("invalid decimal literal"),
+    }
+}
+/// 解析数字字面量，支持0x,0o,0b前缀，下划线分隔，指数和虚数
+pub(crate) fn parse_number(lexeme: &str) -> Result<Literal, &'static str> {
+    let lexeme = lexeme.to_ascii_lowercase();
+    let prefixes = [
+        ("0x", 16, "invalid hexadecimal literal"),
+        ("0o", 8, "invalid octal literal"),
+        ("0b", 2, "invalid binary literal"),
+    ];
+    for (prefix, radix, message) in prefixes {
+        if let Some(digits) = lexeme.strip_prefix(prefix) {
+            // an underscore is also allowed right after the prefix, like 0x_ff
+            let digits = digits.strip_prefix('_').unwrap_or(digits);
+            if !check_digits(digits, radix) {
+                return Err(message);
+            }
+            return Ok(match i64::from_str_radix(&digits.replace('_', ""), radix) {
+                Ok(int) => Literal::Int(int),
+                Err(_) => Literal::LargeInt,
+            });
+        }
+    }
+    if let Some(number) = lexeme.strip_suffix('j') {
+        return match parse_decimal(number, true) {
+            Ok(Literal::Float(float)) => Ok(Literal::Imaginary(float)),
+            _ => Err("invalid imaginary literal"),
+        };
+    }
+    parse_decimal(&lexeme, false)
 }
@@ -1,20 +1,48 @@
 extern crate core;
 
 mod ast;
+#[cfg(test)]
 mod test;
 mod tools;
+// generated by shadow-rs in build.rs
+#[allow(clippy::empty_line_after_doc_comments)]
 pub mod shadow {
     include!(concat!(env!("OUT_DIR"), "/shadow.rs"));
 }
+use std::fs;
+use std::path::PathBuf;
+use std::process::exit;
+
+use crate::ast::ast_struct::PyRootNode;
 use crate::tools::repl::repl;
-use clap::Parser;
+use crate::tools::tokenize::tokenize;
+use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(name = "python")]
 #[command(author = "Asahi Qin")]
 #[command(version = "Python 3.8")]
 #[command(about = "A python interpreter written in rust", long_about = None)]
-pub struct Cli {}
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+    /// Run the script instead of starting the REPL
+    file: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Print the tokens of a file in the format of `python -m tokenize`
+    Tokenize { file: PathBuf },
+}
+
+/// PYTHONPATH中的目录，添加在sys.path中
+fn python_path() -> Vec<String> {
+    std::env::var_os("PYTHONPATH").map_or(vec![], |x| {
+        std::env::split_paths(&x).map(|x| x.to_string_lossy().to_string()).collect()
+    })
+}
 
 fn main() {
     let detail_version = format!(
@@ -24,6 +52,37 @@ fn main() {
         shadow::RUST_VERSION,
         shadow::BUILD_OS
     );
-    repl(detail_version);
-    let _cli = Cli::parse();
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Commands::Tokenize { file }) => {
+            let source = fs::read_to_string(&file).unwrap_or_else(|x| {
+                eprintln!("{}: {}", file.display(), x);
+                exit(1)
+            });
+            match tokenize(source) {
+                Ok(lines) => lines.iter().for_each(|x| println!("{}", x)),
+                Err(errors) => {
+                    errors.iter().for_each(|x| eprintln!("{}: {}", file.display(), x));
+                    exit(1)
+                }
+            }
+        }
+        None => match cli.file {
+            Some(file) => {
+                let source = fs::read_to_string(&file).unwrap_or_else(|x| {
+                    eprintln!("python: can't open file '{}': {}", file.display(), x);
+                    exit(2)
+                });
+                let mut nodes = PyRootNode::default();
+                nodes.script(&file);
+                nodes.search_path(python_path());
+                // an uncaught exception is reported like python does and the process exits with status 1
+                if let Err(exc) = nodes.parser(source).and_then(|_| nodes.exec()) {
+                    eprint!("{}", nodes.traceback(&exc));
+                    exit(1)
+                }
+            }
+            None => repl(detail_version, python_path()),
+        },
+    }
 }
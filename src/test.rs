@@ -1,40 +1,671 @@
 use colored::Colorize;
 
 use crate::ast::analyze::ast_analyze::build_parser;
-use crate::ast::ast_struct::{Calc, Compare, Constant, DataType, Operator, Type};
+use crate::ast::ast_struct::{exec_commands, Calc, Compare, Constant, DataType, Operator, Type};
 use crate::ast::data_type::bool::obj_bool;
 use crate::ast::data_type::float::obj_float;
 use crate::ast::data_type::int::obj_int;
 use crate::ast::data_type::object::obj_to_bool;
 use crate::ast::data_type::object::PyObjAttr;
+use crate::ast::data_type::signature::Signature;
 use crate::ast::data_type::str::obj_str;
 use crate::ast::namespace::PyNamespace;
-use crate::ast::scanner::build_scanner;
+use crate::ast::data_type::bytes::obj_bytes;
+use crate::ast::data_type::complex::obj_complex;
+use crate::ast::scanner::{build_scanner, parse_number, Literal};
+use crate::ast::scanner::TokenType;
+use crate::tools::tokenize::tokenize;
+use crate::ast::scanner::TokenType::{COLON, DEDENT, EOF, EQUAL, IDENTIFIER, IF, INDENT, NEWLINE, NUMBER};
 
 mod tests {
     use std::fs;
 
     use crate::ast::ast_struct::PyRootNode;
+    use crate::ast::data_type::class::{class_of, type_of};
+    use crate::ast::data_type::object::{obj_to_str, PyObject};
+    use crate::ast::data_type::tuple::obj_tuple;
     use crate::ast::namespace::Namespace;
 
     use super::*;
 
+    /// 执行源码，返回执行后的解释器
+    fn run(source: &str) -> PyRootNode {
+        let mut nodes = PyRootNode::default();
+        exec_source(&mut nodes, source);
+        nodes
+    }
+    fn exec_source(nodes: &mut PyRootNode, source: &str) {
+        nodes.parser(source.to_string()).unwrap();
+        if let Err(exc) = nodes.exec() {
+            panic!("{}", nodes.traceback(&exc));
+        }
+    }
+    /// 检查执行后全局变量的值
+    fn assert_globals<'a>(nodes: &mut PyRootNode, expected: impl IntoIterator<Item = (&'a str, PyObject)>) {
+        for (name, value) in expected {
+            assert_eq!(nodes.py_root_env.get_global(name.to_string()).unwrap(), value, "{}", name);
+        }
+    }
+    /// 执行源码，返回没有被捕获的异常的类名和str()
+    fn raises(source: &str) -> (String, String) {
+        raises_in(&mut PyRootNode::default(), source)
+    }
+    fn raises_in(nodes: &mut PyRootNode, source: &str) -> (String, String) {
+        let exc = *nodes.parser(source.to_string()).and_then(|_| nodes.exec()).expect_err("no exception was raised");
+        let name = class_of(&type_of(&exc)).name;
+        (name, obj_to_str(exc, Namespace::Global, &mut nodes.py_root_env).unwrap())
+    }
+    /// 执行源码，返回没有被捕获的异常打印到stderr的内容
+    fn traceback(source: &str) -> String {
+        let mut nodes = PyRootNode::default();
+        let exc = nodes.parser(source.to_string()).and_then(|_| nodes.exec()).expect_err("no exception was raised");
+        nodes.traceback(&exc)
+    }
+
     #[test]
     fn test_scanner() {
         println!("{}", "[INFO] Test scanner".yellow());
         let source = String::from("1+2+3+4+5+6+7+8+9");
         let mut scanner = build_scanner(source);
-        scanner.scan();
+        scanner.scan().unwrap();
         println!("{:#?}", scanner.token);
     }
     #[test]
+    fn test_indent_token() {
+        println!("{}", "[INFO] Test indent token".yellow());
+        let source = String::from("if 1:\n    if 2:\n\n        if 3:\n            a = 1\n# comment\nb = 2");
+        let mut scanner = build_scanner(source);
+        scanner.scan().unwrap();
+        let token_types: Vec<TokenType> = scanner.token.iter().map(|x| x.token_type).collect();
+        assert_eq!(
+            token_types,
+            vec![
+                IF, NUMBER, COLON, NEWLINE, INDENT, IF, NUMBER, COLON, NEWLINE, INDENT, IF, NUMBER,
+                COLON, NEWLINE, INDENT, IDENTIFIER, EQUAL, NUMBER, NEWLINE, DEDENT, DEDENT, DEDENT,
+                IDENTIFIER, EQUAL, NUMBER, NEWLINE, EOF
+            ]
+        );
+    }
+    #[test]
+    fn test_nested_block() {
+        println!("{}", "[INFO] Test nested block".yellow());
+        let mut nodes = run(
+            "a = 0\nb = 0\nif a == 0:\n    if b == 0:\n        if a == b:\n            if 1:\n                a = 1\n        b = 2\n    else:\n        b = 3\nelif b == 1:\n    b = 4\n",
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_int(1)),
+                ("b", obj_int(2)),
+            ],
+        );
+        for (source, message) in [
+            ("x = 1\n    y = 2\n", "unexpected indent"),
+            ("if 1:\n    x = 1\n        y = 2\n", "unexpected indent"),
+            ("if 1:\ny = 2\n", "expected an indented block"),
+            ("while 1:\n    pass\nelse:\nx = 1\n", "expected an indented block"),
+            ("def f():\n", "expected an indented block"),
+        ] {
+            assert_eq!(raises(source), ("IndentationError".to_string(), message.to_string()));
+        }
+    }
+    #[test]
+    fn test_line_joining() {
+        println!("{}", "[INFO] Test line joining".yellow());
+        let source = String::from("a = (1 +\n\n        2) * \\\n  3\nif a == 9 \\\n   and (a <\n 10):\n    b = [\n1]\n");
+        let mut scanner = build_scanner(source);
+        scanner.scan().unwrap();
+        let newlines = scanner.token.iter().filter(|x| x.token_type == NEWLINE).count();
+        assert_eq!(newlines, 3);
+        let indents = scanner.token.iter().filter(|x| x.token_type == INDENT).count();
+        assert_eq!(indents, 1);
+        let mut nodes = run("a = (1 +\n        2) * \\\n  3\nif a == 9 and (a <\n 10):\n    b = 1\n");
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_int(9)),
+                ("b", obj_int(1)),
+            ],
+        );
+    }
+    #[test]
+    fn test_operator_token() {
+        println!("{}", "[INFO] Test operator token".yellow());
+        let source = String::from("a**=b//=c>>=d<<e->f:=g...h.i..@=~&|^ != <> 1.5.real [x]%=.5");
+        let mut scanner = build_scanner(source);
+        scanner.scan().unwrap();
+        let lexemes: Vec<&str> = scanner
+            .token
+            .iter()
+            .filter(|x| ![IDENTIFIER, NUMBER, NEWLINE, EOF].contains(&x.token_type))
+            .map(|x| x.lexeme.as_str())
+            .collect();
+        assert_eq!(
+            lexemes,
+            vec![
+                "**=", "//=", ">>=", "<<", "->", ":=", "...", ".", ".", ".", "@=", "~", "&", "|",
+                "^", "!=", "<", ">", ".", "[", "]", "%="
+            ]
+        );
+        assert_eq!(scanner.token[1].token_type, TokenType::PowEqual);
+        assert_eq!(scanner.token[13].token_type, TokenType::Ellipsis);
+    }
+    #[test]
+    fn test_trivia() {
+        println!("{}", "[INFO] Test trivia".yellow());
+        let sources = [
+            "# header\r\nif a :  # comment\r\n\tb = '''x\r\ny'''\r\n\n\t\t# inner\n\tc = (1,\n  2) \\\n + 3\nd=4",
+            "s = f'{a}'  'é'\nif x:\n    if y:\n        pass\n# trailing\n\n",
+        ];
+        for source in sources {
+            let mut scanner = build_scanner(source.to_string()).trivia(true);
+            scanner.scan().unwrap();
+            // the trivia and the lexeme of every token together give back the source
+            let text: String = scanner.token.iter().map(|x| format!("{}{}", x.trivia, x.lexeme)).collect();
+            assert_eq!(text, source);
+        }
+        let mut scanner = build_scanner(String::from("a = '''x\ny''' # c\nb")).trivia(true);
+        scanner.scan().unwrap();
+        let string = &scanner.token[2];
+        assert_eq!((string.lineno, string.col_offset), (0, 4));
+        assert_eq!((string.end_lineno, string.end_col_offset), (1, 4));
+        assert_eq!(scanner.token[3].trivia, " # c");
+    }
+    #[test]
+    fn test_tokenize() {
+        println!("{}", "[INFO] Test tokenize".yellow());
+        let lines = tokenize(String::from("x = (1, # one\n  'a') \\\n + y\n")).unwrap();
+        let lines: Vec<&str> = lines.iter().map(|x| x.trim_end()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "0,0-0,0:            ENCODING       'utf-8'",
+                "1,0-1,1:            NAME           'x'",
+                "1,2-1,3:            OP             '='",
+                "1,4-1,5:            OP             '('",
+                "1,5-1,6:            NUMBER         '1'",
+                "1,6-1,7:            OP             ','",
+                "1,8-1,13:           COMMENT        '# one'",
+                "1,13-1,14:          NL             '\\n'",
+                "2,2-2,5:            STRING         \"'a'\"",
+                "2,5-2,6:            OP             ')'",
+                "3,1-3,2:            OP             '+'",
+                "3,3-3,4:            NAME           'y'",
+                "3,4-3,5:            NEWLINE        '\\n'",
+                "4,0-4,0:            ENDMARKER      ''",
+            ]
+        );
+        // an empty file has only the ENDMARKER on the first line, like CPython
+        let lines = tokenize(String::new()).unwrap();
+        let lines: Vec<&str> = lines.iter().map(|x| x.trim_end()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "0,0-0,0:            ENCODING       'utf-8'",
+                "1,0-1,0:            ENDMARKER      ''",
+            ]
+        );
+        let mut nodes = run(
+            r#"
+import tokenize
+lines = iter(["if x: # c\n", "  y\n"])
+tokens = tokenize.generate_tokens(lambda: next(lines, ""))
+b = next(tokens)
+a = [(tokenize.tok_name[x.type], x.string, x.start, x.end, x.line) for x in tokens if x.type != tokenize.NAME]
+c = f"{b!r} {b.line!r} {b[0] == tokenize.NAME} {tokenize.OP} {iter(tokens) is tokens}"
+"#,
+        );
+        // expected values are the results of CPython
+        assert_eq!(
+            obj_to_str(nodes.py_root_env.get_global("a".to_string()).unwrap(), Namespace::Global, &mut nodes.py_root_env)
+                .unwrap(),
+            "[('OP', ':', (1, 4), (1, 5), 'if x: # c\\n'), ('COMMENT', '# c', (1, 6), (1, 9), 'if x: # c\\n'), \
+             ('NEWLINE', '\\n', (1, 9), (1, 10), 'if x: # c\\n'), ('INDENT', '  ', (2, 0), (2, 2), '  y\\n'), \
+             ('NEWLINE', '\\n', (2, 3), (2, 4), '  y\\n'), ('DEDENT', '', (3, 0), (3, 0), ''), \
+             ('ENDMARKER', '', (3, 0), (3, 0), '')]"
+        );
+        assert_globals(
+            &mut nodes,
+            [(
+                "c",
+                obj_str(
+                    "TokenInfo(type=1 (NAME), string='if', start=(1, 0), end=(1, 2), line='if x: # c\\n') \
+                     'if x: # c\\n' True 54 True"
+                        .to_string(),
+                ),
+            )],
+        );
+    }
+    #[test]
+    fn test_lex_error() {
+        println!("{}", "[INFO] Test lex error".yellow());
+        let source = String::from("a = 1__0\nb = 'abc\nc = (1 $ 2]\nd = 3\n");
+        let mut scanner = build_scanner(source);
+        let errors = scanner.scan().unwrap_err();
+        let errors: Vec<((u64, u64), &str)> = errors.iter().map(|x| (x.position(), x.message())).collect();
+        assert_eq!(
+            errors,
+            vec![
+                ((0, 4), "invalid decimal literal"),
+                ((1, 4), "unterminated string literal"),
+                ((2, 7), "Unexpected Character"),
+                ((2, 10), "closing parenthesis ']' does not match opening parenthesis '('"),
+            ]
+        );
+        // the scanner keeps going after errors
+        assert_eq!(scanner.token[scanner.token.len() - 3].lexeme, "3");
+        // indentation errors are shown with their own class rather than SyntaxError
+        for (source, text) in [
+            ("if 1:\n        a = 1\n\tb = 2\n", "Error at 3:2,\nTabError: inconsistent use of tabs and spaces in indentation"),
+            ("if 1:\n    a = 1\n  b = 2\n", "Error at 3:3,\nIndentationError: unindent does not match any outer indentation level"),
+        ] {
+            let errors = build_scanner(source.to_string()).scan().unwrap_err();
+            assert_eq!(errors[0].to_string(), text);
+        }
+        assert!(build_scanner(String::new()).scan().is_ok());
+    }
+    #[test]
+    fn test_identifier() {
+        println!("{}", "[INFO] Test identifier".yellow());
+        let source = String::from("z Zz x1 x_2z _ größe 变量 ﬁle ℌ = 1");
+        let mut scanner = build_scanner(source);
+        scanner.scan().unwrap();
+        let literals: Vec<Literal> = scanner
+            .token
+            .iter()
+            .filter(|x| x.token_type == IDENTIFIER)
+            .map(|x| x.literal.clone())
+            .collect();
+        assert_eq!(
+            literals,
+            ["z", "Zz", "x1", "x_2z", "_", "größe", "变量", "file", "H"]
+                .map(|x| Literal::Identifier(x.to_string()))
+                .to_vec()
+        );
+        let mut nodes = run("变量 = 1\nﬁ = 变量 + 1\n");
+        assert_globals(
+            &mut nodes,
+            [
+                ("fi", obj_int(2)),
+            ],
+        );
+    }
+    #[test]
+    fn test_number_literal() {
+        println!("{}", "[INFO] Test number literal".yellow());
+        let source = String::from("0xff 0o17 0B101 1_000 9.5 1e-3 2j .5 1. 0 00 1_0.0_1E+1_0 0x_f");
+        let mut scanner = build_scanner(source);
+        scanner.scan().unwrap();
+        let literals: Vec<Literal> = scanner
+            .token
+            .iter()
+            .filter(|x| x.token_type == NUMBER)
+            .map(|x| x.literal.clone())
+            .collect();
+        assert_eq!(
+            literals,
+            vec![
+                Literal::Int(255),
+                Literal::Int(15),
+                Literal::Int(5),
+                Literal::Int(1000),
+                Literal::Float(9.5),
+                Literal::Float(0.001),
+                Literal::Imaginary(2.0),
+                Literal::Float(0.5),
+                Literal::Float(1.0),
+                Literal::Int(0),
+                Literal::Int(0),
+                Literal::Float(10.01e10),
+                Literal::Int(15),
+            ]
+        );
+        // an int beyond i64 is still a number, it keeps its lexeme and raises when evaluated
+        let mut scanner = build_scanner(String::from("0xFFFF_FFFF_FFFF_FFFF 99999999999999999999"));
+        scanner.scan().unwrap();
+        let numbers: Vec<(String, Literal)> = scanner
+            .token
+            .iter()
+            .filter(|x| x.token_type == NUMBER)
+            .map(|x| (x.lexeme.clone(), x.literal.clone()))
+            .collect();
+        assert_eq!(
+            numbers,
+            vec![
+                ("0xFFFF_FFFF_FFFF_FFFF".to_string(), Literal::LargeInt),
+                ("99999999999999999999".to_string(), Literal::LargeInt),
+            ]
+        );
+        for source in ["0x", "1__0", "1.2.3", "1_", "0b102", "012", "1e", "1.5xj"] {
+            assert!(parse_number(source).is_err(), "{} should be invalid", source);
+        }
+        // a keyword may follow a number directly, any other name may not
+        let mut nodes = run("a = 1if 0else 2\nb = 0x1fif 0b1else 0\nc = 1e5if 1jand 0b1or 0else 0\n");
+        assert_globals(&mut nodes, [("a", obj_int(2)), ("b", obj_int(31)), ("c", obj_float(100000.0))]);
+        for (source, message) in [
+            ("1abc", "invalid decimal literal"),
+            ("1ex", "invalid decimal literal"),
+            ("0x1g", "invalid hexadecimal literal"),
+            ("1jx", "invalid imaginary literal"),
+            ("1f'x'", "invalid decimal literal"),
+        ] {
+            let errors = build_scanner(source.to_string()).scan().unwrap_err();
+            assert_eq!(errors[0].message(), message, "{}", source);
+        }
+    }
+    #[test]
+    fn test_complex_calc() {
+        println!("{}", "[INFO] Test complex calc".yellow());
+        let mut nodes = run("a = (1 + 2j) * 2j\nb = 0o10 + 0b1 * 0x10\n");
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_complex(-4.0, 2.0)),
+                ("b", obj_int(24)),
+            ],
+        );
+    }
+    #[test]
+    fn test_string_literal() {
+        println!("{}", "[INFO] Test string literal".yellow());
+        let source = String::from(
+            r#"'a\'b' "" '''x
+"y"''' r'\d\n' b'\x41\n' Rb"\x" u'é\N{BULLET}' f'{a}' '\101\t' """"""
+x = ''"#,
+        );
+        let mut scanner = build_scanner(source);
+        scanner.scan().unwrap();
+        let literals: Vec<Literal> = scanner
+            .token
+            .iter()
+            .filter(|x| x.token_type == TokenType::STRING)
+            .map(|x| x.literal.clone())
+            .collect();
+        assert_eq!(
+            literals,
+            vec![
+                Literal::Str("a'b".to_string()),
+                Literal::Str("".to_string()),
+                Literal::Str("x\n\"y\"".to_string()),
+                Literal::Str("\\d\\n".to_string()),
+                Literal::Bytes(b"A\n".to_vec()),
+                Literal::Bytes(b"\\x".to_vec()),
+                Literal::Str("\u{e9}\u{2022}".to_string()),
+                Literal::FormatStr("{a}".to_string()),
+                Literal::Str("A\t".to_string()),
+                Literal::Str("".to_string()),
+                Literal::Str("".to_string()),
+            ]
+        );
+        // a multi lines string keeps the line where it starts
+        assert_eq!(scanner.token[2].lineno, 0);
+        assert_eq!(scanner.token[3].lineno, 1);
+    }
+    #[test]
+    fn test_string_concat() {
+        println!("{}", "[INFO] Test string concat".yellow());
+        let mut nodes = run("a = 'hello' \" \" r'world'\nb = b'a' b\"b\" + b'c'\n");
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_str("hello world".to_string())),
+                ("b", obj_bytes(b"abc".to_vec())),
+            ],
+        );
+    }
+    #[test]
+    fn test_format_string() {
+        println!("{}", "[INFO] Test format string".yellow());
+        let mut nodes = run(
+            r#"x = 255
+name = 'py'
+a = f"{x} {x:#x} {x:>6} {x:08,d} {{x}}"
+b = f'{name!r:^8}|{3.14159:.2f}|{1e16}|{0.1 + 0.2}|{2.5:e}'
+c = f"{x = }, {x*2=:5}, {name=}, {'é'!a}"
+w = 4
+d = f'{x:{w}}|{1/3:.{w}g}|{x == 255}' "!" f"{'}'}"
+"#,
+        );
+        for (name, value) in [
+            ("a", "255 0xff    255 0,000,255 {x}"),
+            ("b", "  'py'  |3.14|1e+16|0.30000000000000004|2.500000e+00"),
+            ("c", "x = 255, x*2=  510, name='py', '\\xe9'"),
+            ("d", " 255|0.3333|True!}"),
+        ] {
+            assert_globals(&mut nodes, [(name, obj_str(value.to_string()))]);
+        }
+    }
+    #[test]
+    fn test_function() {
+        println!("{}", "[INFO] Test function".yellow());
+        let mut nodes = run(
+            r#"x = 5
+def add(a, b):
+    c = a + b
+    return c
+def fib(n):
+    if n < 2:
+        return n
+    return fib(n - 1) + fib(n - 2)
+def outer(x):
+    def inner(y):
+        return x + y
+    return inner
+def first_over(limit):
+    n = 0
+    while True:
+        n = n + 1
+        if n > limit:
+            return n
+def nothing():
+    x = 1
+a = add(1, b=2)
+b = fib(10)
+c = outer(10)(5)
+d = first_over(3)
+e = nothing() == None
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_int(3)),
+                ("b", obj_int(55)),
+                ("c", obj_int(15)),
+                ("d", obj_int(4)),
+                ("e", obj_bool(true)),
+                ("x", obj_int(5)),
+            ],
+        );
+        // only the frame captured by inner is kept after the calls return
+        assert_eq!(nodes.py_root_env.enclosing_namespace.len(), 1);
+
+        let mut scanner = build_scanner(String::from("return 1\n"));
+        scanner.scan().unwrap();
+        let mut parser = build_parser(scanner, PyNamespace::default());
+        assert!(parser.create_vec().is_err());
+    }
+    #[test]
+    fn test_unbound_local() {
+        println!("{}", "[INFO] Test unbound local".yellow());
+        let mut nodes = run(
+            r#"x = 5
+def last(n):
+    for i in range(n):
+        y = i
+    return y
+def read_global():
+    return x
+def shadow():
+    try:
+        x = x + 1
+    except UnboundLocalError:
+        return "unbound"
+    return x
+a = last(3)
+b = read_global()
+c = shadow()
+"#,
+        );
+        assert_globals(&mut nodes, [("a", obj_int(2)), ("b", obj_int(5)), ("c", obj_str("unbound".to_string()))]);
+        let message = "cannot access local variable 'x' where it is not associated with a value";
+        for (source, expected) in [
+            ("x = 5\ndef g():\n    x = x + 1\n    return x\ng()\n", ("UnboundLocalError", message)),
+            ("x = 5\ndef g():\n    print(x)\n    x = 1\ng()\n", ("UnboundLocalError", message)),
+            ("def g():\n    x = 1\n    del x\n    return x\ng()\n", ("UnboundLocalError", message)),
+            (
+                "y = 3\ndef g():\n    def inner():\n        return y\n    r = inner()\n    y = 1\ng()\n",
+                (
+                    "NameError",
+                    "cannot access free variable 'y' where it is not associated with a value in enclosing scope",
+                ),
+            ),
+        ] {
+            assert_eq!(raises(source), (expected.0.to_string(), expected.1.to_string()), "{}", source);
+        }
+    }
+    #[test]
+    fn test_recursion_limit() {
+        println!("{}", "[INFO] Test recursion limit".yellow());
+        let mut nodes = run(
+            r#"import sys
+def depth(n):
+    if n == 0:
+        return 0
+    return depth(n - 1) + 1
+def forever():
+    return forever()
+a = depth(900)
+try:
+    forever()
+except RecursionError as e:
+    b = str(e)
+c = sys.getrecursionlimit()
+sys.setrecursionlimit(50)
+try:
+    depth(60)
+except RecursionError:
+    d = depth(40)
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_int(900)),
+                ("b", obj_str("maximum recursion depth exceeded".to_string())),
+                ("c", obj_int(1000)),
+                ("d", obj_int(40)),
+            ],
+        );
+        assert_eq!(
+            raises("def forever():\n    return forever()\nforever()\n"),
+            ("RecursionError".to_string(), "maximum recursion depth exceeded".to_string())
+        );
+    }
+    #[test]
+    fn test_parameters() {
+        println!("{}", "[INFO] Test parameters".yellow());
+        let mut nodes = run(
+            r#"n = 10
+def f(a, b=2, *args, c, d=4, **kwargs):
+    return f"{a} {b} {args} {c} {d} {kwargs}"
+def forward(*args, **kwargs):
+    return f(*args, **kwargs)
+def pos(a, b, /, c):
+    return a + b + c
+def default(x=n):
+    return x
+n = 20
+a = f(1, c=3)
+b = f(1, 5, 6, 7, c=8, e=9, z='x')
+c = forward(10, 20, c=30, d=40)
+d = pos(1, 2, c=3)
+e = default()
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_str("1 2 () 3 4 {}".to_string())),
+                ("b", obj_str("1 5 (6, 7) 8 4 {'e': 9, 'z': 'x'}".to_string())),
+                ("c", obj_str("10 20 () 30 40 {}".to_string())),
+                ("d", obj_int(6)),
+                ("e", obj_int(10)),
+            ],
+        );
+
+        let signature = Signature {
+            posonlyargs: vec!["a".to_string()],
+            args: vec!["b".to_string(), "c".to_string()],
+            kwonlyargs: vec!["d".to_string()],
+            defaults: vec![obj_int(0)],
+            kw_defaults: vec![None],
+            ..Default::default()
+        };
+        for (args, keywords, message) in [
+            (vec![], vec![], "f() missing 2 required positional arguments: 'a' and 'b'"),
+            (vec![obj_int(1), obj_int(2), obj_int(3), obj_int(4)], vec![], "f() takes from 2 to 3 positional arguments but 4 were given"),
+            (vec![obj_int(1), obj_int(2)], vec![], "f() missing 1 required keyword-only argument: 'd'"),
+            (vec![obj_int(1), obj_int(2)], vec![("b", obj_int(2))], "f() got multiple values for argument 'b'"),
+            (vec![obj_int(1), obj_int(2)], vec![("e", obj_int(2))], "f() got an unexpected keyword argument 'e'"),
+            (vec![], vec![("a", obj_int(1))], "f() got some positional-only arguments passed as keyword arguments: 'a'"),
+        ] {
+            let keywords = keywords.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+            assert_eq!(signature.bind("f", args, keywords).unwrap_err(), message);
+        }
+
+        for (source, message) in [
+            ("def f(a=1, b):\n    pass\n", "non-default argument follows default argument"),
+            ("def f(a, a):\n    pass\n", "duplicate argument 'a' in function definition"),
+            ("def f(*, **k):\n    pass\n", "named arguments must follow bare *"),
+            ("def f(/, a):\n    pass\n", "at least one argument must precede /"),
+            ("def f(**k, a):\n    pass\n", "arguments cannot follow var-keyword argument"),
+            ("f(a=1, 2)\n", "positional argument follows keyword argument"),
+            ("f(**a, *b)\n", "iterable argument unpacking follows keyword argument unpacking"),
+        ] {
+            let mut scanner = build_scanner(source.to_string());
+            scanner.scan().unwrap();
+            let mut parser = build_parser(scanner, PyNamespace::default());
+            let error = parser.create_vec().unwrap_err().to_string();
+            assert!(error.ends_with(message), "{}", error);
+        }
+    }
+    #[test]
+    fn test_lambda() {
+        println!("{}", "[INFO] Test lambda".yellow());
+        let mut nodes = run(
+            r#"add = lambda x, y=1: x + y
+def make(n):
+    return lambda x: x * n
+nested = lambda: lambda: 7
+a = add(2)
+b = add(2, y=5)
+c = make(3)(4)
+d = nested()()
+e = (lambda *args, **kwargs: f"{args}{kwargs}")(1, k=2)
+f = (lambda x: x > 1 and x < 5)(3)
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_int(3)),
+                ("b", obj_int(7)),
+                ("c", obj_int(12)),
+                ("d", obj_int(7)),
+                ("e", obj_str("(1,){'k': 2}".to_string())),
+                ("f", obj_bool(true)),
+            ],
+        );
+    }
+    #[test]
     fn test_parser() {
         println!("{}", "[INFO] Test parser".yellow());
         let source = String::from("1 is not 2 and 2 is not 1 and 1+3*(3+2)");
         let mut scanner = build_scanner(source);
-        scanner.scan();
+        scanner.scan().unwrap();
         let mut parser = build_parser(scanner, PyNamespace::default());
-        println!("{:#?}", parser.parser());
+        println!("{:#?}", parser.create_vec());
     }
     #[test]
     fn test_object_compare() {
@@ -42,14 +673,15 @@ mod tests {
         let mut bin = Compare {
             left: Box::new(Type::Constant(Constant::new(obj_int(1)))),
             ops: vec![Operator::Eq, Operator::LtE, Operator::Gt],
-            comparators: Box::new(vec![
+            comparators: vec![
                 Type::Constant(Constant::new(obj_int(1))),
                 Type::Constant(Constant::new(obj_int(2))),
                 Type::Constant(Constant::new(obj_float(-1.0))),
-            ]),
+            ],
         };
         assert_eq!(
             bin.calc(&mut PyNamespace::default(), Namespace::Global)
+                .unwrap()
                 .value
                 .get_value("x".to_string())
                 .unwrap(),
@@ -62,10 +694,10 @@ mod tests {
         let namespace = Namespace::Global;
         let mut env = PyNamespace::default();
         println!("{}", "[INFO] Test Object To Bool".yellow());
-        assert_eq!(obj_to_bool(obj_str(String::from("hello")),namespace.clone(),&mut env), true);
-        assert_eq!(obj_to_bool(obj_float(1.0),namespace.clone(),&mut env), true);
-        assert_eq!(obj_to_bool(obj_bool(true),namespace.clone(),&mut env), true);
-        assert_eq!(obj_to_bool(obj_str("".to_string()),namespace,&mut env), false);
+        assert!(obj_to_bool(obj_str(String::from("hello")),namespace.clone(),&mut env).unwrap());
+        assert!(obj_to_bool(obj_float(1.0),namespace.clone(),&mut env).unwrap());
+        assert!(obj_to_bool(obj_bool(true),namespace.clone(),&mut env).unwrap());
+        assert!(!obj_to_bool(obj_str("".to_string()),namespace,&mut env).unwrap());
     }
 
     #[test]
@@ -73,14 +705,12 @@ mod tests {
         println!("{}", "[INFO] Test Compare".yellow());
         let sources = String::from("False or True and not True");
         let mut scanner = build_scanner(sources);
-        scanner.scan();
+        scanner.scan().unwrap();
         let mut parser = build_parser(scanner, PyNamespace::default());
-        let nodes = parser.parser();
+        let nodes = parser.create_vec();
         println!(
             "{:#?}",
-            nodes
-                .unwrap()
-                .exec(&mut PyNamespace::default(), Namespace::Global)
+            exec_commands(&nodes.unwrap(), &mut PyNamespace::default(), Namespace::Global)
         );
     }
 
@@ -89,14 +719,12 @@ mod tests {
         println!("{}", "[INFO] Test Object Calc".yellow());
         let sources = String::from("-1+3*(3+2)+(-4.7)");
         let mut scanner = build_scanner(sources);
-        scanner.scan();
+        scanner.scan().unwrap();
         let mut parser = build_parser(scanner, PyNamespace::default());
-        let nodes = parser.parser();
+        let nodes = parser.create_vec();
         println!(
             "{:#?}",
-            nodes
-                .unwrap()
-                .exec(&mut PyNamespace::default(), Namespace::Global)
+            exec_commands(&nodes.unwrap(), &mut PyNamespace::default(), Namespace::Global)
         );
     }
 
@@ -138,7 +766,1288 @@ mod tests {
         let source = fs::read_to_string("src/test_py/test.py").unwrap();
         let mut nodes = PyRootNode::default();
         nodes.py_root_env.set_builtin("__name__".to_string(),obj_str("__main__".to_string()));
-        nodes.parser(source);
-        nodes.exec();
+        exec_source(&mut nodes, &source);
+    }
+    #[test]
+    fn test_class() {
+        println!("{}", "[INFO] Test class".yellow());
+        let mut nodes = run(
+            r#"class Money:
+    def __init__(self, amount):
+        print f"init {amount}"
+    def __add__(self, other):
+        return 42
+    def __eq__(self, other):
+        return other == 3
+    def __len__(self):
+        return 0
+    def __str__(self):
+        return "money"
+class Empty():
+    x = 1
+class Double:
+    def __call__(self, n):
+        return n * 2
+m = Money(1)
+a = m + m
+b = m == 3
+c = m != 3
+d = not not m
+e = f"{m}"
+f = Empty()
+g = f == f
+h = f == Empty()
+i = f != Empty()
+j = Double()(21)
+k = f"{Money}"
+class V:
+    def __init__(self, x):
+        self.x = x
+    def __add__(self, other):
+        if isinstance(other, V):
+            return V(self.x + other.x)
+        return NotImplemented
+    def __eq__(self, other):
+        if isinstance(other, V):
+            return self.x == other.x
+        return NotImplemented
+    def __lt__(self, other):
+        return NotImplemented
+    def __len__(self):
+        return self.x
+class R:
+    def __radd__(self, other):
+        return "radd"
+l = f"{(V(1) + V(2)).x} {V(1) + R()} {V(1) == V(1)} {V(1) == 1} {V(1) != 2} {NotImplemented}"
+n = f"{len(V(3))} {len(m)} {len([1, 2])} {len('abc')} {len((1,))} {len({1: 2})} {len(range(5))}"
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_int(42)),
+                ("b", obj_bool(true)),
+                ("c", obj_bool(false)),
+                ("d", obj_bool(false)),
+                ("e", obj_str("money".to_string())),
+                ("g", obj_bool(true)),
+                ("h", obj_bool(false)),
+                ("i", obj_bool(true)),
+                ("j", obj_int(42)),
+                ("k", obj_str("<class '__main__.Money'>".to_string())),
+                ("l", obj_str("3 radd True False True NotImplemented".to_string())),
+                ("n", obj_str("3 0 2 3 1 1 5".to_string())),
+            ],
+        );
+        for (source, error) in [
+            (
+                "class V:\n    def __lt__(self, other):\n        return NotImplemented\nV() < V()\n",
+                ("TypeError", "'<' not supported between instances of 'V' and 'V'"),
+            ),
+            (
+                "class V:\n    def __add__(self, other):\n        return NotImplemented\nV() + 1\n",
+                ("TypeError", "unsupported operand type(s) for +: 'V' and 'int'"),
+            ),
+            ("len(1)\n", ("TypeError", "object of type 'int' has no len()")),
+            (
+                "class A:\n    def __str__(self):\n        return self\nx = str(A())\n",
+                ("TypeError", "__str__ returned non-string (type A)"),
+            ),
+            (
+                "class A:\n    def __repr__(self):\n        return self\nx = f\"{A()!r}\"\n",
+                ("TypeError", "__repr__ returned non-string (type A)"),
+            ),
+            (
+                "class A:\n    def __str__(self):\n        return None\nx = f\"{A()}\"\n",
+                ("TypeError", "__str__ returned non-string (type NoneType)"),
+            ),
+            ("class V:\n    def __len__(self):\n        return -1\nlen(V())\n", ("ValueError", "__len__() should return >= 0")),
+        ] {
+            assert_eq!(raises(source), (error.0.to_string(), error.1.to_string()));
+        }
+    }
+    #[test]
+    fn test_inheritance() {
+        println!("{}", "[INFO] Test inheritance".yellow());
+        let mut nodes = run(
+            r#"class Base:
+    def __str__(self):
+        return "base"
+    def __add__(self, other):
+        return 1
+class Sized:
+    def __str__(self):
+        return "sized"
+    def __len__(self):
+        return 0
+class Child(Sized, Base):
+    def __init__(self):
+        print super()
+class O:
+    x = 0
+class A(O):
+    def __str__(self):
+        return "A"
+class B(O):
+    def __str__(self):
+        return "B"
+class C(A, B):
+    x = 1
+class D(B):
+    x = 2
+class E(C, D):
+    x = 3
+class MyInt(int):
+    def __str__(self):
+        return f"MyInt({int(self)})"
+c = Child()
+a = f"{c}"
+b = c + c
+d = not c
+e = f"{E()}"
+m = MyInt(5)
+f = m + 2
+g = f"{m}"
+h = isinstance(m, int) and not isinstance(3, MyInt)
+i = isinstance(c, Base) and issubclass(E, O) and issubclass(bool, int)
+j = int("  1_000 ") + int(2.7) + int("ff", 16)
+x = float("1.5")
+y = str(12)
+k = f"{x} {y} {bool(0)} {object} {isinstance}"
+l = m == 5
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_str("sized".to_string())),
+                ("b", obj_int(1)),
+                ("d", obj_bool(true)),
+                ("e", obj_str("A".to_string())),
+                ("f", obj_int(7)),
+                ("g", obj_str("MyInt(5)".to_string())),
+                ("h", obj_bool(true)),
+                ("i", obj_bool(true)),
+                ("j", obj_int(1257)),
+                ("k", obj_str("1.5 12 False <class 'object'> <built-in function isinstance>".to_string())),
+                ("l", obj_bool(true)),
+            ],
+        );
+    }
+    #[test]
+    fn test_mro() {
+        println!("{}", "[INFO] Test mro".yellow());
+        let mut nodes = run(
+            r#"class O:
+    x = 0
+class A(O):
+    x = 1
+class B(O):
+    x = 2
+class C(A, B):
+    def check(self):
+        return f"{super()}"
+s = f"{super(C, C())}"
+"#,
+        );
+        let class = match nodes.py_root_env.get_global("C".to_string()).unwrap().get_value("x".to_string()) {
+            Ok(PyObjAttr::Rust(DataType::Class(x))) => *x,
+            _ => panic!("C is not a class"),
+        };
+        let names: Vec<String> = class.mro_classes().into_iter().map(|x| x.name).collect();
+        assert_eq!(names, ["C", "A", "B", "O", "object"]);
+        assert_globals(
+            &mut nodes,
+            [
+                ("s", obj_str("<super: <class '__main__.C'>, <C object>>".to_string())),
+            ],
+        );
+    }
+    #[test]
+    fn test_inconsistent_mro() {
+        println!("{}", "[INFO] Test inconsistent mro".yellow());
+        assert_eq!(
+            raises("class X:\n    x = 0\nclass Y(X):\n    x = 0\nclass Z(X, Y):\n    x = 0\n"),
+            ("TypeError".to_string(), "Cannot create a consistent method resolution order (MRO) for bases X, Y".to_string())
+        );
+    }
+    #[test]
+    fn test_for() {
+        println!("{}", "[INFO] Test for".yellow());
+        let mut nodes = run(
+            r#"a = 0
+for i in range(5):
+    a = a + i
+b = ""
+for ch in "abc":
+    b = f"{b}{ch}-"
+c = 0
+for i in range(10, 0, -3):
+    if i == 4:
+        break
+    c = c + i
+else:
+    c = -1
+d = 0
+for i in range(3):
+    if i == 1:
+        continue
+    d = d + 10
+else:
+    d = d + 1
+e = ""
+for idx, (x, y) in enumerate(zip("ab", range(7, 9))):
+    e = f"{e}{idx}{x}{y};"
+def kw(**kwargs):
+    s = ""
+    for k in kwargs:
+        s = f"{s}{k}"
+    return s
+f = kw(p=1, q=2)
+class Count:
+    def __iter__(self):
+        return iter(range(3))
+g = 0
+for v in Count():
+    g = g + v
+def first_even():
+    for n in range(1, 10):
+        if n > 1 and n - 2 * int(n / 2) == 0:
+            return n
+h = first_even()
+it = iter("xy")
+j = f"{next(it)}{next(it)}{next(it, 'z')}"
+k = f"{range(0, 5)} {range(1, 9, 2)}"
+def total(*xs):
+    t = 0
+    for x in xs:
+        t = t + x
+    return t
+l = total(*range(4))
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_int(10)),
+                ("b", obj_str("a-b-c-".to_string())),
+                ("c", obj_int(17)),
+                ("d", obj_int(21)),
+                ("e", obj_str("0a7;1b8;".to_string())),
+                ("f", obj_str("pq".to_string())),
+                ("g", obj_int(3)),
+                ("h", obj_int(2)),
+                ("j", obj_str("xyz".to_string())),
+                ("k", obj_str("range(0, 5) range(1, 9, 2)".to_string())),
+                ("l", obj_int(6)),
+            ],
+        );
+    }
+    #[test]
+    fn test_attribute() {
+        println!("{}", "[INFO] Test attribute".yellow());
+        let mut nodes = run(
+            r#"class Point:
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+    def total(self):
+        return self.x + self.y
+p = Point(1, 2)
+a = p.total()
+p.x = 10
+m = p.total
+b = m()
+del p.y
+c = f"{p.__dict__} {hasattr(p, 'y')} {getattr(p, 'y', 0)}"
+d = "-".join("a b  c ".split())
+e = f"{'a,b,,c'.split(',', 2)} {'  Ab '.strip().upper()} {'aaa'.replace('a', 'b', 2)}"
+def mk(**kw):
+    return kw
+f = ""
+for k, v in mk(u=1, w=2).items():
+    f = f"{f}{k}{v}"
+class Logged:
+    def __setattr__(self, name, value):
+        super().__setattr__("last", name)
+        super().__setattr__(name, value)
+    def __getattr__(self, name):
+        return f"no {name}"
+q = Logged()
+q.v = 5
+g = f"{q.last} {q.v} {q.zz}"
+class Secret:
+    def __getattribute__(self, name):
+        if name == "hidden":
+            return 42
+        return super().__getattribute__(name)
+s = Secret()
+s.k = 1
+h = s.hidden + s.k
+class Base:
+    tag = "base"
+    def hello(self):
+        return "base"
+class Child(Base):
+    def hello(self):
+        return "child+" + super().hello()
+Child.tag = "child"
+i = f"{Child().hello()} {Child.tag} {Base.tag} {Child.__name__} {Child.__mro__}"
+lst = "x".split()
+lst.append(3)
+j = f"{lst} {Child().hello!r:.30}"
+class Frozen:
+    def __init__(self, x):
+        object.__setattr__(self, "x", x)
+    def __setattr__(self, name, value):
+        raise AttributeError("read only")
+    def __getattribute__(self, name):
+        if name == "double":
+            return object.__getattribute__(self, "x") * 2
+        return object.__getattribute__(self, name)
+fr = Frozen(3)
+try:
+    fr.x = 4
+except AttributeError as err:
+    k = f"{fr.x} {fr.double} {err} {object.__setattr__.__name__} {Frozen.__setattr__ is object.__setattr__}"
+class Fallback:
+    def __getattribute__(self, name):
+        if name == "gone":
+            raise AttributeError(name)
+        return object.__getattribute__(self, name)
+    def __getattr__(self, name):
+        return f"fallback {name}"
+fb = Fallback()
+fb.y = 1
+l = f"{fb.y} {fb.gone} {fb.other}"
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_int(3)),
+                ("b", obj_int(12)),
+                ("c", obj_str("{'x': 10} False 0".to_string())),
+                ("d", obj_str("a-b-c".to_string())),
+                ("e", obj_str("['a', 'b', ',c'] AB bba".to_string())),
+                ("f", obj_str("u1w2".to_string())),
+                ("g", obj_str("v 5 no zz".to_string())),
+                ("h", obj_int(43)),
+                (
+                    "i",
+                    obj_str(
+                        "child+base child base Child (<class '__main__.Child'>, <class '__main__.Base'>, <class 'object'>)"
+                            .to_string(),
+                    ),
+                ),
+                ("j", obj_str("['x', 3] <bound method Child.hello of <".to_string())),
+                ("k", obj_str("3 6 read only __setattr__ False".to_string())),
+                ("l", obj_str("1 fallback gone fallback other".to_string())),
+            ],
+        );
+    }
+    #[test]
+    fn test_missing_attribute() {
+        println!("{}", "[INFO] Test missing attribute".yellow());
+        assert_eq!(
+            raises("class A:\n    x = 1\na = A()\nb = a.x\nc = a.y\n"),
+            ("AttributeError".to_string(), "'A' object has no attribute 'y'".to_string())
+        );
+        assert_eq!(
+            raises("class A:\n    x = 1\na = A()\ndel a.missing\n"),
+            ("AttributeError".to_string(), "'A' object has no attribute 'missing'".to_string())
+        );
+        assert_eq!(
+            raises("class A:\n    x = 1\ndel A.missing\n"),
+            ("AttributeError".to_string(), "type object 'A' has no attribute 'missing'".to_string())
+        );
+    }
+    #[test]
+    fn test_subscript() {
+        println!("{}", "[INFO] Test subscript".yellow());
+        let mut nodes = run(
+            r#"
+def mk(**kw):
+    return kw
+class Grid:
+    def __init__(self):
+        self.rows = "ab cd".split()
+        self.log = "".split()
+    def __getitem__(self, key):
+        return key
+    def __setitem__(self, key, value):
+        self.log.append(key)
+    def __delitem__(self, key):
+        self.log.append(-key)
+s = "hello world"
+a = f"{s[0]} {s[-1]} {s[1:4]} {s[::-1]} {s[-3:]}"
+l = "a b c d e".split()
+l[0] = "z"
+l[1:3] = "x y z".split()
+del l[-1]
+del l[::2]
+b = f"{l} {l[1:]} {l[::-1]}"
+d = mk(a=1)
+d["b"] = 2
+del d["a"]
+c = f"{d} {d['b']}"
+r = range(10)
+e = f"{r[3]} {r[-1]} {r[2:8:2]} {b'abc'[1]} {b'abc'[1:]}"
+g = Grid()
+g[3] = 1
+del g[4]
+g.rows[0] = "zz"
+f = f"{g[1, 2]} {g[1:2, ::3]} {g[1,]} {g.log} {g.rows[1][0]} {g.rows}"
+sl = slice(1, 5)
+h = f"{sl} {sl.stop} {s[sl]} {g[1, 2, 3][1:]}"
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_str("h d ell dlrow olleh rld".to_string())),
+                ("b", obj_str("['x', 'z'] ['z'] ['z', 'x']".to_string())),
+                ("c", obj_str("{'b': 2} 2".to_string())),
+                ("e", obj_str("3 9 range(2, 8, 2) 98 b'bc'".to_string())),
+                (
+                    "f",
+                    obj_str("(1, 2) (slice(1, 2, None), slice(None, None, 3)) (1,) [3, -4] c ['zz', 'cd']".to_string()),
+                ),
+                ("h", obj_str("slice(1, 5, None) 5 ello (2, 3)".to_string())),
+            ],
+        );
+    }
+    #[test]
+    fn test_dict_key() {
+        println!("{}", "[INFO] Test dict key".yellow());
+        let mut nodes = run(
+            r#"
+class K:
+    def __init__(self, x):
+        self.x = x
+    def __eq__(self, other):
+        return isinstance(other, K) and self.x == other.x
+    def __hash__(self):
+        return self.x
+class P:
+    y = 0
+d = {1: "a"}
+a = f"{d[True]} {1.0 in d} {d.get(1.0)} {d.get(2)} {(1, 2) in {(1.0, 2): 0}}"
+d[1.0] = "b"
+del d[True]
+e = {K(1): "x"}
+p = P()
+f = {p: 1}
+b = f"{d} {e.get(K(1))} {K(1) in e} {e.get(K(2))} {f[p]} {f.get(P())}"
+"#,
+        );
+        // expected values are the results of CPython
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_str("a True a None True".to_string())),
+                ("b", obj_str("{} x True None 1 None".to_string())),
+            ],
+        );
+        for (source, name) in [
+            ("d = {}\nd[[1]] = 3\n", "list"),
+            ("d = {1: 2}\nx = d[{}]\n", "dict"),
+            ("x = [1] in {1: 2}\n", "list"),
+            ("class Q:\n    def __eq__(self, other):\n        return True\nd = {}\nd[Q()] = 1\n", "Q"),
+        ] {
+            assert_eq!(raises(source), ("TypeError".to_string(), format!("unhashable type: '{}'", name)));
+        }
+    }
+    #[test]
+    fn test_index_out_of_range() {
+        println!("{}", "[INFO] Test index out of range".yellow());
+        assert_eq!(
+            raises("l = \"a b\".split()\nx = l[-2]\ny = l[2]\n"),
+            ("IndexError".to_string(), "list index out of range".to_string())
+        );
+    }
+    #[test]
+    fn test_display() {
+        println!("{}", "[INFO] Test display".yellow());
+        let mut nodes = run(
+            r#"
+l = [1, 2, 3]
+t = (1, 2, *l)
+d = {1: "x", "y": l, **{3: 4}, 1: "z"}
+s = {1, 2, 2, *l}
+a = f"{l} {t} {d} {s}"
+b = f"{[]} {()} {(1,)} {{}} {(5)} {[*l, *l]} {[1, 2,][-1]}"
+m = [
+    1,
+    (2,
+     3),
+]
+l.append(4)
+c = f"{m} {t[-1]} {d.__len__()} {[[1], [2]][1][0]}"
+e = f"{ {1: 'a', 1.0: 'b', True: 'c'} } { {1, 1.0, True} } { {(1, 2), (1.0, 2.0)} } {2 in {1, 2.0}}"
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_str("[1, 2, 3] (1, 2, 1, 2, 3) {1: 'z', 'y': [1, 2, 3], 3: 4} {1, 2, 3}".to_string())),
+                ("b", obj_str("[] () (1,) {} 5 [1, 2, 3, 1, 2, 3] 2".to_string())),
+                ("c", obj_str("[1, (2, 3)] 3 3 2".to_string())),
+                ("e", obj_str("{1: 'c'} {1} {(1, 2)} True".to_string())),
+            ],
+        );
+        for source in ["x = {1, [2]}\n", "x = {[1]: 2}\n", "x = [1] in {1}\n"] {
+            assert_eq!(raises(source), ("TypeError".to_string(), "unhashable type: 'list'".to_string()));
+        }
+    }
+    #[test]
+    fn test_comprehension() {
+        println!("{}", "[INFO] Test comprehension".yellow());
+        let mut nodes = run(
+            r#"
+x = 100
+l = [1, 2, 3]
+a = f"{[x * 2 for x in l if x != 2]} {[(x, y) for x in range(3) for y in range(x)]} {x}"
+b = f"{ {x: y for x in l for y in 'abc'[:x]} } { {x > 1 for x in l} }"
+gen = (i * i for i in range(4))
+first = next(gen)
+c = f"{first} {[y for y in gen]} {[k for k, v in {1: 2, 3: 4}.items()]}"
+def shift(n):
+    return (i + n for i in range(n))
+fs = [lambda: i for i in range(3)]
+d = f"{[z for z in shift(3)]} {[f() for f in fs]} {next(x for x in l if x > 1)}"
+e = f"{ {x: i for i, x in enumerate([1, 1.0, True])} } { {x for x in [1.0, 1, True]} } { {x % 3 for x in range(9)} }"
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_str("[2, 6] [(1, 0), (2, 0), (2, 1)] 100".to_string())),
+                ("b", obj_str("{1: 'a', 2: 'b', 3: 'c'} {False, True}".to_string())),
+                ("c", obj_str("0 [1, 4, 9] [1, 3]".to_string())),
+                ("d", obj_str("[3, 4, 5] [2, 2, 2] 2".to_string())),
+                ("e", obj_str("{1: 2} {1.0} {0, 1, 2}".to_string())),
+            ],
+        );
+        for (source, name) in [("x = {x for x in [[1]]}\n", "list"), ("x = {x: 1 for x in [{}]}\n", "dict")] {
+            assert_eq!(raises(source), ("TypeError".to_string(), format!("unhashable type: '{}'", name)));
+        }
+    }
+    #[test]
+    fn test_operator_precedence() {
+        println!("{}", "[INFO] Test operator precedence".yellow());
+        let mut nodes = run(
+            r#"
+a1 = 10 - 3 - 2
+a2 = 8 / 4 / 2
+a3 = 2 ** 3 ** 2
+a4 = -2 ** 2
+a5 = -2 ** -1
+a6 = 2 * -3 ** 2
+b1 = -7 // 2
+b2 = -7 % 3
+b3 = 7 % -3
+b4 = -7.5 % 2
+b5 = 2 + 3 * 4 // 5 - 6
+b6 = 10 - 2 ** 2 * 3 // 4 % 5
+c1 = 1 + 2 << 3
+c2 = 64 >> 2 >> 1
+c3 = ~5
+c4 = -~5
+c5 = 5 & 3 | 8 ^ 1
+c6 = 6 ^ 3 & 5
+c7 = True ^ True
+c8 = -9 >> 1
+d1 = 1 | 2 == 3
+d2 = 1 < (2 < 3)
+d3 = 3 > 2 > 1 > 2
+d4 = False and True or True
+d5 = True or False and False
+d6 = not True and False
+e1 = 1 if 2 > 3 else 4 if False else 5
+e2 = (-8) ** (1 / 3)
+e3 = 2 ** -1
+class V:
+    def __init__(self, x):
+        self.x = x
+    def __radd__(self, other):
+        return other * 10 + self.x
+    def __matmul__(self, other):
+        return self.x * other.x
+e4 = 3 + V(1)
+e5 = V(2) @ V(3) * 2
+f1 = 1 + 1 in [2, 3] == True
+f2 = not 3 in (1, 2)
+f3 = 3 not in (1, 2) and "ab" in "cab"
+f4 = None is None is not False
+f5 = [1, (2, 3)] == [1, (2, 3)] != [1]
+f6 = 2 in {1: 2}
+calls = []
+def g(x):
+    calls.append(x)
+    return x
+f7 = g(1) < g(2) < g(3) > g(0)
+f8 = calls == [1, 2, 3, 0]
+g1 = (1, 2) < (1, 3)
+g2 = [1] <= [2]
+g3 = (1, 2) > (1,)
+g4 = [1, [2, 3]] >= [1, [2, 3]]
+g5 = (2,) <= (1, 5)
+g6 = [] < [0] < [0, 0] > [0]
+"#,
+        );
+        // expected values are the results of CPython
+        assert_globals(
+            &mut nodes,
+            [
+                ("a1", obj_int(5)),
+                ("a2", obj_float(1.0)),
+                ("a3", obj_int(512)),
+                ("a4", obj_int(-4)),
+                ("a5", obj_float(-0.5)),
+                ("a6", obj_int(-18)),
+                ("b1", obj_int(-4)),
+                ("b2", obj_int(2)),
+                ("b3", obj_int(-2)),
+                ("b4", obj_float(0.5)),
+                ("b5", obj_int(-2)),
+                ("b6", obj_int(7)),
+                ("c1", obj_int(24)),
+                ("c2", obj_int(8)),
+                ("c3", obj_int(-6)),
+                ("c4", obj_int(6)),
+                ("c5", obj_int(9)),
+                ("c6", obj_int(7)),
+                ("c7", obj_bool(false)),
+                ("c8", obj_int(-5)),
+                ("d1", obj_bool(true)),
+                ("d2", obj_bool(false)),
+                ("d3", obj_bool(false)),
+                ("d4", obj_bool(true)),
+                ("d5", obj_bool(true)),
+                ("d6", obj_bool(false)),
+                ("e1", obj_int(5)),
+                ("e2", obj_complex(1.0000000000000002, 1.7320508075688772)),
+                ("e3", obj_float(0.5)),
+                ("e4", obj_int(31)),
+                ("e5", obj_int(12)),
+                ("f1", obj_bool(false)),
+                ("f2", obj_bool(true)),
+                ("f3", obj_bool(true)),
+                ("f4", obj_bool(true)),
+                ("f5", obj_bool(true)),
+                ("f6", obj_bool(false)),
+                ("f7", obj_bool(true)),
+                ("f8", obj_bool(true)),
+                ("g1", obj_bool(true)),
+                ("g2", obj_bool(true)),
+                ("g3", obj_bool(true)),
+                ("g4", obj_bool(true)),
+                ("g5", obj_bool(false)),
+                ("g6", obj_bool(true)),
+            ],
+        );
+        for (source, symbol, types) in [
+            ("1 < 'a'", "<", ("int", "str")),
+            ("1 < 2 >= None", ">=", ("int", "NoneType")),
+            ("[] > 1j", ">", ("list", "complex")),
+            ("(1, 2) < [1, 2]", "<", ("tuple", "list")),
+            ("[1, 'a'] < [1, 2]", "<", ("str", "int")),
+        ] {
+            assert_eq!(
+                raises(format!("x = {}", source).as_str()),
+                (
+                    "TypeError".to_string(),
+                    format!("'{}' not supported between instances of '{}' and '{}'", symbol, types.0, types.1)
+                )
+            );
+        }
+        assert_eq!(
+            raises("x = 1 in 2"),
+            ("TypeError".to_string(), "argument of type 'int' is not iterable".to_string())
+        );
+    }
+    #[test]
+    fn test_zero_division() {
+        println!("{}", "[INFO] Test zero division".yellow());
+        assert_eq!(
+            raises("a = 7 // 2
+b = a % (a - 3)
+"),
+            ("ZeroDivisionError".to_string(), "integer division or modulo by zero".to_string())
+        );
+    }
+    #[test]
+    fn test_integer_overflow() {
+        println!("{}", "[INFO] Test integer overflow".yellow());
+        for source in [
+            "9223372036854775807 + 1",
+            "-9223372036854775807 - 2",
+            "4611686018427387904 * 2",
+            "9223372036854775807 + True",
+            "-(-9223372036854775807 - 1)",
+            "(-9223372036854775807 - 1) // -1",
+        ] {
+            assert_eq!(
+                raises(format!("x = {}\n", source).as_str()),
+                ("OverflowError".to_string(), "integer overflow".to_string()),
+                "{}",
+                source
+            );
+        }
+        let mut nodes = run("try:\n    x = 9223372036854775807 + 1\nexcept OverflowError:\n    x = 0\ny = True - 5\nz = (-9223372036854775807 - 1) % -1\n");
+        assert_globals(&mut nodes, [("x", obj_int(0)), ("y", obj_int(-4)), ("z", obj_int(0))]);
+        assert_eq!(
+            raises("MASK = 0xFFFFFFFFFFFFFFFF\n"),
+            ("OverflowError".to_string(), "integer literal too large: 0xFFFFFFFFFFFFFFFF".to_string())
+        );
+        // the literal is only evaluated when the function runs
+        let mut nodes = run("def mask():\n    return 0xFFFFFFFFFFFFFFFF\ntry:\n    m = mask()\nexcept OverflowError:\n    m = -1\n");
+        assert_globals(&mut nodes, [("m", obj_int(-1))]);
+    }
+    #[test]
+    fn test_assignment() {
+        println!("{}", "[INFO] Test assignment".yellow());
+        let mut nodes = run(
+            r#"
+x = 1
+x += 2
+x **= 2
+x //= 2
+x <<= 1
+a = b = c = 0
+b -= 1
+p, (q, r) = 1, [2, 3]
+first, *rest = [1, 2, 3, 4]
+h, *m, t = "ab"
+[u, *v], w = (1, 2, 3), 4
+l = l2 = [1]
+l += (2,)
+l = l + [3]
+class V:
+    def __init__(self, n):
+        self.n = n
+    def __add__(self, other):
+        return V(self.n + other * 100)
+o = V(1)
+o.n += 1
+o += 1
+d = {"k": 1}
+d["k"] *= 5
+def f():
+    return 1, 2
+g, k = f()
+y = f"{a} {b} {c} {p} {q} {r} {first} {rest} {h} {m} {t} {u} {v} {w}"
+z = f"{l} {l2} {o.n} {d} {g} {k}"
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("x", obj_int(8)),
+                ("y", obj_str("0 -1 0 1 2 3 1 [2, 3, 4] a [] b 1 [2, 3] 4".to_string())),
+                ("z", obj_str("[1, 2, 3] [1, 2] 102 {'k': 5} 1 2".to_string())),
+            ],
+        );
+    }
+    #[test]
+    fn test_starred_unpack() {
+        println!("{}", "[INFO] Test starred unpack".yellow());
+        assert_eq!(
+            raises("a, *b, c, d = [1, 2]\n"),
+            ("ValueError".to_string(), "not enough values to unpack (expected at least 3, got 2)".to_string())
+        );
+    }
+    #[test]
+    fn test_exception() {
+        println!("{}", "[INFO] Test exception".yellow());
+        let mut nodes = run(
+            r#"
+log = []
+def f(x):
+    try:
+        log.append("try")
+        r = 10 // x
+    except ZeroDivisionError as e:
+        log.append(str(e))
+        return -1
+    else:
+        log.append("else")
+        return r
+    finally:
+        log.append("finally")
+a = f(2) + f(0)
+class MyError(ValueError):
+    def __init__(self, msg, code):
+        super().__init__(msg)
+        self.code = code
+try:
+    raise MyError("bad", 3)
+except (KeyError, ValueError) as e:
+    b = f"{e} {e.code} {e.args} {e!r} {isinstance(e, Exception)}"
+try:
+    try:
+        undefined
+    except NameError as e:
+        raise RuntimeError("wrapped") from e
+except Exception as e:
+    c = f"{e} | {e.__cause__} | {e.__cause__.__class__.__name__}"
+try:
+    try:
+        {}["k"]
+    except LookupError:
+        raise TypeError
+except TypeError as e:
+    d = f"{e!r} {e.__context__!r}"
+class Count:
+    def __init__(self):
+        self.i = 0
+    def __iter__(self):
+        return self
+    def __next__(self):
+        self.i += 1
+        if self.i > 3:
+            raise StopIteration
+        return self.i
+g = [x for x in Count()]
+h = f"{log} {g}"
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_int(4)),
+                ("b", obj_str("bad 3 ('bad',) MyError('bad') True".to_string())),
+                ("c", obj_str("wrapped | name 'undefined' is not defined | NameError".to_string())),
+                ("d", obj_str("TypeError() KeyError('k')".to_string())),
+                (
+                    "h",
+                    obj_str(
+                        "['try', 'else', 'finally', 'try', 'integer division or modulo by zero', 'finally'] [1, 2, 3]"
+                            .to_string(),
+                    ),
+                ),
+            ],
+        );
+    }
+    #[test]
+    fn test_uncaught_exception() {
+        println!("{}", "[INFO] Test uncaught exception".yellow());
+        assert_eq!(
+            raises(r#"
+class Fatal(Exception):
+    x = 1
+try:
+    raise Fatal("stop")
+except ValueError:
+    x = 2
+finally:
+    x = 3
+"#),
+            ("Fatal".to_string(), "stop".to_string())
+        );
+        assert_eq!(
+            traceback("a = 1\nb = a + c\nd = 2\n"),
+            "Traceback (most recent call last):\nNameError: name 'c' is not defined\n"
+        );
+        assert_eq!(
+            traceback("class Fatal(Exception):\n    x = 1\nraise Fatal\n"),
+            "Traceback (most recent call last):\nFatal\n"
+        );
+        assert_eq!(
+            traceback("try:\n    {}['k']\nexcept KeyError as e:\n    raise ValueError('bad') from e\n"),
+            concat!(
+                "Traceback (most recent call last):\nKeyError: 'k'\n",
+                "\nThe above exception was the direct cause of the following exception:\n\n",
+                "Traceback (most recent call last):\nValueError: bad\n",
+            )
+        );
+        assert_eq!(
+            traceback("try:\n    1 / 0\nexcept ZeroDivisionError:\n    undefined\n"),
+            concat!(
+                "Traceback (most recent call last):\nZeroDivisionError: division by zero\n",
+                "\nDuring handling of the above exception, another exception occurred:\n\n",
+                "Traceback (most recent call last):\nNameError: name 'undefined' is not defined\n",
+            )
+        );
+    }
+    #[test]
+    fn test_syntax_error_location() {
+        println!("{}", "[INFO] Test syntax error location".yellow());
+        // every lexical error is reported with the line it is on
+        assert_eq!(
+            traceback("x = 1\nif x:\n    y = 1__0\nz = 'abc\n"),
+            concat!(
+                "  File \"<stdin>\", line 3\n    y = 1__0\n        ^\nSyntaxError: invalid decimal literal\n",
+                "  File \"<stdin>\", line 4\n    z = 'abc\n        ^\nSyntaxError: unterminated string literal\n",
+            )
+        );
+        assert_eq!(
+            traceback("x = (1 +\ny = )\n"),
+            "  File \"<stdin>\", line 2\n    y = )\n      ^\nSyntaxError: invalid syntax\n"
+        );
+        let dir = std::env::temp_dir().join("python_in_rust_test_syntax_error");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("broken.py"), "a = 1\nb = 2 +\n").unwrap();
+        let mut nodes = PyRootNode::default();
+        nodes.search_path(vec![dir.to_string_lossy().to_string()]);
+        exec_source(
+            &mut nodes,
+            "try:\n    import broken\nexcept SyntaxError as e:\n    where = (e.filename.endswith('broken.py'), e.lineno, e.offset, e.text, e.msg)\n",
+        );
+        assert_globals(
+            &mut nodes,
+            [(
+                "where",
+                obj_tuple(vec![obj_bool(true), obj_int(2), obj_int(8), obj_str("b = 2 +".to_string()), obj_str("invalid syntax".to_string())]),
+            )],
+        );
+    }
+    #[test]
+    fn test_with() {
+        println!("{}", "[INFO] Test with".yellow());
+        let path = std::env::temp_dir().join("python_in_rust_test_with.txt");
+        let mut nodes = run(
+            &r#"
+log = []
+class Lock:
+    def __init__(self, name, suppress=False):
+        self.name = name
+        self.suppress = suppress
+        self.held = False
+    def __enter__(self):
+        self.held = True
+        log.append(f"acquire {self.name}")
+        return self
+    def __exit__(self, exc_type, exc, tb):
+        self.held = False
+        log.append(f"release {self.name} {exc!r}")
+        return self.suppress
+with Lock("a") as x, Lock("b") as y:
+    log.append(f"{x.held} {y.held}")
+with Lock("s", True):
+    raise ValueError("hidden")
+try:
+    with Lock("p"):
+        {}["k"]
+except KeyError as e:
+    log.append(f"caught {e!r}")
+def first():
+    for i in range(3):
+        with Lock(f"f{i}"):
+            if i == 1:
+                return i
+with (
+    Lock("m1") as m1,
+    Lock("m2"),
+):
+    n = first()
+a = f"{log} {n} {x.held}"
+with open("TMPFILE", "w") as fh:
+    w = fh.write("one\ntwo\n")
+with open("TMPFILE", mode="a") as fh:
+    fh.write("three")
+with open("TMPFILE") as fh:
+    r = f"{fh.readline()!r} {fh.read(2)!r} {fh.read()!r}"
+with open("TMPFILE") as fh:
+    lines = [line for line in fh]
+b = f"{w} {fh.closed} {r} {lines}"
+"#
+            .replace("TMPFILE", path.to_str().unwrap()),
+        );
+        std::fs::remove_file(path).unwrap();
+        assert_globals(
+            &mut nodes,
+            [
+                (
+                    "a",
+                    obj_str(
+                        "['acquire a', 'acquire b', 'True True', 'release b None', 'release a None', 'acquire s', \
+                         \"release s ValueError('hidden')\", 'acquire p', \"release p KeyError('k')\", \
+                         \"caught KeyError('k')\", 'acquire m1', 'acquire m2', 'acquire f0', 'release f0 None', \
+                         'acquire f1', 'release f1 None', 'release m2 None', 'release m1 None'] 1 False"
+                            .to_string(),
+                    ),
+                ),
+                (
+                    "b",
+                    obj_str("8 True 'one\\n' 'tw' 'o\\nthree' ['one\\n', 'two\\n', 'three']".to_string()),
+                ),
+            ],
+        );
+    }
+    #[test]
+    fn test_with_without_enter() {
+        println!("{}", "[INFO] Test with without __enter__".yellow());
+        assert_eq!(
+            raises(r#"
+class Plain:
+    x = 1
+with Plain():
+    x = 2
+"#),
+            ("TypeError".to_string(), "'Plain' object does not support the context manager protocol".to_string())
+        );
+    }
+    #[test]
+    fn test_import() {
+        println!("{}", "[INFO] Test import".yellow());
+        let dir = std::env::temp_dir().join("python_in_rust_test_import");
+        for (path, source) in [
+            ("pkg/__init__.py", "VERSION = \"1.0\"\nfrom .core import greet\n"),
+            ("pkg/core.py", "PREFIX = \"hello\"\ndef greet(name):\n    return f\"{PREFIX} {name} from {__name__}\"\nclass Thing:\n    def __init__(self):\n        self.kind = \"thing\"\n"),
+            ("pkg/sub/__init__.py", ""),
+            ("pkg/sub/leaf.py", "from .. import core\nfrom ..core import PREFIX as P\nfrom . import sibling\nvalue = f\"{P} {core.PREFIX} {sibling.NAME} {__package__}\"\n"),
+            ("pkg/sub/sibling.py", "NAME = \"sib\"\n"),
+            ("pkg/star.py", "__all__ = [\"x\", \"y\"]\nx = 1\ny = 2\nz = 3\n"),
+            ("cyc_a.py", "import cyc_b\nA = 1\ndef get():\n    return cyc_b.B\n"),
+            ("cyc_b.py", "import cyc_a\nB = 2\ndef get():\n    return cyc_a.A\n"),
+        ] {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, source).unwrap();
+        }
+        let mut nodes = PyRootNode::default();
+        nodes.search_path(vec![dir.to_string_lossy().to_string()]);
+        exec_source(
+            &mut nodes,
+            r#"
+import pkg.sub.leaf
+import pkg.core as c
+from pkg.core import greet, Thing as T
+from pkg.star import *
+from pkg import (
+    VERSION,
+    core,
+)
+import cyc_a
+a = f"{VERSION} {greet('x')} {pkg.sub.leaf.value}"
+b = f"{T().kind} {T.__module__} {c.__name__} {core.__name__} {x} {y}"
+c = f"{cyc_a.get()} {cyc_a.cyc_b.get()} {cyc_a.__name__}"
+try:
+    import missing
+except ModuleNotFoundError as e:
+    d = str(e)
+try:
+    import pkg.core.nope
+except ImportError as e:
+    d = f"{d} | {e}"
+pkg.core.PREFIX = "hi"
+e = greet("y")
+"#,
+        );
+        fs::remove_dir_all(dir).unwrap();
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_str("1.0 hello x from pkg.core hello hello sib pkg.sub".to_string())),
+                ("b", obj_str("thing pkg.core pkg.core pkg.core 1 2".to_string())),
+                ("c", obj_str("2 1 cyc_a".to_string())),
+                (
+                    "d",
+                    obj_str(
+                        "No module named 'missing' | No module named 'pkg.core.nope'; 'pkg.core' is not a package"
+                            .to_string(),
+                    ),
+                ),
+                ("e", obj_str("hi y from pkg.core".to_string())),
+            ],
+        );
+    }
+    #[test]
+    fn test_circular_from_import() {
+        println!("{}", "[INFO] Test circular from import".yellow());
+        let dir = std::env::temp_dir().join("python_in_rust_test_circular_import");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("cyc_c.py"), "from cyc_d import D\nC = 3\n").unwrap();
+        fs::write(dir.join("cyc_d.py"), "from cyc_c import C\nD = 4\n").unwrap();
+        let mut nodes = PyRootNode::default();
+        nodes.search_path(vec![dir.to_string_lossy().to_string()]);
+        let (name, message) = raises_in(&mut nodes, "import cyc_c\n");
+        fs::remove_dir_all(dir).unwrap();
+        assert_eq!(name, "ImportError");
+        assert!(message.starts_with("cannot import name 'C' from partially initialized module 'cyc_c'"), "{}", message);
+    }
+    #[test]
+    fn test_search_path() {
+        println!("{}", "[INFO] Test search path".yellow());
+        let mut nodes = PyRootNode::default();
+        nodes.search_path(vec!["first".to_string(), "second".to_string()]);
+        nodes.search_path(vec!["third".to_string()]);
+        exec_source(&mut nodes, "import sys\na = f\"{sys.path}\"\n");
+        assert_globals(&mut nodes, [("a", obj_str("['', 'first', 'second', 'third']".to_string()))]);
+    }
+    #[test]
+    fn test_generator() {
+        println!("{}", "[INFO] Test generator".yellow());
+        let mut nodes = run(
+            r#"
+log = []
+def countdown(n):
+    try:
+        while n > 0:
+            yield n
+            n -= 1
+    finally:
+        log.append("countdown closed")
+    return "liftoff"
+g = countdown(3)
+first = [next(g), next(g), next(g)]
+try:
+    next(g)
+except StopIteration as e:
+    first.append(e.value)
+a = f"{first} {g.__class__.__name__} {log}"
+def averager():
+    total = 0
+    count = 0
+    average = None
+    while True:
+        try:
+            value = yield average
+        except ValueError:
+            total, count, average = 0, 0, None
+            continue
+        if value == None:
+            return count
+        total += value
+        count += 1
+        average = total / count
+avg = averager()
+next(avg)
+sent = [avg.send(10), avg.send(20), avg.throw(ValueError), avg.send(3)]
+try:
+    avg.send(None)
+except StopIteration as e:
+    sent.append(e.value)
+b = f"{sent}"
+def read_lines(lines):
+    for line in lines:
+        yield line
+def strip(lines):
+    for line in lines:
+        if line.strip():
+            yield line.strip()
+def collect():
+    counts = yield from counts_of(strip(read_lines([" a", "", "b ", "  ", "c"])))
+    yield f"total {counts}"
+def counts_of(items):
+    n = 0
+    for item in items:
+        yield item.upper()
+        n += 1
+    return n
+c = f"{[x for x in collect()]}"
+g = countdown(5)
+next(g)
+closed = g.close()
+d = f"{closed} {log} {[x for x in g]}"
+def loops():
+    for i in range(6):
+        if i == 1:
+            continue
+        if i == 4:
+            break
+        with_value = yield i
+    else:
+        yield "unreachable"
+    while True:
+        yield "while"
+        break
+e = f"{[x for x in loops()]}"
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_str("[3, 2, 1, 'liftoff'] generator ['countdown closed']".to_string())),
+                ("b", obj_str("[10.0, 15.0, None, 3.0, 1]".to_string())),
+                ("c", obj_str("['A', 'B', 'C', 'total 3']".to_string())),
+                ("d", obj_str("None ['countdown closed', 'countdown closed'] []".to_string())),
+                ("e", obj_str("[0, 2, 3, 'while']".to_string())),
+            ],
+        );
+    }
+    #[test]
+    fn test_yield_expression() {
+        println!("{}", "[INFO] Test yield expression".yellow());
+        let mut nodes = run(
+            r#"
+log = []
+def trace(x):
+    log.append(x)
+    return x
+def pair(a, b=None):
+    return (a, b)
+def operands():
+    x = 1 + (yield 2)
+    y = trace("left") + (yield "right")
+    z = pair((yield "a"), b=(yield "b"))
+    log.append(f"{x} {y} {z} {[0, 1, 2, 3][(yield 'lo'):(yield 'hi')]}")
+    return (yield "last")
+g = operands()
+a = [next(g), g.send(10), g.send("!"), g.send(1), g.send(2), g.send(1), g.send(3)]
+try:
+    g.send("done")
+except StopIteration as e:
+    a.append(e.value)
+a = f"{a} {log}"
+def branches():
+    if trace(0) and (yield "skipped"):
+        log.append("skipped")
+    second = "then" if (yield "test") else (yield "else")
+    n = 0
+    while (yield n) < 2:
+        n = n + 1
+    else:
+        log.append(f"{second} {n}")
+    if (yield f"{(yield 'f')}!"):
+        log.append("if")
+    if trace(1) > 2 < (yield "never"):
+        log.append("never")
+    return 1 + (yield from operands())
+log = []
+g = branches()
+b = [next(g), g.send(0), g.send("other"), g.send(1), g.send(2), g.send("F"), g.send(True)]
+b = f"{b}"
+c = f"{log}"
+def chain():
+    return trace(1) < trace(2) < (yield "mid") < trace(9)
+log = []
+g = chain()
+d = [next(g)]
+try:
+    g.send(5)
+except StopIteration as e:
+    d.append(e.value)
+d = f"{d} {log}"
+"#,
+        );
+        assert_globals(
+            &mut nodes,
+            [
+                ("a", obj_str("[2, 'right', 'a', 'b', 'lo', 'hi', 'last', 'done'] ['left', '11 left! (1, 2) [1, 2]']".to_string())),
+                ("b", obj_str("['test', 'else', 0, 1, 'f', 'F!', 2]".to_string())),
+                ("c", obj_str("[0, 'other 1', 'if', 1]".to_string())),
+                ("d", obj_str("['mid', True] [1, 2, 9]".to_string())),
+            ],
+        );
+        assert_eq!(
+            raises("x = 1 + (yield 2)\n"),
+            ("SyntaxError".to_string(), "'yield' outside function".to_string())
+        );
+    }
+    #[test]
+    fn test_generator_reentry() {
+        println!("{}", "[INFO] Test generator reentry".yellow());
+        assert_eq!(
+            raises(r#"
+def gen():
+    yield next(g)
+g = gen()
+next(g)
+"#),
+            ("ValueError".to_string(), "generator already executing".to_string())
+        );
     }
 }
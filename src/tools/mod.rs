@@ -1,2 +1,3 @@
 pub mod repl;
 pub mod string;
+pub mod tokenize;
@@ -3,7 +3,9 @@ use std::io::Write;
 
 use crate::ast::ast_struct::{PyRootNode, Type};
 
-pub fn repl(version: String) {
+/// ## fn repl
+/// 交互式解释器，path是添加在sys.path中的目录
+pub fn repl(version: String, path: Vec<String>) {
     println!("{}", version);
     loop {
         print!(">>> ");
@@ -15,11 +17,12 @@ pub fn repl(version: String) {
             break;
         }
         let mut nodes = PyRootNode::default();
-        nodes.parser(source);
-        match nodes.exec() {
-            Type::Constant(x) => {
+        nodes.search_path(path.clone());
+        match nodes.parser(source).and_then(|_| nodes.exec()) {
+            Ok(Type::Constant(x)) => {
                 println!("{:#?}", x)
             }
+            Err(exc) => eprint!("{}", nodes.traceback(&exc)),
             _ => {}
         }
     }
@@ -0,0 +1,165 @@
+use std::fmt::{Display, Formatter};
+
+use crate::ast::data_type::str::str_repr;
+use crate::ast::error::lex_error::LexError;
+use crate::ast::scanner::TokenType::{
+    Break, Continue, Is, AND, CLASS, DEDENT, DEL, DEF, ELIF, ELSE, EOF, FALSE, FOR, IDENTIFIER, IF,
+    In, INDENT, LAMBDA, NEWLINE, NOT, NUMBER, OR, PRINT, RETURN, SELF, STRING, TRUE, WHILE, TRY,
+    EXCEPT, FINALLY, RAISE, AS, FROM, WITH, IMPORT, YIELD,
+};
+use crate::ast::scanner::{build_scanner, Token, TokenType};
+
+/// token类型的名字和编号，与CPython的token模块相同
+pub const TOKEN_TYPES: [(&str, i64); 11] = [
+    ("ENDMARKER", 0),
+    ("NAME", 1),
+    ("NUMBER", 2),
+    ("STRING", 3),
+    ("NEWLINE", 4),
+    ("INDENT", 5),
+    ("DEDENT", 6),
+    ("OP", 54),
+    ("COMMENT", 61),
+    ("NL", 62),
+    ("ENCODING", 63),
+];
+
+/// tokenize.TokenInfo的字段，也可以像tuple一样按下标访问
+pub const TOKEN_INFO_FIELDS: [&str; 5] = ["type", "string", "start", "end", "line"];
+
+/// token类型名字对应的编号
+pub fn token_type(name: &str) -> i64 {
+    TOKEN_TYPES.iter().find(|x| x.0 == name).map_or(-1, |x| x.1)
+}
+
+/// 编号对应的token类型名字，相当于tokenize.tok_name[x]
+pub fn token_type_name(value: i64) -> Option<&'static str> {
+    TOKEN_TYPES.iter().find(|x| x.1 == value).map(|x| x.0)
+}
+
+fn token_name(token_type: TokenType) -> &'static str {
+    match token_type {
+        NUMBER => "NUMBER",
+        STRING => "STRING",
+        NEWLINE => "NEWLINE",
+        INDENT => "INDENT",
+        DEDENT => "DEDENT",
+        EOF => "ENDMARKER",
+        IDENTIFIER | AND | CLASS | DEL | Break | Continue | ELSE | ELIF | FALSE | FOR | IF | OR
+        | RETURN | SELF | TRUE | WHILE | DEF | LAMBDA | NOT | PRINT | Is | In | TRY | EXCEPT
+        | FINALLY | RAISE | AS | FROM | WITH | IMPORT | YIELD
+        | TokenType::None => "NAME",
+        _ => "OP",
+    }
+}
+
+/// ## struct TokenInfo
+/// tokenize模块输出的token，行号从1开始
+/// - name：token的类型，如NAME、OP
+/// - string：token的源码
+#[derive(Debug, Clone)]
+pub struct TokenInfo {
+    pub name: &'static str,
+    pub string: String,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+}
+
+/// 与python -m tokenize的输出格式相同
+impl Display for TokenInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let range = format!("{},{}-{},{}:", self.start.0, self.start.1, self.end.0, self.end.1);
+        write!(f, "{:<20}{:<15}{:<15}", range, self.name, str_repr(&self.string))
+    }
+}
+
+/// scanner中的行号从0开始
+fn token_info(name: &'static str, start: (usize, usize), end: (usize, usize), string: &str) -> TokenInfo {
+    TokenInfo {
+        name,
+        string: string.to_string(),
+        start: (start.0 + 1, start.1),
+        end: (end.0 + 1, end.1),
+    }
+}
+
+/// 从trivia中取出COMMENT和NL，position是trivia开始的位置
+fn trivia_tokens(trivia: &str, mut position: (usize, usize)) -> Vec<TokenInfo> {
+    let mut lines = vec![];
+    let chars: Vec<char> = trivia.chars().collect();
+    let mut index = 0;
+    while index < chars.len() {
+        match chars[index] {
+            '#' => {
+                let comment: String = chars[index..]
+                    .iter()
+                    .take_while(|x| **x != '\r' && **x != '\n')
+                    .collect();
+                let length = comment.chars().count();
+                let end = (position.0, position.1 + length);
+                lines.push(token_info("COMMENT", position, end, comment.as_str()));
+                position = end;
+                index += length;
+            }
+            '\\' => {
+                // a line continuation is not a token
+                let length = if chars.get(index + 1) == Some(&'\r') { 3 } else { 2 };
+                position = (position.0 + 1, 0);
+                index += length;
+            }
+            '\n' | '\r' if chars[index] == '\n' || chars.get(index + 1) == Some(&'\n') => {
+                let newline = if chars[index] == '\r' { "\r\n" } else { "\n" };
+                let end = (position.0, position.1 + newline.len());
+                lines.push(token_info("NL", position, end, newline));
+                position = (position.0 + 1, 0);
+                index += newline.len();
+            }
+            _ => {
+                position.1 += 1;
+                index += 1;
+            }
+        }
+    }
+    lines
+}
+
+fn token_lines(token: &Token) -> TokenInfo {
+    let start = (token.lineno, token.col_offset);
+    let end = if token.token_type == NEWLINE {
+        // the NEWLINE at the end of a file without line break is an empty string
+        let length = token.lexeme.chars().count().max(1);
+        (token.lineno, token.col_offset + length)
+    } else {
+        (token.end_lineno, token.end_col_offset)
+    };
+    token_info(token_name(token.token_type), start, end, token.lexeme.as_str())
+}
+
+/// ## fn generate_tokens
+/// 扫描源码，得到与CPython tokenize.generate_tokens相同的token，包括注释和空行
+pub fn generate_tokens(source: String) -> Result<Vec<TokenInfo>, Vec<LexError>> {
+    let mut scanner = build_scanner(source).trivia(true);
+    let tokens = scanner.scan()?;
+    let mut infos = vec![];
+    let mut position = (0, 0);
+    for token in tokens.iter() {
+        infos.extend(trivia_tokens(token.trivia.as_str(), position));
+        infos.push(token_lines(token));
+        position = (token.end_lineno, token.end_col_offset);
+    }
+    Ok(infos)
+}
+
+/// ## fn tokenize
+/// 按照python -m tokenize的格式输出每个token，第一行是ENCODING
+pub fn tokenize(source: String) -> Result<Vec<String>, Vec<LexError>> {
+    let encoding = TokenInfo {
+        name: "ENCODING",
+        string: "utf-8".to_string(),
+        start: (0, 0),
+        end: (0, 0),
+    };
+    let mut lines = vec![encoding.to_string()];
+    lines.extend(generate_tokens(source)?.iter().map(ToString::to_string));
+    Ok(lines)
+}